@@ -140,6 +140,29 @@ mutation {
   }
 }
 
+//
+// Chapter seeking
+//
+
+// Chapter links on an episode's page point to a media fragment like `#t=65.5`.
+// Rather than navigate, seek the page's player to the chapter and start it.
+document.addEventListener('click', function (e) {
+  const link = e.target.closest('a.chapter-seek');
+  if (!link) {
+    return;
+  }
+
+  const match = /#t=([0-9.]+)/.exec(link.getAttribute('href'));
+  const audio = document.querySelector('audio');
+  if (!match || !audio) {
+    return;
+  }
+
+  e.preventDefault();
+  audio.currentTime = parseFloat(match[1]);
+  audio.play();
+});
+
 //
 // Private functions
 //
@@ -0,0 +1,14 @@
+ALTER TABLE episode
+    DROP COLUMN block,
+    DROP COLUMN duration_seconds,
+    DROP COLUMN episode_number,
+    DROP COLUMN episode_type,
+    DROP COLUMN image_url,
+    DROP COLUMN season;
+
+ALTER TABLE podcast
+    DROP COLUMN author,
+    DROP COLUMN block,
+    DROP COLUMN itunes_image_url,
+    DROP COLUMN owner_email,
+    DROP COLUMN owner_name;
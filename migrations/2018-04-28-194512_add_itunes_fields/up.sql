@@ -0,0 +1,27 @@
+ALTER TABLE podcast
+    ADD COLUMN author TEXT
+        CHECK (char_length(author) <= 500),
+    ADD COLUMN block BOOLEAN,
+    ADD COLUMN itunes_image_url TEXT
+        CHECK (char_length(itunes_image_url) <= 500),
+    ADD COLUMN owner_email TEXT
+        CHECK (char_length(owner_email) <= 500),
+    ADD COLUMN owner_name TEXT
+        CHECK (char_length(owner_name) <= 500);
+
+ALTER TABLE episode
+    ADD COLUMN block BOOLEAN,
+    ADD COLUMN duration_seconds BIGINT
+        CHECK (duration_seconds >= 0),
+    ADD COLUMN episode_number INT,
+    ADD COLUMN episode_type TEXT
+        CHECK (char_length(episode_type) <= 100),
+    ADD COLUMN image_url TEXT
+        CHECK (char_length(image_url) <= 500),
+    ADD COLUMN season INT;
+
+COMMENT ON COLUMN episode.duration_seconds
+    IS 'Normalized from `itunes:duration`, which may be either `HH:MM:SS` or plain seconds.';
+
+COMMENT ON COLUMN podcast.itunes_image_url
+    IS 'From `itunes:image`, kept apart from `image_url` because iTunes artwork has its own size requirements.';
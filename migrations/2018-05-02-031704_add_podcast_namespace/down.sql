@@ -0,0 +1,8 @@
+DROP TABLE IF EXISTS podcast_person;
+DROP TABLE IF EXISTS podcast_funding;
+DROP TABLE IF EXISTS episode_transcript;
+DROP TABLE IF EXISTS episode_chapters_url;
+
+ALTER TABLE podcast
+    DROP COLUMN guid,
+    DROP COLUMN locked;
@@ -0,0 +1,107 @@
+ALTER TABLE podcast
+    ADD COLUMN guid TEXT
+        CHECK (char_length(guid) <= 100),
+    ADD COLUMN locked BOOLEAN;
+
+COMMENT ON COLUMN podcast.guid
+    IS 'Globally unique identifier from `podcast:guid` (Podcasting 2.0 namespace).';
+COMMENT ON COLUMN podcast.locked
+    IS 'Whether the podcast has asked not to be imported elsewhere through `podcast:locked`.';
+
+--
+-- episode_chapters_url
+--
+
+CREATE TABLE episode_chapters_url (
+    id BIGSERIAL PRIMARY KEY,
+
+    episode_id BIGINT NOT NULL
+        REFERENCES episode (id) ON DELETE RESTRICT,
+    media_type TEXT
+        CHECK (char_length(media_type) <= 100),
+    url TEXT NOT NULL
+        CHECK (char_length(url) <= 500)
+);
+COMMENT ON TABLE episode_chapters_url
+    IS 'Links to chapter files for episodes from `podcast:chapters`.';
+
+CREATE UNIQUE INDEX episode_chapters_url_episode_id_url
+    ON episode_chapters_url (episode_id, url);
+
+--
+-- episode_transcript
+--
+
+CREATE TABLE episode_transcript (
+    id BIGSERIAL PRIMARY KEY,
+
+    episode_id BIGINT NOT NULL
+        REFERENCES episode (id) ON DELETE RESTRICT,
+    language TEXT
+        CHECK (char_length(language) <= 100),
+    media_type TEXT NOT NULL
+        CHECK (char_length(media_type) <= 100),
+    rel TEXT
+        CHECK (char_length(rel) <= 100),
+    url TEXT NOT NULL
+        CHECK (char_length(url) <= 500)
+);
+COMMENT ON TABLE episode_transcript
+    IS 'Links to episode transcripts from `podcast:transcript`.';
+
+CREATE UNIQUE INDEX episode_transcript_episode_id_url
+    ON episode_transcript (episode_id, url);
+
+--
+-- podcast_funding
+--
+
+CREATE TABLE podcast_funding (
+    id BIGSERIAL PRIMARY KEY,
+
+    podcast_id BIGINT NOT NULL
+        REFERENCES podcast (id) ON DELETE RESTRICT,
+    title TEXT
+        CHECK (char_length(title) <= 500),
+    url TEXT NOT NULL
+        CHECK (char_length(url) <= 500)
+);
+COMMENT ON TABLE podcast_funding
+    IS 'Links for supporting a podcast financially from `podcast:funding`.';
+
+CREATE UNIQUE INDEX podcast_funding_podcast_id_url
+    ON podcast_funding (podcast_id, url);
+
+--
+-- podcast_person
+--
+
+CREATE TABLE podcast_person (
+    id BIGSERIAL PRIMARY KEY,
+
+    -- Set for people who appear on a specific episode and null for those who
+    -- are associated with the podcast as a whole.
+    episode_id BIGINT
+        REFERENCES episode (id) ON DELETE RESTRICT,
+
+    -- Named this way because `group` is a reserved word.
+    group_name TEXT
+        CHECK (char_length(group_name) <= 100),
+    href TEXT
+        CHECK (char_length(href) <= 500),
+    image_url TEXT
+        CHECK (char_length(image_url) <= 500),
+    name TEXT NOT NULL
+        CHECK (char_length(name) <= 500),
+    podcast_id BIGINT NOT NULL
+        REFERENCES podcast (id) ON DELETE RESTRICT,
+    role TEXT
+        CHECK (char_length(role) <= 100)
+);
+COMMENT ON TABLE podcast_person
+    IS 'People associated with a podcast or one of its episodes from `podcast:person`.';
+
+CREATE INDEX podcast_person_podcast_id_episode_id
+    ON podcast_person (podcast_id, episode_id);
+CREATE INDEX podcast_person_episode_id
+    ON podcast_person (episode_id) WHERE episode_id IS NOT NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE podcast_feed_location
+    DROP COLUMN etag,
+    DROP COLUMN last_modified;
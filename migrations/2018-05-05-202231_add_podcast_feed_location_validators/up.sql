@@ -0,0 +1,10 @@
+ALTER TABLE podcast_feed_location
+    ADD COLUMN etag TEXT
+        CHECK (char_length(etag) <= 500),
+    ADD COLUMN last_modified TEXT
+        CHECK (char_length(last_modified) <= 100);
+
+COMMENT ON COLUMN podcast_feed_location.etag
+    IS 'Value of the last `ETag` header received, sent back as `If-None-Match`.';
+COMMENT ON COLUMN podcast_feed_location.last_modified
+    IS 'Value of the last `Last-Modified` header received, sent back as `If-Modified-Since`.';
@@ -0,0 +1,2 @@
+ALTER TABLE podcast_feed_location
+    DROP COLUMN superseded_at;
@@ -0,0 +1,5 @@
+ALTER TABLE podcast_feed_location
+    ADD COLUMN superseded_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN podcast_feed_location.superseded_at
+    IS 'Set when a podcast has moved (permanent redirect or `itunes:new-feed-url`) and this location should no longer be crawled.';
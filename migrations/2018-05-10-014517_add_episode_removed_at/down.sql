@@ -0,0 +1,2 @@
+ALTER TABLE episode
+    DROP COLUMN removed_at;
@@ -0,0 +1,5 @@
+ALTER TABLE episode
+    ADD COLUMN removed_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN episode.removed_at
+    IS 'Set when an episode disappears from its podcast''s feed and cleared if it comes back. Removed episodes are hidden from podcast listings, but not from an account''s history.';
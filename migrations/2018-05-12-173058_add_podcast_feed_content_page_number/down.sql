@@ -0,0 +1,2 @@
+ALTER TABLE podcast_feed_content
+    DROP COLUMN page_number;
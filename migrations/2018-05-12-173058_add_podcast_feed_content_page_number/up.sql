@@ -0,0 +1,6 @@
+ALTER TABLE podcast_feed_content
+    ADD COLUMN page_number INTEGER NOT NULL DEFAULT 1
+        CHECK (page_number > 0);
+
+COMMENT ON COLUMN podcast_feed_content.page_number
+    IS 'Position of the content in a paged feed (RFC 5005). The feed itself is page 1, and pages reached by following `next` links count up from there.';
@@ -0,0 +1,2 @@
+ALTER TABLE episode
+    DROP COLUMN published_at_fallback;
@@ -0,0 +1,6 @@
+ALTER TABLE episode
+    ADD COLUMN published_at_fallback TEXT
+        CHECK (char_length(published_at_fallback) <= 50);
+
+COMMENT ON COLUMN episode.published_at_fallback
+    IS 'Names the fallback that was needed to parse the episode''s publishing date out of its feed (like `rfc2822_repaired` or `no_timezone`). Null if the date was valid.';
@@ -0,0 +1,23 @@
+CREATE TABLE episode_revision (
+    id BIGSERIAL PRIMARY KEY,
+
+    created_at TIMESTAMPTZ NOT NULL
+        DEFAULT NOW(),
+    episode_id BIGINT NOT NULL
+        REFERENCES episode (id) ON DELETE RESTRICT,
+    field TEXT NOT NULL
+        CHECK (field IN ('description', 'media_url', 'published_at', 'title')),
+    new_value TEXT
+        CHECK (char_length(new_value) <= 20000),
+    old_value TEXT
+        CHECK (char_length(old_value) <= 20000)
+);
+COMMENT ON TABLE episode_revision
+    IS 'A change to one of an episode''s fields found when its podcast''s feed was updated. Used to answer questions about what a publisher changed and when.';
+COMMENT ON COLUMN episode_revision.new_value
+    IS 'The field''s value after the change. Null if the field was removed.';
+COMMENT ON COLUMN episode_revision.old_value
+    IS 'The field''s value before the change. Null if the field was previously missing.';
+
+CREATE INDEX episode_revision_episode_id_created_at
+    ON episode_revision (episode_id, created_at);
@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS podcast_feed_content_sha256_hash;
+
+DROP TABLE IF EXISTS content_blob;
+DROP TABLE IF EXISTS content_dictionary;
+
+-- Content that only existed as a blob is lost.
+DELETE FROM podcast_feed_content
+    WHERE content_gzip IS NULL;
+ALTER TABLE podcast_feed_content
+    ALTER COLUMN content_gzip SET NOT NULL;
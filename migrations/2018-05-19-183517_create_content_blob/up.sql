@@ -0,0 +1,60 @@
+--
+-- content_dictionary
+--
+
+CREATE TABLE content_dictionary (
+    id BIGSERIAL PRIMARY KEY,
+
+    content BYTEA NOT NULL
+        CHECK (length(content) <= 1000000),
+    created_at TIMESTAMPTZ NOT NULL
+        DEFAULT NOW(),
+    num_samples INTEGER NOT NULL
+);
+COMMENT ON TABLE content_dictionary
+    IS 'Zstandard dictionaries trained on stored feed content. Dictionaries are never modified after they''re created, and the most recent one is used to compress new blobs.';
+
+--
+-- content_blob
+--
+
+CREATE TABLE content_blob (
+    id BIGSERIAL PRIMARY KEY,
+
+    content_zstd BYTEA NOT NULL
+        CHECK (length(content_zstd) <= 52428800),
+    created_at TIMESTAMPTZ NOT NULL
+        DEFAULT NOW(),
+    dictionary_id BIGINT
+        REFERENCES content_dictionary (id) ON DELETE RESTRICT,
+    last_used_at TIMESTAMPTZ NOT NULL
+        DEFAULT NOW(),
+    sha256_hash TEXT NOT NULL
+        CHECK (char_length(sha256_hash) = 64)
+);
+COMMENT ON TABLE content_blob
+    IS 'Content-addressed storage for raw feed content. A blob is shared by every `podcast_feed_content` row with the same hash, regardless of podcast.';
+COMMENT ON COLUMN content_blob.dictionary_id
+    IS 'The dictionary that the content was compressed with, if any.';
+COMMENT ON COLUMN content_blob.last_used_at
+    IS 'The last time that the blob was stored or referenced by new content. Used to avoid racing the cleaner.';
+
+CREATE UNIQUE INDEX content_blob_sha256_hash
+    ON content_blob (sha256_hash);
+CREATE INDEX content_blob_last_used_at
+    ON content_blob (last_used_at);
+
+--
+-- podcast_feed_content
+--
+
+-- Content moves to `content_blob`. Existing rows keep their gzipped content
+-- until they're rewritten by `podcast_feed_content_migrator`.
+ALTER TABLE podcast_feed_content
+    ALTER COLUMN content_gzip DROP NOT NULL;
+
+COMMENT ON COLUMN podcast_feed_content.content_gzip
+    IS 'Legacy gzipped content. Null once the content has been moved to `content_blob`.';
+
+CREATE INDEX podcast_feed_content_sha256_hash
+    ON podcast_feed_content (sha256_hash);
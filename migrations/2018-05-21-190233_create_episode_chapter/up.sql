@@ -0,0 +1,25 @@
+CREATE TABLE episode_chapter (
+    id BIGSERIAL PRIMARY KEY,
+
+    episode_id BIGINT NOT NULL
+        REFERENCES episode (id) ON DELETE RESTRICT,
+    href TEXT
+        CHECK (char_length(href) <= 500),
+    image_url TEXT
+        CHECK (char_length(image_url) <= 500),
+    source TEXT NOT NULL
+        CHECK (source IN ('json', 'psc')),
+    start_seconds DOUBLE PRECISION NOT NULL
+        CHECK (start_seconds >= 0),
+    title TEXT NOT NULL
+        CHECK (char_length(title) <= 500)
+);
+COMMENT ON TABLE episode_chapter
+    IS 'Chapters within an episode, either from `psc:chapters` in its feed or from a JSON chapters file linked through `podcast:chapters`.';
+COMMENT ON COLUMN episode_chapter.source
+    IS 'Where the chapter came from. `psc` chapters are replaced whenever the feed is updated, and `json` chapters whenever the chapters file is fetched.';
+COMMENT ON COLUMN episode_chapter.start_seconds
+    IS 'The offset into the episode''s media that the chapter starts at.';
+
+CREATE INDEX episode_chapter_episode_id_start_seconds
+    ON episode_chapter (episode_id, start_seconds);
@@ -0,0 +1,164 @@
+--
+-- category
+--
+
+CREATE TABLE category (
+    id BIGSERIAL PRIMARY KEY,
+
+    name TEXT NOT NULL
+        CHECK (char_length(name) <= 100),
+    parent_id BIGINT
+        REFERENCES category (id) ON DELETE RESTRICT,
+    slug TEXT NOT NULL UNIQUE
+        CHECK (char_length(slug) <= 100)
+);
+COMMENT ON TABLE category
+    IS 'Podcast categories, normalized to Apple''s category tree.';
+COMMENT ON COLUMN category.parent_id
+    IS 'The top-level category that this one is a subcategory of. Apple''s tree is only ever two levels deep.';
+COMMENT ON COLUMN category.slug
+    IS 'A URL-safe name for the category (see `links::slug_category`). Also used to match names found in feeds.';
+
+CREATE INDEX category_parent_id
+    ON category (parent_id);
+
+INSERT INTO category (name, slug)
+    VALUES ('Arts', 'arts'),
+        ('Business', 'business'),
+        ('Comedy', 'comedy'),
+        ('Education', 'education'),
+        ('Fiction', 'fiction'),
+        ('Government', 'government'),
+        ('Health & Fitness', 'health-and-fitness'),
+        ('History', 'history'),
+        ('Kids & Family', 'kids-and-family'),
+        ('Leisure', 'leisure'),
+        ('Music', 'music'),
+        ('News', 'news'),
+        ('Religion & Spirituality', 'religion-and-spirituality'),
+        ('Science', 'science'),
+        ('Society & Culture', 'society-and-culture'),
+        ('Sports', 'sports'),
+        ('Technology', 'technology'),
+        ('True Crime', 'true-crime'),
+        ('TV & Film', 'tv-and-film')
+    ON CONFLICT (slug) DO UPDATE SET name = EXCLUDED.name;
+
+INSERT INTO category (name, parent_id, slug)
+    SELECT sub.name, parent.id, sub.slug
+    FROM (VALUES
+        ('arts', 'Books', 'books'),
+        ('arts', 'Design', 'design'),
+        ('arts', 'Fashion & Beauty', 'fashion-and-beauty'),
+        ('arts', 'Food', 'food'),
+        ('arts', 'Performing Arts', 'performing-arts'),
+        ('arts', 'Visual Arts', 'visual-arts'),
+        ('business', 'Careers', 'careers'),
+        ('business', 'Entrepreneurship', 'entrepreneurship'),
+        ('business', 'Investing', 'investing'),
+        ('business', 'Management', 'management'),
+        ('business', 'Marketing', 'marketing'),
+        ('business', 'Non-Profit', 'non-profit'),
+        ('comedy', 'Comedy Interviews', 'comedy-interviews'),
+        ('comedy', 'Improv', 'improv'),
+        ('comedy', 'Stand-Up', 'stand-up'),
+        ('education', 'Courses', 'courses'),
+        ('education', 'How To', 'how-to'),
+        ('education', 'Language Learning', 'language-learning'),
+        ('education', 'Self-Improvement', 'self-improvement'),
+        ('fiction', 'Comedy Fiction', 'comedy-fiction'),
+        ('fiction', 'Drama', 'drama'),
+        ('fiction', 'Science Fiction', 'science-fiction'),
+        ('health-and-fitness', 'Alternative Health', 'alternative-health'),
+        ('health-and-fitness', 'Fitness', 'fitness'),
+        ('health-and-fitness', 'Medicine', 'medicine'),
+        ('health-and-fitness', 'Mental Health', 'mental-health'),
+        ('health-and-fitness', 'Nutrition', 'nutrition'),
+        ('health-and-fitness', 'Sexuality', 'sexuality'),
+        ('kids-and-family', 'Education for Kids', 'education-for-kids'),
+        ('kids-and-family', 'Parenting', 'parenting'),
+        ('kids-and-family', 'Pets & Animals', 'pets-and-animals'),
+        ('kids-and-family', 'Stories for Kids', 'stories-for-kids'),
+        ('leisure', 'Animation & Manga', 'animation-and-manga'),
+        ('leisure', 'Automotive', 'automotive'),
+        ('leisure', 'Aviation', 'aviation'),
+        ('leisure', 'Crafts', 'crafts'),
+        ('leisure', 'Games', 'games'),
+        ('leisure', 'Hobbies', 'hobbies'),
+        ('leisure', 'Home & Garden', 'home-and-garden'),
+        ('leisure', 'Video Games', 'video-games'),
+        ('music', 'Music Commentary', 'music-commentary'),
+        ('music', 'Music History', 'music-history'),
+        ('music', 'Music Interviews', 'music-interviews'),
+        ('news', 'Business News', 'business-news'),
+        ('news', 'Daily News', 'daily-news'),
+        ('news', 'Entertainment News', 'entertainment-news'),
+        ('news', 'News Commentary', 'news-commentary'),
+        ('news', 'Politics', 'politics'),
+        ('news', 'Sports News', 'sports-news'),
+        ('news', 'Tech News', 'tech-news'),
+        ('religion-and-spirituality', 'Buddhism', 'buddhism'),
+        ('religion-and-spirituality', 'Christianity', 'christianity'),
+        ('religion-and-spirituality', 'Hinduism', 'hinduism'),
+        ('religion-and-spirituality', 'Islam', 'islam'),
+        ('religion-and-spirituality', 'Judaism', 'judaism'),
+        ('religion-and-spirituality', 'Religion', 'religion'),
+        ('religion-and-spirituality', 'Spirituality', 'spirituality'),
+        ('science', 'Astronomy', 'astronomy'),
+        ('science', 'Chemistry', 'chemistry'),
+        ('science', 'Earth Sciences', 'earth-sciences'),
+        ('science', 'Life Sciences', 'life-sciences'),
+        ('science', 'Mathematics', 'mathematics'),
+        ('science', 'Natural Sciences', 'natural-sciences'),
+        ('science', 'Nature', 'nature'),
+        ('science', 'Physics', 'physics'),
+        ('science', 'Social Sciences', 'social-sciences'),
+        ('society-and-culture', 'Documentary', 'documentary'),
+        ('society-and-culture', 'Personal Journals', 'personal-journals'),
+        ('society-and-culture', 'Philosophy', 'philosophy'),
+        ('society-and-culture', 'Places & Travel', 'places-and-travel'),
+        ('society-and-culture', 'Relationships', 'relationships'),
+        ('sports', 'Baseball', 'baseball'),
+        ('sports', 'Basketball', 'basketball'),
+        ('sports', 'Cricket', 'cricket'),
+        ('sports', 'Fantasy Sports', 'fantasy-sports'),
+        ('sports', 'Football', 'football'),
+        ('sports', 'Golf', 'golf'),
+        ('sports', 'Hockey', 'hockey'),
+        ('sports', 'Rugby', 'rugby'),
+        ('sports', 'Running', 'running'),
+        ('sports', 'Soccer', 'soccer'),
+        ('sports', 'Swimming', 'swimming'),
+        ('sports', 'Tennis', 'tennis'),
+        ('sports', 'Volleyball', 'volleyball'),
+        ('sports', 'Wilderness', 'wilderness'),
+        ('sports', 'Wrestling', 'wrestling'),
+        ('tv-and-film', 'After Shows', 'after-shows'),
+        ('tv-and-film', 'Film History', 'film-history'),
+        ('tv-and-film', 'Film Interviews', 'film-interviews'),
+        ('tv-and-film', 'Film Reviews', 'film-reviews'),
+        ('tv-and-film', 'TV Reviews', 'tv-reviews')
+    ) AS sub (parent_slug, name, slug)
+    INNER JOIN category parent
+        ON parent.slug = sub.parent_slug
+    ON CONFLICT (slug) DO UPDATE SET name = EXCLUDED.name, parent_id = EXCLUDED.parent_id;
+
+--
+-- podcast_category
+--
+
+CREATE TABLE podcast_category (
+    id BIGSERIAL PRIMARY KEY,
+
+    category_id BIGINT NOT NULL
+        REFERENCES category (id) ON DELETE RESTRICT,
+    podcast_id BIGINT NOT NULL
+        REFERENCES podcast (id) ON DELETE RESTRICT
+);
+COMMENT ON TABLE podcast_category
+    IS 'Categories that a podcast has placed itself in through `itunes:category`.';
+
+CREATE INDEX podcast_category_category_id
+    ON podcast_category (category_id);
+CREATE UNIQUE INDEX podcast_category_podcast_id_category_id
+    ON podcast_category (podcast_id, category_id);
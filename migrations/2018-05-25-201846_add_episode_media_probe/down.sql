@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS episode_media_gone_at;
+
+ALTER TABLE episode
+    DROP COLUMN media_content_length,
+    DROP COLUMN media_content_type,
+    DROP COLUMN media_gone_at,
+    DROP COLUMN media_probed_at,
+    DROP COLUMN media_probed_url,
+    DROP COLUMN media_status;
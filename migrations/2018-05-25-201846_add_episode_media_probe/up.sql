@@ -0,0 +1,26 @@
+ALTER TABLE episode
+    ADD COLUMN media_content_length BIGINT
+        CHECK (media_content_length >= 0),
+    ADD COLUMN media_content_type TEXT
+        CHECK (char_length(media_content_type) <= 100),
+    ADD COLUMN media_gone_at TIMESTAMPTZ,
+    ADD COLUMN media_probed_at TIMESTAMPTZ,
+    ADD COLUMN media_probed_url TEXT
+        CHECK (char_length(media_probed_url) <= 500),
+    ADD COLUMN media_status INTEGER;
+
+COMMENT ON COLUMN episode.media_content_length
+    IS 'The size in bytes of the episode''s media as reported by its host the last time that it was probed.';
+COMMENT ON COLUMN episode.media_content_type
+    IS 'The content type of the episode''s media as reported by its host (which may differ from what the feed claims).';
+COMMENT ON COLUMN episode.media_gone_at
+    IS 'Set when a probe finds that the episode''s media no longer exists (a 404 or 410) and cleared by a later successful probe.';
+COMMENT ON COLUMN episode.media_probed_at
+    IS 'When the episode''s media was last probed.';
+COMMENT ON COLUMN episode.media_probed_url
+    IS 'The media URL that was last probed. When it differs from `media_url` the probe results are stale and the media should be probed again.';
+COMMENT ON COLUMN episode.media_status
+    IS 'The HTTP status of the last probe of the episode''s media, or null if the request couldn''t be made at all.';
+
+CREATE INDEX episode_media_gone_at
+    ON episode (media_gone_at);
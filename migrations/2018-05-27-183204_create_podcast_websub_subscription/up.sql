@@ -0,0 +1,30 @@
+CREATE TABLE podcast_websub_subscription (
+    id BIGSERIAL PRIMARY KEY,
+
+    expires_at TIMESTAMPTZ,
+    hub_url TEXT NOT NULL
+        CHECK (char_length(hub_url) <= 500),
+    podcast_id BIGINT NOT NULL UNIQUE
+        REFERENCES podcast (id) ON DELETE RESTRICT,
+    requested_at TIMESTAMPTZ NOT NULL,
+    secret TEXT NOT NULL
+        CHECK (char_length(secret) <= 100),
+    topic_url TEXT NOT NULL
+        CHECK (char_length(topic_url) <= 500),
+    verified_at TIMESTAMPTZ
+);
+COMMENT ON TABLE podcast_websub_subscription
+    IS 'A subscription to a WebSub hub advertised by a podcast''s feed, through which the hub pushes us the feed whenever it changes.';
+COMMENT ON COLUMN podcast_websub_subscription.expires_at
+    IS 'When the hub will stop pushing content unless the subscription is renewed. Set from the lease that the hub grants when it verifies the subscription.';
+COMMENT ON COLUMN podcast_websub_subscription.requested_at
+    IS 'When we last asked the hub to subscribe us (or renew the subscription).';
+COMMENT ON COLUMN podcast_websub_subscription.secret
+    IS 'A secret shared with the hub that it uses to sign the content that it pushes to us.';
+COMMENT ON COLUMN podcast_websub_subscription.topic_url
+    IS 'The URL of the feed that we subscribed to, as given by its `rel="self"` link.';
+COMMENT ON COLUMN podcast_websub_subscription.verified_at
+    IS 'When the hub last verified our intent to subscribe. The hub won''t push content until this happens.';
+
+CREATE INDEX podcast_websub_subscription_expires_at
+    ON podcast_websub_subscription (expires_at);
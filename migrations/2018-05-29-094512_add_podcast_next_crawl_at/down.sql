@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS podcast_next_crawl_at;
+
+ALTER TABLE podcast
+    DROP COLUMN next_crawl_at;
+
+ALTER TABLE podcast_exception
+    DROP COLUMN num_occurrences;
@@ -0,0 +1,15 @@
+ALTER TABLE podcast
+    ADD COLUMN next_crawl_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+COMMENT ON COLUMN podcast.next_crawl_at
+    IS 'When the crawler should next retrieve the podcast''s feed. Recalculated after every update from the podcast''s publishing history.';
+
+CREATE INDEX podcast_next_crawl_at
+    ON podcast (next_crawl_at);
+
+ALTER TABLE podcast_exception
+    ADD COLUMN num_occurrences INTEGER NOT NULL DEFAULT 1
+        CHECK (num_occurrences > 0);
+
+COMMENT ON COLUMN podcast_exception.num_occurrences
+    IS 'Number of consecutive times that updating the podcast has failed. The exception is removed on the next successful update.';
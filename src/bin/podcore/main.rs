@@ -12,6 +12,7 @@ extern crate podcore;
 extern crate r2d2;
 extern crate r2d2_diesel;
 extern crate rand;
+extern crate serde_json;
 #[macro_use]
 extern crate slog;
 extern crate slog_async;
@@ -21,14 +22,19 @@ extern crate tokio_core;
 use podcore::api;
 use podcore::error_helpers;
 use podcore::errors::*;
-use podcore::http_requester::{HttpRequesterFactoryLive, HttpRequesterLive};
+use podcore::http_requester::{HttpRequesterFactoryLive, HttpRequesterLive, MAX_BODY_SIZE};
 use podcore::mediators::cleaner;
 use podcore::mediators::directory_podcast_searcher;
+use podcore::mediators::episode_revision_lister;
+use podcore::mediators::feed_validator;
 use podcore::mediators::job_worker;
 use podcore::mediators::podcast_crawler;
+use podcore::mediators::podcast_feed_content_migrator;
 use podcore::mediators::podcast_feed_location_upgrader;
+use podcore::mediators::podcast_merger;
 use podcore::mediators::podcast_reingester;
 use podcore::mediators::podcast_updater;
+use podcore::mediators::podcast_updater::FeedReport;
 use podcore::web;
 
 use clap::{App, ArgMatches, SubCommand};
@@ -70,19 +76,28 @@ fn main() {
             "    --pool-timeout=[SECONDS] 'Timeout for getting a database connection from pool",
         )
         .arg_from_usage("    --log-async 'Log asynchronously (good for logging on servers)'")
+        .arg_from_usage(
+            "    --max-body-size=[BYTES] 'Maximum size of fetched HTTP bodies (no more than the \
+             default)'",
+        )
         .arg_from_usage("-c, --num-connections=[NUM] 'Number of Postgres connections'")
         .arg_from_usage("-q, --quiet 'Quiets all output'")
         .subcommand(
             SubCommand::with_name("add")
                 .about("Fetches a podcast and adds it to the database")
                 .arg_from_usage("--force 'Force the podcast to be readded even if it exists'")
-                .arg_from_usage("<URL>... 'URL(s) to fetch'"),
+                .arg_from_usage("<URL>... 'URL(s) of feeds or podcast web pages to fetch'"),
         )
         .subcommand(
             SubCommand::with_name("api")
                 .about("Starts the API server")
                 .arg_from_usage("-p, --port=[PORT] 'Port to bind server to'"),
         )
+        .subcommand(
+            SubCommand::with_name("changelog")
+                .about("Prints the changes that have been made to a podcast's episodes")
+                .arg_from_usage("<PODCAST_ID> 'ID of the podcast'"),
+        )
         .subcommand(
             SubCommand::with_name("clean")
                 .about("Cleans the database (should be run periodically)")
@@ -91,13 +106,26 @@ fn main() {
         .subcommand(
             SubCommand::with_name("crawl")
                 .about("Crawls the web to retrieve podcasts that need to be updated")
+                .arg_from_usage("--host-burst=[NUM] 'Requests a host gets in a burst'")
+                .arg_from_usage("--host-max-in-flight=[NUM] 'Concurrent requests to a host'")
+                .arg_from_usage("--host-rps=[NUM] 'Requests per second to a host after a burst'")
                 .arg_from_usage("--run-once 'Run only one time instead of looping'"),
         )
         .subcommand(
             SubCommand::with_name("error")
                 .about("Triggers an error (for testing error output and Sentry)"),
         )
+        .subcommand(
+            SubCommand::with_name("merge-podcasts")
+                .about("Merges podcasts that are the same feed under different URLs")
+                .arg_from_usage("--dry-run 'Print duplicates without merging them'"),
+        )
         .subcommand(SubCommand::with_name("migrate").about("Migrates the database"))
+        .subcommand(
+            SubCommand::with_name("migrate-content")
+                .about("Moves stored feed content into the blob store")
+                .arg_from_usage("--train-dictionary 'Train a new compression dictionary first'"),
+        )
         .subcommand(
             SubCommand::with_name("reingest")
                 .about("Reingests podcasts by reusing their stored raw feeds"),
@@ -114,7 +142,13 @@ fn main() {
         )
         .subcommand(
             SubCommand::with_name("upgrade-https")
-                .about("Upgrades podcast locations to HTTPS for hosts known to support it"),
+                .about("Upgrades podcast locations to HTTPS for hosts that support it"),
+        )
+        .subcommand(
+            SubCommand::with_name("validate-feed")
+                .about("Checks a feed for problems without adding it to the database")
+                .arg_from_usage("--json 'Print the report as JSON'")
+                .arg_from_usage("<SOURCE> 'URL or path of the feed to validate'"),
         )
         .subcommand(
             SubCommand::with_name("web")
@@ -131,17 +165,25 @@ fn main() {
     let options = parse_global_options(&matches);
     let log = log(&options);
 
+    if let Err(ref e) = validate_global_options(&options) {
+        handle_error(&log, e);
+    }
+
     let res = match matches.subcommand_name() {
         Some("add") => subcommand_add(&log, &matches, &options),
         Some("api") => subcommand_api(&log, &matches, &options),
+        Some("changelog") => subcommand_changelog(&log, &matches, &options),
         Some("clean") => subcommand_clean(&log, &matches, &options),
         Some("crawl") => subcommand_crawl(&log, &matches, &options),
         Some("error") => subcommand_error(&log, &matches, &options),
+        Some("merge-podcasts") => subcommand_merge_podcasts(&log, &matches, &options),
         Some("migrate") => subcommand_migrate(&log, &matches, &options),
+        Some("migrate-content") => subcommand_migrate_content(&log, &matches, &options),
         Some("reingest") => subcommand_reingest(&log, &matches, &options),
         Some("search") => subcommand_search(&log, &matches, &options),
         Some("sleep") => subcommand_sleep(&log, &matches, &options),
         Some("upgrade-https") => subcommand_upgrade_https(&log, &matches, &options),
+        Some("validate-feed") => subcommand_validate_feed(&log, &matches, &options),
         Some("web") => subcommand_web(&log, &matches, &options),
         Some("work") => subcommand_work(&log, &matches, &options),
         None => {
@@ -190,19 +232,73 @@ fn subcommand_add(log: &Logger, matches: &ArgMatches, options: &GlobalOptions) -
     let client = Client::configure()
         .connector(HttpsConnector::new(4, &core.handle()).map_err(Error::from)?)
         .build(&core.handle());
-    let mut http_requester = HttpRequesterLive { client, core };
+    let mut http_requester = HttpRequesterLive {
+        client,
+        core,
+        max_body_size: options.max_body_size,
+    };
 
     for url in matches.values_of("URL").unwrap().collect::<Vec<_>>() {
-        podcast_updater::Mediator {
+        let res = podcast_updater::Mediator {
             conn:             &*conn,
             disable_shortcut: force,
+            enqueue_jobs:     true,
             feed_url:         url.to_owned().to_owned(),
+            follow_pages:     force,
             http_requester:   &mut http_requester,
         }.run(log)?;
+
+        // Let the user know if the URL they gave us was a web page so that they can
+        // use the feed's URL directly next time.
+        if let Some(discovered_url) = res.discovered_url {
+            info!(log, "Discovered feed in web page";
+                "url" => url, "feed_url" => discovered_url.as_str());
+        }
     }
     Ok(())
 }
 
+fn subcommand_changelog(log: &Logger, matches: &ArgMatches, options: &GlobalOptions) -> Result<()> {
+    let matches = matches.subcommand_matches("changelog").unwrap();
+    let pool = pool(log, options)?;
+    let conn = pool.get()?;
+
+    let podcast_id = matches
+        .value_of("PODCAST_ID")
+        .unwrap()
+        .parse::<i64>()
+        .chain_err(|| "Error parsing podcast ID")?;
+
+    let res = episode_revision_lister::Mediator {
+        conn: &*conn,
+        podcast_id,
+    }.run(log)?;
+
+    println!("Podcast: {} (ID: {})", res.podcast.title, res.podcast.id);
+    if res.revisions.is_empty() {
+        println!("No changes recorded");
+    }
+    for &(ref revision, ref episode) in &res.revisions {
+        println!(
+            "{} Episode {} (GUID: {}): {} changed",
+            revision.created_at.to_rfc3339(),
+            episode.id,
+            episode.guid,
+            revision.field
+        );
+        println!(
+            "  Old: {}",
+            revision.old_value.as_ref().map(String::as_str).unwrap_or("(none)")
+        );
+        println!(
+            "  New: {}",
+            revision.new_value.as_ref().map(String::as_str).unwrap_or("(none)")
+        );
+    }
+
+    Ok(())
+}
+
 fn subcommand_clean(log: &Logger, matches: &ArgMatches, options: &GlobalOptions) -> Result<()> {
     let matches = matches.subcommand_matches("clean").unwrap();
     let mut num_loops = 0;
@@ -217,6 +313,7 @@ fn subcommand_clean(log: &Logger, matches: &ArgMatches, options: &GlobalOptions)
         info!(log, "Finished work loop";
             "num_loops" => num_loops,
             "num_account_cleaned" => res.num_account_cleaned,
+            "num_content_blob_cleaned" => res.num_content_blob_cleaned,
             "num_directory_podcast_cleaned" => res.num_directory_podcast_cleaned,
             "num_directory_search_cleaned" => res.num_directory_search_cleaned,
             "num_key_cleaned" => res.num_key_cleaned,
@@ -238,15 +335,48 @@ fn subcommand_crawl(log: &Logger, matches: &ArgMatches, options: &GlobalOptions)
     let mut num_loops = 0;
     let run_once = matches.is_present("run-once");
 
+    let default_host_limits = podcast_crawler::HostLimits::default();
+    let host_limits = podcast_crawler::HostLimits {
+        burst: matches
+            .value_of("host-burst")
+            .map(|s| s.parse::<u32>().unwrap())
+            .unwrap_or_else(|| {
+                env::var("HOST_BURST")
+                    .map(|s| s.parse::<u32>().unwrap())
+                    .unwrap_or(default_host_limits.burst)
+            }),
+        max_in_flight: matches
+            .value_of("host-max-in-flight")
+            .map(|s| s.parse::<u32>().unwrap())
+            .unwrap_or_else(|| {
+                env::var("HOST_MAX_IN_FLIGHT")
+                    .map(|s| s.parse::<u32>().unwrap())
+                    .unwrap_or(default_host_limits.max_in_flight)
+            }),
+        requests_per_second: matches
+            .value_of("host-rps")
+            .map(|s| s.parse::<f64>().unwrap())
+            .unwrap_or_else(|| {
+                env::var("HOST_RPS")
+                    .map(|s| s.parse::<f64>().unwrap())
+                    .unwrap_or(default_host_limits.requests_per_second)
+            }),
+    };
+
     loop {
         let res = podcast_crawler::Mediator {
             num_workers:            options.num_connections - 1,
+            host_limits:            host_limits.clone(),
             pool:                   pool(log, options)?.clone(),
-            http_requester_factory: Box::new(HttpRequesterFactoryLive {}),
+            http_requester_factory: Box::new(HttpRequesterFactoryLive {
+                max_body_size: options.max_body_size,
+            }),
         }.run(log)?;
 
         num_loops += 1;
-        info!(log, "Finished work loop"; "num_loops" => num_loops, "num_podcasts" => res.num_podcasts);
+        info!(log, "Finished work loop";
+            "num_loops" => num_loops, "num_deferred" => res.num_deferred,
+            "num_podcasts" => res.num_podcasts, "num_throttled" => res.num_throttled);
 
         if run_once {
             break (Ok(()));
@@ -269,6 +399,29 @@ fn subcommand_error(_log: &Logger, matches: &ArgMatches, _options: &GlobalOption
         .chain_err(|| "Chained context 2"))
 }
 
+fn subcommand_merge_podcasts(
+    log: &Logger,
+    matches: &ArgMatches,
+    options: &GlobalOptions,
+) -> Result<()> {
+    let matches = matches.subcommand_matches("merge-podcasts").unwrap();
+    let pool = pool(log, options)?;
+    let conn = pool.get()?;
+
+    let dry_run = matches.is_present("dry-run");
+    let res = podcast_merger::Mediator {
+        conn: &*conn,
+        dry_run,
+    }.run(log)?;
+
+    for merge in &res.merges {
+        info!(log, "Duplicate podcast";
+            "duplicate_id" => merge.duplicate_id, "survivor_id" => merge.survivor_id);
+    }
+    info!(log, "Finished podcast merge"; "dry_run" => dry_run, "num_merged" => res.merges.len());
+    Ok(())
+}
+
 fn subcommand_migrate(log: &Logger, matches: &ArgMatches, options: &GlobalOptions) -> Result<()> {
     let _matches = matches.subcommand_matches("migrate").unwrap();
     let pool = pool(log, options)?;
@@ -286,6 +439,27 @@ fn subcommand_migrate(log: &Logger, matches: &ArgMatches, options: &GlobalOption
     Ok(())
 }
 
+fn subcommand_migrate_content(
+    log: &Logger,
+    matches: &ArgMatches,
+    options: &GlobalOptions,
+) -> Result<()> {
+    let matches = matches.subcommand_matches("migrate-content").unwrap();
+    let pool = pool(log, options)?;
+    let conn = pool.get()?;
+
+    let res = podcast_feed_content_migrator::Mediator {
+        conn:             &*conn,
+        train_dictionary: matches.is_present("train-dictionary"),
+    }.run(log)?;
+
+    info!(log, "Finished content migration";
+        "dictionary_id" => res.dictionary.map(|d| d.id),
+        "num_migrated" => res.num_migrated,
+        "num_recompressed" => res.num_recompressed);
+    Ok(())
+}
+
 fn subcommand_reingest(log: &Logger, matches: &ArgMatches, options: &GlobalOptions) -> Result<()> {
     let _matches = matches.subcommand_matches("reingest").unwrap();
 
@@ -305,7 +479,11 @@ fn subcommand_search(log: &Logger, matches: &ArgMatches, options: &GlobalOptions
     let client = Client::configure()
         .connector(HttpsConnector::new(4, &core.handle()).map_err(Error::from)?)
         .build(&core.handle());
-    let mut http_requester = HttpRequesterLive { client, core };
+    let mut http_requester = HttpRequesterLive {
+        client,
+        core,
+        max_body_size: options.max_body_size,
+    };
 
     let query = matches.value_of("QUERY").unwrap();
     directory_podcast_searcher::Mediator {
@@ -341,9 +519,53 @@ fn subcommand_upgrade_https(
     let pool = pool(log, options)?;
     let conn = pool.get()?;
 
-    let res = podcast_feed_location_upgrader::Mediator { conn: &*conn }.run(log)?;
+    let res = podcast_feed_location_upgrader::Mediator {
+        conn:                   &*conn,
+        http_requester_factory: Box::new(HttpRequesterFactoryLive {
+            max_body_size: options.max_body_size,
+        }),
+    }.run(log)?;
 
-    info!(log, "Finished podcast HTTPS upgrade"; "num_upgraded" => res.num_upgraded);
+    info!(log, "Finished podcast HTTPS upgrade";
+        "num_probed" => res.num_probed, "num_upgraded" => res.num_upgraded);
+    Ok(())
+}
+
+fn subcommand_validate_feed(
+    log: &Logger,
+    matches: &ArgMatches,
+    options: &GlobalOptions,
+) -> Result<()> {
+    let matches = matches.subcommand_matches("validate-feed").unwrap();
+
+    let core = Core::new().unwrap();
+    let client = Client::configure()
+        .connector(HttpsConnector::new(4, &core.handle()).map_err(Error::from)?)
+        .build(&core.handle());
+    let mut http_requester = HttpRequesterLive {
+        client,
+        core,
+        max_body_size: options.max_body_size,
+    };
+
+    let res = feed_validator::Mediator {
+        http_requester: &mut http_requester,
+        source:         matches.value_of("SOURCE").unwrap().to_owned(),
+    }.run(log)?;
+
+    if matches.is_present("json") {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&res.report).chain_err(|| "Error serializing report")?
+        );
+    } else {
+        print_feed_report(&res.report);
+    }
+
+    // Exit non-zero for an invalid feed so that the command is easy to script.
+    if !res.report.is_valid() {
+        ::std::process::exit(1);
+    }
     Ok(())
 }
 
@@ -408,11 +630,21 @@ fn subcommand_work(log: &Logger, matches: &ArgMatches, options: &GlobalOptions)
         .map(|s| s.parse::<u32>().unwrap())
         .unwrap_or_else(|_| options.num_connections * 5);
 
+    // Where the web server can be reached from the outside world. WebSub hubs are
+    // given callback URLs under it, so subscribing to them fails without it.
+    let public_url = env::var("PUBLIC_URL").ok();
+    if public_url.is_none() {
+        warn!(log, "PUBLIC_URL not set -- WebSub subscription jobs will fail");
+    }
+
     loop {
         let _res = job_worker::Mediator {
             num_workers,
             pool: pool(log, options)?.clone(),
-            http_requester_factory: Box::new(HttpRequesterFactoryLive {}),
+            http_requester_factory: Box::new(HttpRequesterFactoryLive {
+                max_body_size: options.max_body_size,
+            }),
+            public_url: public_url.clone(),
             run_once,
         }.run(log)?;
     }
@@ -459,6 +691,7 @@ const SLEEP_SECONDS: u64 = 60;
 
 struct GlobalOptions {
     log_async:       bool,
+    max_body_size:   usize,
     num_connections: u32,
     pool_timeout:    Duration,
     quiet:           bool,
@@ -500,6 +733,15 @@ fn parse_global_options(matches: &ArgMatches) -> GlobalOptions {
             !stdout_isatty()
         },
 
+        max_body_size: matches
+            .value_of("max-body-size")
+            .map(|s| s.parse::<usize>().unwrap())
+            .unwrap_or_else(|| {
+                env::var("MAX_BODY_SIZE")
+                    .map(|s| s.parse::<usize>().unwrap())
+                    .unwrap_or(MAX_BODY_SIZE)
+            }),
+
         num_connections: matches
             .value_of("num-connections")
             .map(|s| s.parse::<u32>().unwrap())
@@ -524,6 +766,64 @@ fn parse_global_options(matches: &ArgMatches) -> GlobalOptions {
     }
 }
 
+// Checks options that can't be enforced by clap alone.
+fn validate_global_options(options: &GlobalOptions) -> Result<()> {
+    // Fetched feeds are stored, and the blob store can't hold anything bigger
+    // than the default because its size is fixed by a database constraint.
+    if options.max_body_size > MAX_BODY_SIZE {
+        bail!(
+            "Maximum body size of {} bytes is larger than the largest allowed ({} bytes)",
+            options.max_body_size,
+            MAX_BODY_SIZE
+        );
+    }
+    Ok(())
+}
+
+/// Prints a feed validation report in a format that's suitable for humans.
+fn print_feed_report(report: &FeedReport) {
+    if let Some(ref parse_error) = report.parse_error {
+        println!("Feed could not be parsed: {}", parse_error);
+        return;
+    }
+
+    println!(
+        "Podcast: {}",
+        report
+            .podcast_title
+            .as_ref()
+            .map(String::as_str)
+            .unwrap_or("(untitled)")
+    );
+    for problem in &report.podcast_problems {
+        println!("  Problem: {}", problem);
+    }
+
+    for episode in &report.episodes {
+        println!(
+            "Episode #{} (GUID: {}, title: {}): {}",
+            episode.position,
+            episode.guid.as_ref().map(String::as_str).unwrap_or("none"),
+            episode.title.as_ref().map(String::as_str).unwrap_or("none"),
+            episode.problem
+        );
+    }
+
+    println!(
+        "{} of {} episode(s) valid",
+        report.num_episodes_valid, report.num_episodes
+    );
+    for (fallback, num_episodes) in &report.published_at_fallbacks {
+        println!(
+            "  {} episode(s) with a nonstandard publishing date ({})",
+            num_episodes, fallback
+        );
+    }
+    if report.is_valid() {
+        println!("No problems found");
+    }
+}
+
 /// Initializes and returns a connection pool suitable for use across threads.
 fn pool(log: &Logger, options: &GlobalOptions) -> Result<Pool<ConnectionManager<PgConnection>>> {
     debug!(log, "Initializing connection pool";
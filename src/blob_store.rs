@@ -0,0 +1,475 @@
+//! A content-addressed store for raw feed content.
+//!
+//! Blobs are keyed by the SHA-256 hash of the content that produced them, so
+//! a feed that hasn't changed between crawls (or that's shared between
+//! podcasts) is only stored once. Content is compressed with Zstandard, using
+//! the most recently trained dictionary if there is one. Dictionaries are
+//! immutable once created, which makes them safe to cache for the life of the
+//! process.
+
+use errors::*;
+use model;
+use model::insertable;
+use schema;
+use time_helpers;
+
+use chrono::Utc;
+use diesel;
+use diesel::pg::upsert::excluded;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use slog::Logger;
+use std::collections::HashMap;
+use std::io::prelude::*;
+use std::sync::{Arc, Mutex};
+use zstd;
+
+/// The maximum size of a trained dictionary. This is the size recommended by
+/// Zstandard's own tooling.
+pub const DICTIONARY_MAX_SIZE: usize = 112_640;
+
+/// The maximum size of a blob's compressed content in bytes. This matches the
+/// `CHECK` on `content_blob.content_zstd` and is the same as the largest
+/// allowed maximum size of a response body, so any feed that we'll fetch can
+/// be stored.
+pub const MAX_CONTENT_ZSTD_SIZE: usize = 50 * 1024 * 1024;
+
+/// The level to compress content at. Content is written once and read rarely,
+/// so it's worth trading some speed for a better ratio.
+const COMPRESSION_LEVEL: i32 = 19;
+
+/// The level that a `Compressor` compresses content at. Feeds are compressed
+/// as they're read, which happens on every crawl whether or not their content
+/// turns out to be new, so this trades ratio for speed instead.
+const STREAM_COMPRESSION_LEVEL: i32 = 3;
+
+lazy_static! {
+    static ref DICTIONARIES: Mutex<HashMap<i64, Arc<Vec<u8>>>> = Mutex::new(HashMap::new());
+}
+
+/// Compresses content as it's written so that it can be stored without ever
+/// holding the whole of it in memory. See `store_compressed`.
+pub struct Compressor {
+    dictionary_id: Option<i64>,
+    encoder:       zstd::stream::Encoder<Vec<u8>>,
+}
+
+impl Compressor {
+    /// Starts compressing with the most recently trained dictionary, if there
+    /// is one.
+    pub fn new(log: &Logger, conn: &PgConnection) -> Result<Compressor> {
+        let dictionary = current_dictionary(log, conn)?;
+        let encoder = zstd::stream::Encoder::with_dictionary(
+            Vec::new(),
+            STREAM_COMPRESSION_LEVEL,
+            dictionary
+                .as_ref()
+                .map(|&(_, ref content)| content.as_slice())
+                .unwrap_or(&[]),
+        ).chain_err(|| "Error initializing compressor")?;
+        Ok(Compressor {
+            dictionary_id: dictionary.map(|(id, _)| id),
+            encoder,
+        })
+    }
+}
+
+impl Write for Compressor {
+    fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+        self.encoder.write(buf)
+    }
+
+    fn flush(&mut self) -> ::std::io::Result<()> {
+        self.encoder.flush()
+    }
+}
+
+/// Compresses content, optionally with a dictionary.
+pub fn compress(data: &[u8], dictionary: Option<&[u8]>) -> Result<Vec<u8>> {
+    let mut encoder = zstd::stream::Encoder::with_dictionary(
+        Vec::new(),
+        COMPRESSION_LEVEL,
+        dictionary.unwrap_or(&[]),
+    ).chain_err(|| "Error initializing compressor")?;
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Decompresses content that was compressed with `compress` using the same
+/// dictionary (if any).
+pub fn decompress(data: &[u8], dictionary: Option<&[u8]>) -> Result<Vec<u8>> {
+    let mut decoder = zstd::stream::Decoder::with_dictionary(data, dictionary.unwrap_or(&[]))
+        .chain_err(|| "Error initializing decompressor")?;
+    let mut content = Vec::new();
+    decoder
+        .read_to_end(&mut content)
+        .chain_err(|| "Error decompressing content")?;
+    Ok(content)
+}
+
+/// Loads and decompresses the content stored under a hash, or returns `None`
+/// if there is no such blob.
+pub fn load(log: &Logger, conn: &PgConnection, sha256_hash: &str) -> Result<Option<Vec<u8>>> {
+    let blob: Option<model::ContentBlob> =
+        time_helpers::log_timed(&log.new(o!("step" => "select_content_blob")), |_log| {
+            schema::content_blob::table
+                .filter(schema::content_blob::sha256_hash.eq(sha256_hash))
+                .first(conn)
+                .optional()
+                .chain_err(|| "Error selecting content blob")
+        })?;
+
+    let blob = match blob {
+        Some(blob) => blob,
+        None => return Ok(None),
+    };
+
+    let content = match blob.dictionary_id {
+        Some(dictionary_id) => {
+            let dictionary = load_dictionary(log, conn, dictionary_id)?;
+            decompress(blob.content_zstd.as_slice(), Some(dictionary.as_slice()))?
+        }
+        None => decompress(blob.content_zstd.as_slice(), None)?,
+    };
+    Ok(Some(content))
+}
+
+/// Stores content under its hash. Content that's already stored isn't
+/// compressed again, but its blob is marked as used so that the cleaner
+/// leaves it alone.
+pub fn store(log: &Logger, conn: &PgConnection, sha256_hash: &str, data: &[u8]) -> Result<()> {
+    if touch_blob(log, conn, sha256_hash)? {
+        return Ok(());
+    }
+
+    let dictionary = current_dictionary(log, conn)?;
+    let blob_ins = insertable::ContentBlob {
+        content_zstd:  compress(
+            data,
+            dictionary.as_ref().map(|&(_, ref content)| content.as_slice()),
+        )?,
+        dictionary_id: dictionary.as_ref().map(|&(id, _)| id),
+        sha256_hash:   sha256_hash.to_owned(),
+    };
+    write_blob(log, conn, &blob_ins)
+}
+
+/// Stores content that was compressed by a `Compressor` under its hash. Like
+/// with `store`, content that's already stored is left as it is, but its blob
+/// is marked as used.
+pub fn store_compressed(
+    log: &Logger,
+    conn: &PgConnection,
+    sha256_hash: &str,
+    compressor: Compressor,
+) -> Result<()> {
+    if touch_blob(log, conn, sha256_hash)? {
+        return Ok(());
+    }
+
+    let blob_ins = insertable::ContentBlob {
+        content_zstd:  compressor
+            .encoder
+            .finish()
+            .chain_err(|| "Error finishing compression")?,
+        dictionary_id: compressor.dictionary_id,
+        sha256_hash:   sha256_hash.to_owned(),
+    };
+    write_blob(log, conn, &blob_ins)
+}
+
+/// Rewrites a blob's content using the most recent dictionary. Returns
+/// `false` if it was already compressed with that dictionary (or there isn't
+/// one).
+pub fn recompress(log: &Logger, conn: &PgConnection, blob: &model::ContentBlob) -> Result<bool> {
+    let (dictionary_id, dictionary) = match current_dictionary(log, conn)? {
+        Some(dictionary) => dictionary,
+        None => return Ok(false),
+    };
+    if blob.dictionary_id == Some(dictionary_id) {
+        return Ok(false);
+    }
+
+    let content = match blob.dictionary_id {
+        Some(old_dictionary_id) => {
+            let old_dictionary = load_dictionary(log, conn, old_dictionary_id)?;
+            decompress(blob.content_zstd.as_slice(), Some(old_dictionary.as_slice()))?
+        }
+        None => decompress(blob.content_zstd.as_slice(), None)?,
+    };
+
+    let blob_ins = insertable::ContentBlob {
+        content_zstd:  compress(content.as_slice(), Some(dictionary.as_slice()))?,
+        dictionary_id: Some(dictionary_id),
+        sha256_hash:   blob.sha256_hash.clone(),
+    };
+    write_blob(log, conn, &blob_ins)?;
+    Ok(true)
+}
+
+/// Trains a new dictionary from samples of content and makes it the one that
+/// new blobs are compressed with.
+pub fn train_dictionary(
+    log: &Logger,
+    conn: &PgConnection,
+    samples: &[Vec<u8>],
+) -> Result<model::ContentDictionary> {
+    let content = time_helpers::log_timed(
+        &log.new(o!("step" => "train_dictionary", "num_samples" => samples.len())),
+        |_log| {
+            zstd::dict::from_samples(samples, DICTIONARY_MAX_SIZE)
+                .chain_err(|| "Error training dictionary")
+        },
+    )?;
+
+    time_helpers::log_timed(
+        &log.new(o!("step" => "insert_content_dictionary")),
+        |_log| {
+            diesel::insert_into(schema::content_dictionary::table)
+                .values(&insertable::ContentDictionary {
+                    content,
+                    num_samples: samples.len() as i32,
+                })
+                .get_result(conn)
+                .chain_err(|| "Error inserting content dictionary")
+        },
+    )
+}
+
+//
+// Private functions
+//
+
+// Gets the ID and content of the most recently trained dictionary, if there is
+// one.
+fn current_dictionary(log: &Logger, conn: &PgConnection) -> Result<Option<(i64, Arc<Vec<u8>>)>> {
+    let dictionary_id: Option<i64> = time_helpers::log_timed(
+        &log.new(o!("step" => "select_current_dictionary_id")),
+        |_log| {
+            schema::content_dictionary::table
+                .select(schema::content_dictionary::id)
+                .order(schema::content_dictionary::id.desc())
+                .first(conn)
+                .optional()
+                .chain_err(|| "Error selecting current content dictionary")
+        },
+    )?;
+
+    match dictionary_id {
+        Some(dictionary_id) => Ok(Some((
+            dictionary_id,
+            load_dictionary(log, conn, dictionary_id)?,
+        ))),
+        None => Ok(None),
+    }
+}
+
+// Loads a dictionary's content, going to the database only if it's not
+// already cached.
+fn load_dictionary(log: &Logger, conn: &PgConnection, dictionary_id: i64) -> Result<Arc<Vec<u8>>> {
+    if let Some(content) = DICTIONARIES.lock().unwrap().get(&dictionary_id) {
+        return Ok(Arc::clone(content));
+    }
+
+    let content: Vec<u8> = time_helpers::log_timed(
+        &log.new(o!("step" => "select_content_dictionary", "dictionary_id" => dictionary_id)),
+        |_log| {
+            schema::content_dictionary::table
+                .filter(schema::content_dictionary::id.eq(dictionary_id))
+                .select(schema::content_dictionary::content)
+                .first(conn)
+                .chain_err(|| format!("Error selecting content dictionary {}", dictionary_id))
+        },
+    )?;
+
+    let content = Arc::new(content);
+    DICTIONARIES
+        .lock()
+        .unwrap()
+        .insert(dictionary_id, Arc::clone(&content));
+    Ok(content)
+}
+
+// Fails with a descriptive error instead of leaving it to the database to
+// reject content that's too big with a constraint violation.
+fn check_size(blob_ins: &insertable::ContentBlob, max_size: usize) -> Result<()> {
+    if blob_ins.content_zstd.len() > max_size {
+        bail!(ErrorKind::BlobTooLarge(
+            blob_ins.sha256_hash.clone(),
+            blob_ins.content_zstd.len(),
+            max_size
+        ));
+    }
+    Ok(())
+}
+
+// Marks the blob stored under a hash as used so that the cleaner leaves it
+// alone. Returns `false` if there is no such blob.
+fn touch_blob(log: &Logger, conn: &PgConnection, sha256_hash: &str) -> Result<bool> {
+    let num_updated = time_helpers::log_timed(
+        &log.new(o!("step" => "update_content_blob_last_used_at")),
+        |_log| {
+            diesel::update(
+                schema::content_blob::table
+                    .filter(schema::content_blob::sha256_hash.eq(sha256_hash)),
+            ).set(schema::content_blob::last_used_at.eq(Utc::now()))
+                .execute(conn)
+                .chain_err(|| "Error updating content blob")
+        },
+    )?;
+    Ok(num_updated > 0)
+}
+
+fn write_blob(log: &Logger, conn: &PgConnection, blob_ins: &insertable::ContentBlob) -> Result<()> {
+    check_size(blob_ins, MAX_CONTENT_ZSTD_SIZE)?;
+
+    time_helpers::log_timed(&log.new(o!("step" => "upsert_content_blob")), |_log| {
+        diesel::insert_into(schema::content_blob::table)
+            .values(blob_ins)
+            .on_conflict(schema::content_blob::sha256_hash)
+            .do_update()
+            .set((
+                schema::content_blob::content_zstd.eq(excluded(schema::content_blob::content_zstd)),
+                schema::content_blob::dictionary_id
+                    .eq(excluded(schema::content_blob::dictionary_id)),
+                schema::content_blob::last_used_at.eq(Utc::now()),
+            ))
+            .execute(conn)
+            .chain_err(|| "Error upserting content blob")
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use blob_store::*;
+    use http_requester;
+    use test_helpers;
+
+    #[test]
+    fn test_blob_store_compress() {
+        let content = test_helpers::IDEAL_FEED;
+
+        let compressed = compress(content, None).unwrap();
+        assert!(compressed.len() < content.len());
+        assert_eq!(content, decompress(compressed.as_slice(), None).unwrap().as_slice());
+
+        // Any content can be used as a raw dictionary, and is a reasonable stand in
+        // for a trained one.
+        let dictionary = test_helpers::MINIMAL_FEED;
+        let compressed = compress(content, Some(dictionary)).unwrap();
+        assert_eq!(
+            content,
+            decompress(compressed.as_slice(), Some(dictionary))
+                .unwrap()
+                .as_slice()
+        );
+    }
+
+    #[test]
+    fn test_blob_store_check_size() {
+        let blob_ins = insertable::ContentBlob {
+            content_zstd:  compress(test_helpers::IDEAL_FEED, None).unwrap(),
+            dictionary_id: None,
+            sha256_hash:   "c".repeat(64),
+        };
+        let size = blob_ins.content_zstd.len();
+
+        check_size(&blob_ins, size).unwrap();
+        match check_size(&blob_ins, size - 1).err().unwrap().kind() {
+            &ErrorKind::BlobTooLarge(_, actual_size, max_size) => {
+                assert_eq!(size, actual_size);
+                assert_eq!(size - 1, max_size);
+            }
+            e => panic!("Unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_blob_store_max_content_zstd_size() {
+        // The largest body that the requester can be configured to read has to
+        // fit. Compression only makes content smaller in practice.
+        assert_eq!(http_requester::MAX_BODY_SIZE, MAX_CONTENT_ZSTD_SIZE);
+    }
+
+    #[test]
+    fn test_blob_store_store_and_load() {
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+
+        let sha256_hash = "a".repeat(64);
+        assert_eq!(None, load(&log, &*conn, sha256_hash.as_str()).unwrap());
+
+        store(&log, &*conn, sha256_hash.as_str(), test_helpers::IDEAL_FEED).unwrap();
+        assert_eq!(
+            Some(test_helpers::IDEAL_FEED.to_vec()),
+            load(&log, &*conn, sha256_hash.as_str()).unwrap()
+        );
+
+        // Storing the same content again is a no-op.
+        store(&log, &*conn, sha256_hash.as_str(), test_helpers::IDEAL_FEED).unwrap();
+        assert_eq!(Ok(1), schema::content_blob::table.count().first(&*conn));
+    }
+
+    #[test]
+    fn test_blob_store_store_compressed() {
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+
+        let sha256_hash = "d".repeat(64);
+        let mut compressor = Compressor::new(&log, &*conn).unwrap();
+        for chunk in test_helpers::IDEAL_FEED.chunks(100) {
+            compressor.write_all(chunk).unwrap();
+        }
+        store_compressed(&log, &*conn, sha256_hash.as_str(), compressor).unwrap();
+        assert_eq!(
+            Some(test_helpers::IDEAL_FEED.to_vec()),
+            load(&log, &*conn, sha256_hash.as_str()).unwrap()
+        );
+
+        // Storing the same content again is a no-op.
+        let compressor = Compressor::new(&log, &*conn).unwrap();
+        store_compressed(&log, &*conn, sha256_hash.as_str(), compressor).unwrap();
+        assert_eq!(
+            Some(test_helpers::IDEAL_FEED.to_vec()),
+            load(&log, &*conn, sha256_hash.as_str()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_blob_store_recompress() {
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+
+        let sha256_hash = "b".repeat(64);
+        store(&log, &*conn, sha256_hash.as_str(), test_helpers::IDEAL_FEED).unwrap();
+
+        let dictionary: model::ContentDictionary =
+            diesel::insert_into(schema::content_dictionary::table)
+                .values(&insertable::ContentDictionary {
+                    content:     test_helpers::MINIMAL_FEED.to_vec(),
+                    num_samples: 1,
+                })
+                .get_result(&*conn)
+                .unwrap();
+
+        let blob: model::ContentBlob = schema::content_blob::table
+            .filter(schema::content_blob::sha256_hash.eq(sha256_hash.as_str()))
+            .first(&*conn)
+            .unwrap();
+        assert_eq!(None, blob.dictionary_id);
+        assert!(recompress(&log, &*conn, &blob).unwrap());
+
+        let blob: model::ContentBlob = schema::content_blob::table
+            .filter(schema::content_blob::sha256_hash.eq(sha256_hash.as_str()))
+            .first(&*conn)
+            .unwrap();
+        assert_eq!(Some(dictionary.id), blob.dictionary_id);
+        assert!(!recompress(&log, &*conn, &blob).unwrap());
+
+        assert_eq!(
+            Some(test_helpers::IDEAL_FEED.to_vec()),
+            load(&log, &*conn, sha256_hash.as_str()).unwrap()
+        );
+    }
+}
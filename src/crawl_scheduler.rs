@@ -0,0 +1,355 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use std::cmp;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// What we know about a podcast's history that's used to decide when its feed
+/// should next be crawled.
+pub struct History<'a> {
+    /// Number of consecutive times that updating the podcast has failed.
+    pub num_exceptions: i32,
+
+    /// Publication times of the podcast's most recent episodes in any order.
+    pub published_at: &'a [DateTime<Utc>],
+
+    /// Whether a WebSub hub is pushing the podcast's feed to us.
+    pub pushed: bool,
+}
+
+/// Decides when a podcast's feed should next be crawled.
+///
+/// Publishers tend to be creatures of habit, so we look at the median gap
+/// between a podcast's episodes along with the day of the week and hour of the
+/// day that it usually releases them. A daily show gets checked often around
+/// its usual release time and a lot less in between, while a podcast that
+/// hasn't released anything in a long time gets checked rarely. Failing feeds
+/// are backed off exponentially.
+pub fn next_crawl_at(now: DateTime<Utc>, history: &History) -> DateTime<Utc> {
+    let interval = if history.pushed {
+        Duration::days(INTERVAL_PUSHED_DAYS)
+    } else {
+        publishing_interval(now, history.published_at)
+    };
+    now + back_off(interval, history.num_exceptions)
+}
+
+//
+// Private constants
+//
+
+// Number of consecutive failures after which a failing feed's interval stops
+// doubling.
+const BACK_OFF_MAX_EXCEPTIONS: i32 = 10;
+
+// Longest interval that a failing feed will be backed off to.
+const BACK_OFF_MAX_DAYS: i64 = 7;
+
+// A podcast is considered dormant if it hasn't released an episode in this many
+// times its median gap between episodes ...
+const DORMANT_GAP_MULTIPLE: i32 = 4;
+
+// ... or this many days, whichever is longer. The floor is there so that a
+// podcast that usually releases daily doesn't look dormant after a week off.
+const DORMANT_MIN_DAYS: i64 = 60;
+
+// Minimum fraction of a podcast's episodes (as a percentage) that need to
+// share a day or hour before it's considered a habit.
+const HABIT_MIN_PERCENT: usize = 50;
+
+// Minimum number of episodes needed before we'll look for habits.
+const HABIT_MIN_SAMPLES: usize = 3;
+
+// Interval for a podcast without enough episodes to say anything about how
+// often it publishes.
+const INTERVAL_DEFAULT_MINUTES: i64 = 60;
+
+// Interval for a podcast that hasn't published anything in a long time. This
+// shouldn't be *too* long because there are some high-quality podcasts that
+// almost never see updates, but which we'd still like to see new episodes of
+// reasonably quickly (e.g., "Hardcore History").
+const INTERVAL_DORMANT_DAYS: i64 = 7;
+
+// An active podcast is checked this many times per median gap between its
+// episodes (bounded by the limits below).
+const INTERVAL_GAP_DIVISOR: i32 = 4;
+
+const INTERVAL_MAX_HOURS: i64 = 24;
+const INTERVAL_MIN_MINUTES: i64 = 60;
+
+// Interval for a podcast whose feed is pushed to us. Crawling these is only a
+// fallback for when the hub misses an update (or our subscription has lapsed
+// without us noticing), so it can be much less frequent.
+const INTERVAL_PUSHED_DAYS: i64 = 7;
+
+// Interval for a podcast that's in the window around its usual release time.
+const INTERVAL_RELEASE_MINUTES: i64 = 15;
+
+// How long before and after a podcast's usual release hour that we consider
+// it to be in its release window. Episodes are rarely released on the dot, and
+// this also absorbs a publisher's daylight saving time shifting the hour in
+// UTC.
+const RELEASE_WINDOW_HOURS: i64 = 1;
+
+//
+// Private functions
+//
+
+// Doubles an interval for each consecutive failure.
+fn back_off(interval: Duration, num_exceptions: i32) -> Duration {
+    if num_exceptions <= 0 {
+        return interval;
+    }
+
+    let factor = 2i32.pow(cmp::min(num_exceptions, BACK_OFF_MAX_EXCEPTIONS) as u32);
+    cmp::max(
+        interval,
+        cmp::min(interval * factor, Duration::days(BACK_OFF_MAX_DAYS)),
+    )
+}
+
+// Returns the value that appears most often if it appears often enough to be
+// considered a habit.
+fn habit<T, I>(values: I) -> Option<T>
+where
+    T: Copy + Eq + Hash + Ord,
+    I: Iterator<Item = T>,
+{
+    let mut counts: HashMap<T, usize> = HashMap::new();
+    let mut total = 0;
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+        total += 1;
+    }
+
+    if total < HABIT_MIN_SAMPLES {
+        return None;
+    }
+
+    // Ties are broken by value so that the result is deterministic.
+    counts
+        .into_iter()
+        .max_by_key(|&(value, count)| (count, cmp::Reverse(value)))
+        .and_then(|(value, count)| {
+            if count * 100 >= total * HABIT_MIN_PERCENT {
+                Some(value)
+            } else {
+                None
+            }
+        })
+}
+
+// Returns the median gap between a set of publication times sorted from
+// newest to oldest. There must be at least two of them.
+fn median_gap(published_at: &[DateTime<Utc>]) -> Duration {
+    let mut gaps: Vec<Duration> = published_at
+        .windows(2)
+        .map(|pair| pair[0].signed_duration_since(pair[1]))
+        .collect();
+    gaps.sort();
+    gaps[gaps.len() / 2]
+}
+
+// Returns the start of the next window around a podcast's usual release time,
+// which may be in the past if we're currently in one. `None` if the podcast
+// doesn't release at a habitual time.
+fn next_release_window(
+    now: DateTime<Utc>,
+    published_at: &[DateTime<Utc>],
+) -> Option<DateTime<Utc>> {
+    let hour = habit(published_at.iter().map(|t| t.hour()));
+    let weekday = habit(
+        published_at
+            .iter()
+            .map(|t| t.weekday().num_days_from_monday()),
+    );
+    if hour.is_none() && weekday.is_none() {
+        return None;
+    }
+
+    // Step through hours starting from far enough back to catch a window that
+    // we're currently in until we find one that matches the habits. A week is
+    // always enough to find one.
+    let start = Utc.timestamp(now.timestamp() - now.timestamp() % 3600, 0)
+        - Duration::hours(1 + RELEASE_WINDOW_HOURS);
+    (0..(24 * 8))
+        .map(|i| start + Duration::hours(i))
+        .find(|t| {
+            hour.map_or(true, |h| t.hour() == h)
+                && weekday.map_or(true, |d| t.weekday().num_days_from_monday() == d)
+                && *t + Duration::hours(1 + RELEASE_WINDOW_HOURS) > now
+        })
+        .map(|t| t - Duration::hours(RELEASE_WINDOW_HOURS))
+}
+
+fn publishing_interval(now: DateTime<Utc>, published_at: &[DateTime<Utc>]) -> Duration {
+    let mut published_at = published_at.to_vec();
+    published_at.sort_by(|a, b| b.cmp(a));
+    published_at.dedup();
+
+    if published_at.len() < 2 {
+        return Duration::minutes(INTERVAL_DEFAULT_MINUTES);
+    }
+
+    let median_gap = median_gap(&published_at);
+    let dormant_after = cmp::max(
+        median_gap * DORMANT_GAP_MULTIPLE,
+        Duration::days(DORMANT_MIN_DAYS),
+    );
+    if now.signed_duration_since(published_at[0]) > dormant_after {
+        return Duration::days(INTERVAL_DORMANT_DAYS);
+    }
+
+    let interval = cmp::min(
+        cmp::max(
+            median_gap / INTERVAL_GAP_DIVISOR,
+            Duration::minutes(INTERVAL_MIN_MINUTES),
+        ),
+        Duration::hours(INTERVAL_MAX_HOURS),
+    );
+
+    // Don't sleep through the next release window if it opens before we'd
+    // otherwise check again.
+    match next_release_window(now, &published_at) {
+        Some(window) if window <= now => Duration::minutes(INTERVAL_RELEASE_MINUTES),
+        Some(window) => cmp::min(interval, window.signed_duration_since(now)),
+        None => interval,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crawl_scheduler::*;
+
+    #[test]
+    fn test_crawl_scheduler_default() {
+        let now = Utc.ymd(2018, 5, 29).and_hms(12, 0, 0);
+        assert_eq!(
+            now + Duration::minutes(INTERVAL_DEFAULT_MINUTES),
+            next_crawl_at(now, &history(&[]))
+        );
+        assert_eq!(
+            now + Duration::minutes(INTERVAL_DEFAULT_MINUTES),
+            next_crawl_at(now, &history(&[now - Duration::days(1)]))
+        );
+    }
+
+    #[test]
+    fn test_crawl_scheduler_daily() {
+        // Released every day at 10:00 UTC.
+        let published_at: Vec<DateTime<Utc>> = (1..15)
+            .map(|i| Utc.ymd(2018, 5, 29).and_hms(10, 0, 0) - Duration::days(i))
+            .collect();
+
+        // In the release window.
+        let now = Utc.ymd(2018, 5, 29).and_hms(10, 30, 0);
+        assert_eq!(
+            now + Duration::minutes(INTERVAL_RELEASE_MINUTES),
+            next_crawl_at(now, &history(&published_at))
+        );
+
+        // Just before the release window opens.
+        let now = Utc.ymd(2018, 5, 29).and_hms(7, 0, 0);
+        assert_eq!(
+            Utc.ymd(2018, 5, 29).and_hms(9, 0, 0),
+            next_crawl_at(now, &history(&published_at))
+        );
+
+        // Well outside of the release window.
+        let now = Utc.ymd(2018, 5, 29).and_hms(14, 0, 0);
+        assert_eq!(
+            now + Duration::hours(6),
+            next_crawl_at(now, &history(&published_at))
+        );
+    }
+
+    #[test]
+    fn test_crawl_scheduler_weekly() {
+        // Released every Monday at 08:00 UTC.
+        let published_at: Vec<DateTime<Utc>> = (0..8)
+            .map(|i| Utc.ymd(2018, 5, 28).and_hms(8, 0, 0) - Duration::weeks(i))
+            .collect();
+
+        // Checked daily in between releases.
+        let now = Utc.ymd(2018, 5, 30).and_hms(12, 0, 0);
+        assert_eq!(
+            now + Duration::hours(INTERVAL_MAX_HOURS),
+            next_crawl_at(now, &history(&published_at))
+        );
+
+        // But not at the expense of missing the window.
+        let now = Utc.ymd(2018, 6, 3).and_hms(12, 0, 0);
+        assert_eq!(
+            Utc.ymd(2018, 6, 4).and_hms(7, 0, 0),
+            next_crawl_at(now, &history(&published_at))
+        );
+    }
+
+    #[test]
+    fn test_crawl_scheduler_dormant() {
+        let published_at: Vec<DateTime<Utc>> = (0..5)
+            .map(|i| Utc.ymd(2017, 1, 1).and_hms(0, 0, 0) + Duration::weeks(i))
+            .collect();
+
+        let now = Utc.ymd(2018, 5, 29).and_hms(12, 0, 0);
+        assert_eq!(
+            now + Duration::days(INTERVAL_DORMANT_DAYS),
+            next_crawl_at(now, &history(&published_at))
+        );
+    }
+
+    #[test]
+    fn test_crawl_scheduler_pushed() {
+        let now = Utc.ymd(2018, 5, 29).and_hms(12, 0, 0);
+        assert_eq!(
+            now + Duration::days(INTERVAL_PUSHED_DAYS),
+            next_crawl_at(
+                now,
+                &History {
+                    num_exceptions: 0,
+                    published_at:   &[],
+                    pushed:         true,
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn test_crawl_scheduler_exceptions() {
+        let now = Utc.ymd(2018, 5, 29).and_hms(12, 0, 0);
+        let crawl_at = |num_exceptions| {
+            next_crawl_at(
+                now,
+                &History {
+                    num_exceptions,
+                    published_at: &[],
+                    pushed: false,
+                },
+            )
+        };
+
+        assert_eq!(now + Duration::hours(2), crawl_at(1));
+        assert_eq!(now + Duration::hours(8), crawl_at(3));
+        assert_eq!(now + Duration::days(BACK_OFF_MAX_DAYS), crawl_at(100));
+    }
+
+    #[test]
+    fn test_crawl_scheduler_habit() {
+        assert_eq!(None, habit(vec![1, 1].into_iter()));
+        assert_eq!(Some(1), habit(vec![1, 1, 2].into_iter()));
+        assert_eq!(Some(1), habit(vec![1, 2, 1, 2].into_iter()));
+        assert_eq!(None, habit(vec![1, 2, 3].into_iter()));
+    }
+
+    //
+    // Private types/functions
+    //
+
+    fn history(published_at: &[DateTime<Utc>]) -> History {
+        History {
+            num_exceptions: 0,
+            published_at,
+            pushed: false,
+        }
+    }
+}
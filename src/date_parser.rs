@@ -0,0 +1,776 @@
+use errors::*;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+/// A date that was successfully parsed out of a feed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedDateTime {
+    pub date_time: DateTime<Utc>,
+
+    /// The fallback that was needed to parse the date, or `None` if it was
+    /// valid RFC 2822 or RFC 3339 to begin with.
+    pub fallback: Option<Fallback>,
+}
+
+/// Identifies the layer of the parser that managed to parse a date that
+/// wasn't valid. These are ordered from least to most lossy.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Fallback {
+    /// RFC 2822 after repairing known problems like localized or long month
+    /// names, unknown timezone abbreviations, or a wrong day of the week.
+    Rfc2822Repaired,
+
+    /// ISO 8601 that isn't quite RFC 3339, like one that's missing seconds.
+    Iso8601,
+
+    /// One of a table of nonstandard formats seen in the wild.
+    KnownFormat,
+
+    /// Had no timezone, so UTC was assumed.
+    NoTimezone,
+
+    /// Had no time, so midnight UTC was assumed.
+    DateOnly,
+}
+
+impl Fallback {
+    /// A stable name for the fallback suitable for storage and reporting.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Fallback::Rfc2822Repaired => "rfc2822_repaired",
+            Fallback::Iso8601 => "iso8601",
+            Fallback::KnownFormat => "known_format",
+            Fallback::NoTimezone => "no_timezone",
+            Fallback::DateOnly => "date_only",
+        }
+    }
+}
+
+/// Parses a date from a feed. Valid RFC 2822 (RSS) and RFC 3339 (Atom, JSON
+/// Feed) are tried first, then progressively more lenient fallbacks, the
+/// first of which to succeed is recorded in the result.
+pub fn parse(s: &str) -> Result<ParsedDateTime> {
+    let s = s.trim();
+
+    if let Ok(d) = DateTime::parse_from_rfc2822(s).or_else(|_| DateTime::parse_from_rfc3339(s))
+    {
+        return Ok(ParsedDateTime {
+            date_time: d.with_timezone(&Utc),
+            fallback:  None,
+        });
+    }
+
+    let repaired = repair(s);
+    let repaired = repaired.as_str();
+
+    if let Ok(d) = DateTime::parse_from_rfc2822(repaired) {
+        return Ok(ParsedDateTime {
+            date_time: d.with_timezone(&Utc),
+            fallback:  Some(Fallback::Rfc2822Repaired),
+        });
+    }
+
+    for format in FORMATS.iter() {
+        let date_time = match format.kind {
+            FormatKind::DateOnly => NaiveDate::parse_from_str(repaired, format.format)
+                .map(|d| DateTime::<Utc>::from_utc(d.and_hms(0, 0, 0), Utc))
+                .ok(),
+            FormatKind::NoOffset => NaiveDateTime::parse_from_str(repaired, format.format)
+                .map(|d| DateTime::<Utc>::from_utc(d, Utc))
+                .ok(),
+            FormatKind::Offset => DateTime::parse_from_str(repaired, format.format)
+                .map(|d| d.with_timezone(&Utc))
+                .ok(),
+        };
+
+        if let Some(date_time) = date_time {
+            return Ok(ParsedDateTime {
+                date_time,
+                fallback: Some(format.fallback),
+            });
+        }
+    }
+
+    bail!("Error parsing publishing date {:?} from feed item", s)
+}
+
+//
+// Private types
+//
+
+/// A nonstandard format that we'll try to parse a date with after it's been
+/// repaired.
+struct Format {
+    fallback: Fallback,
+    format:   &'static str,
+    kind:     FormatKind,
+}
+
+enum FormatKind {
+    DateOnly,
+    NoOffset,
+    Offset,
+}
+
+/// Represents a regex find and replace rule that we use to coerce dates that
+/// are not technically valid RFC 2822 into ones that are and which we can
+/// parse.
+struct ReplaceRule {
+    find:    Regex,
+    replace: &'static str,
+}
+
+//
+// Private constants
+//
+
+// Day names in English, French, German, Spanish, Italian, Dutch, and
+// Portuguese, separated by `|` for use in a regex.
+const DAY_NAMES_LONG: &str = "monday|tuesday|wednesday|thursday|friday|saturday|sunday|\
+                              lundi|mardi|mercredi|jeudi|vendredi|samedi|dimanche|\
+                              montag|dienstag|mittwoch|donnerstag|freitag|samstag|sonntag|\
+                              lunes|martes|miércoles|miercoles|jueves|viernes|sábado|sabado|\
+                              domingo|lunedì|lunedi|martedì|martedi|mercoledì|mercoledi|\
+                              giovedì|giovedi|venerdì|venerdi|sabato|domenica|maandag|\
+                              dinsdag|woensdag|donderdag|vrijdag|zaterdag|zondag|segunda|\
+                              terça|terca|quarta|quinta|sexta";
+
+const DAY_NAMES_SHORT: &str = "mon|tue|tues|wed|thu|thur|thurs|fri|sat|sun|lun|mar|mer|jeu|\
+                               ven|sam|dim|mo|di|mi|do|fr|sa|so|mié|mie|jue|vie|sáb|sab|dom|\
+                               gio|ma|wo|vr|za|zo|seg|ter|qua|qui|sex";
+
+// Formats are tried in order, so the more specific and less lossy ones come
+// first. Note that these run on the repaired date, so month names have already
+// been translated, timezone abbreviations converted to offsets, and missing
+// seconds filled in.
+const FORMATS: &[Format] = &[
+    // Like: "2017-12-24T21:37+01:00"
+    Format {
+        fallback: Fallback::Iso8601,
+        format:   "%Y-%m-%dT%H:%M:%S%.f%z",
+        kind:     FormatKind::Offset,
+    },
+    Format {
+        fallback: Fallback::Iso8601,
+        format:   "%Y-%m-%dT%H:%M%z",
+        kind:     FormatKind::Offset,
+    },
+    // Like: "Dec 24, 2017 21:37:32 +0000"
+    Format {
+        fallback: Fallback::KnownFormat,
+        format:   "%b %d, %Y %H:%M:%S %z",
+        kind:     FormatKind::Offset,
+    },
+    // Like: "Sun Dec 24 21:37:32 PST 2017" (the format of Unix `date`)
+    Format {
+        fallback: Fallback::KnownFormat,
+        format:   "%a %b %d %H:%M:%S %z %Y",
+        kind:     FormatKind::Offset,
+    },
+    Format {
+        fallback: Fallback::KnownFormat,
+        format:   "%Y/%m/%d %H:%M:%S %z",
+        kind:     FormatKind::Offset,
+    },
+    Format {
+        fallback: Fallback::KnownFormat,
+        format:   "%m/%d/%Y %H:%M:%S %z",
+        kind:     FormatKind::Offset,
+    },
+    Format {
+        fallback: Fallback::KnownFormat,
+        format:   "%m/%d/%Y %I:%M:%S %p %z",
+        kind:     FormatKind::Offset,
+    },
+    // All of the above, but without a timezone
+    Format {
+        fallback: Fallback::NoTimezone,
+        format:   "%Y-%m-%dT%H:%M:%S%.f",
+        kind:     FormatKind::NoOffset,
+    },
+    Format {
+        fallback: Fallback::NoTimezone,
+        format:   "%Y-%m-%dT%H:%M",
+        kind:     FormatKind::NoOffset,
+    },
+    Format {
+        fallback: Fallback::NoTimezone,
+        format:   "%d %b %Y %H:%M:%S",
+        kind:     FormatKind::NoOffset,
+    },
+    Format {
+        fallback: Fallback::NoTimezone,
+        format:   "%b %d, %Y %H:%M:%S",
+        kind:     FormatKind::NoOffset,
+    },
+    Format {
+        fallback: Fallback::NoTimezone,
+        format:   "%a %b %d %H:%M:%S %Y",
+        kind:     FormatKind::NoOffset,
+    },
+    Format {
+        fallback: Fallback::NoTimezone,
+        format:   "%Y/%m/%d %H:%M:%S",
+        kind:     FormatKind::NoOffset,
+    },
+    Format {
+        fallback: Fallback::NoTimezone,
+        format:   "%m/%d/%Y %H:%M:%S",
+        kind:     FormatKind::NoOffset,
+    },
+    Format {
+        fallback: Fallback::NoTimezone,
+        format:   "%m/%d/%Y %I:%M:%S %p",
+        kind:     FormatKind::NoOffset,
+    },
+    // And finally, dates without any time at all
+    Format {
+        fallback: Fallback::DateOnly,
+        format:   "%Y-%m-%d",
+        kind:     FormatKind::DateOnly,
+    },
+    Format {
+        fallback: Fallback::DateOnly,
+        format:   "%d %b %Y",
+        kind:     FormatKind::DateOnly,
+    },
+    Format {
+        fallback: Fallback::DateOnly,
+        format:   "%b %d, %Y",
+        kind:     FormatKind::DateOnly,
+    },
+    Format {
+        fallback: Fallback::DateOnly,
+        format:   "%Y/%m/%d",
+        kind:     FormatKind::DateOnly,
+    },
+    Format {
+        fallback: Fallback::DateOnly,
+        format:   "%m/%d/%Y",
+        kind:     FormatKind::DateOnly,
+    },
+];
+
+// Long and localized names for each month, separated by `|` for use in a
+// regex, along with the RFC 2822 abbreviation that they're translated to.
+// Abbreviations that are already the same as RFC 2822's are left out.
+const MONTH_NAMES: &[(&str, &str)] = &[
+    (
+        "Jan",
+        "january|janvier|januar|jänner|enero|gennaio|januari|janeiro|janv|jän|ene|gen",
+    ),
+    (
+        "Feb",
+        "february|février|fevrier|februar|febrero|febbraio|februari|fevereiro|févr|fevr|fév|fev",
+    ),
+    ("Mar", "march|mars|märz|maerz|marzo|maart|março|marco|mär|mrt"),
+    ("Apr", "april|avril|abril|aprile|avr|abr"),
+    ("May", "mai|mayo|maggio|maio|mei|mag"),
+    ("Jun", "june|juin|juni|junio|giugno|junho|giu"),
+    ("Jul", "july|juillet|juli|julio|luglio|julho|juil|lug"),
+    ("Aug", "august|août|aout|agosto|augustus|aoû|ago"),
+    ("Sep", "september|septembre|septiembre|settembre|setembro|sept|set"),
+    ("Oct", "october|octobre|oktober|octubre|ottobre|outubro|okt|ott|out"),
+    ("Nov", "november|novembre|noviembre|novembro"),
+    (
+        "Dec",
+        "december|décembre|decembre|dezember|diciembre|dicembre|dezembro|déc|dez|dic",
+    ),
+];
+
+// Abbreviations that RFC 2822 doesn't know about, along with the North
+// American ones that it does because `%z` doesn't know about any of them.
+// Ambiguous abbreviations like "IST" are left out.
+const TIMEZONE_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("ACST", "+0930"),
+    ("ADT", "-0300"),
+    ("AEDT", "+1100"),
+    ("AEST", "+1000"),
+    ("AKDT", "-0800"),
+    ("AKST", "-0900"),
+    ("AST", "-0400"),
+    ("AWST", "+0800"),
+    ("BRT", "-0300"),
+    ("BST", "+0100"),
+    ("CDT", "-0500"),
+    ("CEST", "+0200"),
+    ("CET", "+0100"),
+    ("CST", "-0600"),
+    ("EDT", "-0400"),
+    ("EEST", "+0300"),
+    ("EET", "+0200"),
+    ("EST", "-0500"),
+    ("GMT", "+0000"),
+    ("HKT", "+0800"),
+    ("HST", "-1000"),
+    ("JST", "+0900"),
+    ("KST", "+0900"),
+    ("MDT", "-0600"),
+    ("MESZ", "+0200"),
+    ("MEZ", "+0100"),
+    ("MSK", "+0300"),
+    ("MST", "-0700"),
+    ("NDT", "-0230"),
+    ("NST", "-0330"),
+    ("NZDT", "+1300"),
+    ("NZST", "+1200"),
+    ("PDT", "-0700"),
+    ("PST", "-0800"),
+    ("SGT", "+0800"),
+    ("UTC", "+0000"),
+    ("WEST", "+0100"),
+    ("WET", "+0000"),
+];
+
+//
+// Private functions
+//
+
+// Rewrites a date to fix problems that we know about. The result is hopefully
+// valid RFC 2822, but if not, it's at least normalized enough to give the
+// formats in `FORMATS` a good chance.
+#[cfg_attr(feature = "cargo-clippy", allow(trivial_regex))]
+fn repair(s: &str) -> String {
+    lazy_static! {
+        static ref RULES: Vec<ReplaceRule> = {
+            let mut rules = vec!(
+                // Extra whitespace
+                ReplaceRule { find: Regex::new(r"\s+").unwrap(), replace: " ", },
+
+                // Like: "So, 24. Dez 2017" (German puts a period after the day)
+                ReplaceRule { find: Regex::new(r"\b(?P<d>\d{1,2})\. ").unwrap(), replace: "$d ", },
+
+                // Like: "24 de diciembre de 2017" (Spanish and Portuguese)
+                ReplaceRule { find: Regex::new(r"(?i) de ").unwrap(), replace: " ", },
+
+                // Day names are optional in RFC 2822, so drop any that aren't just "Mon"
+                // through "Sun" (or are wrong) instead of translating them. Long names
+                // are always dropped, but abbreviations only with punctuation so that
+                // something like "Mar 24, 2017" (where "mar" is March and not French
+                // or Spanish for Tuesday) is left alone.
+                ReplaceRule {
+                    find: Regex::new(&format!(
+                        r"(?i)^(?:{})(?:-feira)?\.?,? ?(?P<d>\d)",
+                        DAY_NAMES_LONG
+                    )).unwrap(),
+                    replace: "$d",
+                },
+                ReplaceRule {
+                    find: Regex::new(&format!(
+                        r"(?i)^(?:{})(?:\.,?|,) ?(?P<d>\d)",
+                        DAY_NAMES_SHORT
+                    )).unwrap(),
+                    replace: "$d",
+                },
+            );
+
+            // Long and localized month names
+            rules.extend(MONTH_NAMES.iter().map(|&(abbreviation, names)| ReplaceRule {
+                find:    Regex::new(&format!(r"(?i)\b(?:{})\b\.?", names)).unwrap(),
+                replace: abbreviation,
+            }));
+
+            rules.extend(vec!(
+                // The "-0000" timezone is not considered valid by true pedants
+                ReplaceRule { find: Regex::new(r"-0000$").unwrap(), replace: "+0000", },
+
+                // Like: "+01:00" (RFC 2822 offsets don't have a colon)
+                ReplaceRule {
+                    find: Regex::new(r"(?P<sign>[+-])(?P<h>\d{2}):(?P<m>\d{2})$").unwrap(),
+                    replace: "$sign$h$m",
+                },
+
+                // Like: "2017-12-24T21:37Z"
+                ReplaceRule { find: Regex::new(r"(?P<t>\d)[Zz]$").unwrap(), replace: "$t+0000", },
+
+                // Like: "2017-12-24 21:37:32"
+                ReplaceRule {
+                    find: Regex::new(r"^(?P<date>\d{4}-\d{2}-\d{2})[ t](?P<time>\d)").unwrap(),
+                    replace: "${date}T$time",
+                },
+
+                // Like: "Mon, 27 Mar 2017 9:42:00 EST" (technically need two digits
+                // everywhere to be valid)
+                ReplaceRule { find: Regex::new(r"\b(?P<h>\d):").unwrap(), replace: "0$h:", },
+
+                // Like: "12/24/2017 09:37 PM" (no seconds)
+                ReplaceRule {
+                    find: Regex::new(r" (?P<hm>\d{2}:\d{2})(?P<end> |$)").unwrap(),
+                    replace: " $hm:00$end",
+                },
+            ));
+
+            rules
+        };
+
+        // Like: "GMT+2" or "UTC-05:30"
+        static ref GMT_OFFSET: Regex = Regex::new(
+            r"\b(?:GMT|UTC)(?P<sign>[+-])(?P<h>\d{1,2})(?::?(?P<m>\d{2}))?\b"
+        ).unwrap();
+
+        static ref TIMEZONE: Regex = Regex::new(r"\b[A-Z]{2,4}\b").unwrap();
+        static ref TIMEZONE_OFFSETS: HashMap<&'static str, &'static str> =
+            TIMEZONE_ABBREVIATIONS.iter().cloned().collect();
+    }
+
+    let mut s = s.trim().to_owned();
+    for r in RULES.iter() {
+        s = r.find.replace_all(s.as_str(), r.replace).into_owned();
+    }
+
+    s = GMT_OFFSET
+        .replace_all(s.as_str(), |caps: &Captures| {
+            format!(
+                "{}{:0>2}{}",
+                &caps["sign"],
+                &caps["h"],
+                caps.name("m").map(|m| m.as_str()).unwrap_or("00")
+            )
+        })
+        .into_owned();
+
+    TIMEZONE
+        .replace_all(s.as_str(), |caps: &Captures| {
+            TIMEZONE_OFFSETS
+                .get(&caps[0])
+                .map(|offset| (*offset).to_owned())
+                .unwrap_or_else(|| caps[0].to_owned())
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use date_parser::*;
+
+    use chrono::prelude::*;
+
+    #[test]
+    fn test_date_parser_corpus() {
+        // Each case is a date as found in a real feed along with what it should be
+        // parsed to and which fallback (if any) is expected to parse it.
+        let utc = |y, m, d, h, i, s| Utc.ymd(y, m, d).and_hms(h, i, s);
+        let cases: Vec<(&str, DateTime<Utc>, Option<Fallback>)> = vec![
+            //
+            // Valid
+            //
+            (
+                "Sun, 24 Dec 2017 21:37:32 +0000",
+                utc(2017, 12, 24, 21, 37, 32),
+                None,
+            ),
+            // Named North American timezones are part of RFC 2822
+            (
+                "Sun, 24 Dec 2017 21:37:32 EST",
+                utc(2017, 12, 25, 2, 37, 32),
+                None,
+            ),
+            (
+                "Sun, 24 Dec 2017 21:37:32 PDT",
+                utc(2017, 12, 25, 4, 37, 32),
+                None,
+            ),
+            (
+                "Sun, 24 Dec 2017 21:37:32 GMT",
+                utc(2017, 12, 24, 21, 37, 32),
+                None,
+            ),
+            (
+                "24 Dec 2017 21:37:32 +0100",
+                utc(2017, 12, 24, 20, 37, 32),
+                None,
+            ),
+            // RFC 3339 as used by Atom feeds
+            (
+                "2017-12-24T21:37:32Z",
+                utc(2017, 12, 24, 21, 37, 32),
+                None,
+            ),
+            (
+                "2017-12-24T16:37:32-05:00",
+                utc(2017, 12, 24, 21, 37, 32),
+                None,
+            ),
+            (
+                "  Sun, 24 Dec 2017 21:37:32 +0000\n",
+                utc(2017, 12, 24, 21, 37, 32),
+                None,
+            ),
+            //
+            // RFC 2822 after repairs
+            //
+            // Never forget how uselessly pedantic Rust programmers are. A "-0000" is
+            // technically considered missing even though it's obvious to anyone on
+            // Earth what should be done with it.
+            (
+                "Sun, 24 Dec 2017 21:37:32 -0000",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            // Notice the truncated "0:" -- seen on Communion After Dark
+            (
+                "Sun, 24 Dec 2017 0:37:32 EST",
+                utc(2017, 12, 24, 5, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            // Wrong day of the week
+            (
+                "Mon, 24 Dec 2017 21:37:32 +0000",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "Sunday, 24 December 2017 21:37:32 +0000",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "Sun, 24 Sept 2017 21:37:32 +0000",
+                utc(2017, 9, 24, 21, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "Sun, 24 Dec 2017 21:37:32 CEST",
+                utc(2017, 12, 24, 19, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "Sun, 24 Dec 2017 21:37:32 AEDT",
+                utc(2017, 12, 24, 10, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "Sun, 24 Dec 2017 21:37:32 UTC",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "Sun, 24 Dec 2017 21:37:32 GMT+2",
+                utc(2017, 12, 24, 19, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "Sun, 24 Dec 2017 21:37:32 GMT-05:30",
+                utc(2017, 12, 25, 3, 7, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "Sun, 24 Dec 2017 21:37:32 +01:00",
+                utc(2017, 12, 24, 20, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            // French
+            (
+                "dim., 24 déc. 2017 21:37:32 +0100",
+                utc(2017, 12, 24, 20, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "mardi 1 août 2017 08:00:00 +0200",
+                utc(2017, 8, 1, 6, 0, 0),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            // German
+            (
+                "So, 24. Dez 2017 21:37:32 MEZ",
+                utc(2017, 12, 24, 20, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "Mi, 01 Mär 2017 09:00:00 +0100",
+                utc(2017, 3, 1, 8, 0, 0),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            // Spanish
+            (
+                "dom, 24 dic 2017 21:37:32 +0100",
+                utc(2017, 12, 24, 20, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            (
+                "24 de diciembre de 2017 21:37:32 +0100",
+                utc(2017, 12, 24, 20, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            // Italian
+            (
+                "lun, 04 giu 2018 10:00:00 +0200",
+                utc(2018, 6, 4, 8, 0, 0),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            // Portuguese
+            (
+                "segunda-feira, 4 de junho de 2018 10:00:00 -0300",
+                utc(2018, 6, 4, 13, 0, 0),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            // Dutch
+            (
+                "zo, 24 december 2017 21:37:32 +0100",
+                utc(2017, 12, 24, 20, 37, 32),
+                Some(Fallback::Rfc2822Repaired),
+            ),
+            //
+            // ISO 8601
+            //
+            (
+                "2017-12-24T21:37Z",
+                utc(2017, 12, 24, 21, 37, 0),
+                Some(Fallback::Iso8601),
+            ),
+            (
+                "2017-12-24T21:37-05:00",
+                utc(2017, 12, 25, 2, 37, 0),
+                Some(Fallback::Iso8601),
+            ),
+            //
+            // Other known formats
+            //
+            (
+                "Dec 24, 2017 21:37:32 +0000",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::KnownFormat),
+            ),
+            (
+                "Mar 24, 2017 21:37:32 +0000",
+                utc(2017, 3, 24, 21, 37, 32),
+                Some(Fallback::KnownFormat),
+            ),
+            (
+                "Sun Dec 24 21:37:32 PST 2017",
+                utc(2017, 12, 25, 5, 37, 32),
+                Some(Fallback::KnownFormat),
+            ),
+            (
+                "2017/12/24 21:37:32 +0000",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::KnownFormat),
+            ),
+            (
+                "12/24/2017 9:37:32 PM EST",
+                utc(2017, 12, 25, 2, 37, 32),
+                Some(Fallback::KnownFormat),
+            ),
+            //
+            // No timezone
+            //
+            (
+                "Sun, 24 Dec 2017 21:37:32",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::NoTimezone),
+            ),
+            (
+                "24 Dec 2017 21:37",
+                utc(2017, 12, 24, 21, 37, 0),
+                Some(Fallback::NoTimezone),
+            ),
+            (
+                "2017-12-24T21:37:32",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::NoTimezone),
+            ),
+            (
+                "2017-12-24 21:37:32",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::NoTimezone),
+            ),
+            (
+                "2017-12-24T21:37",
+                utc(2017, 12, 24, 21, 37, 0),
+                Some(Fallback::NoTimezone),
+            ),
+            (
+                "December 24, 2017 21:37:32",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::NoTimezone),
+            ),
+            (
+                "Sun Dec 24 21:37:32 2017",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::NoTimezone),
+            ),
+            (
+                "12/24/2017 9:37 PM",
+                utc(2017, 12, 24, 21, 37, 0),
+                Some(Fallback::NoTimezone),
+            ),
+            (
+                "2017/12/24 21:37:32",
+                utc(2017, 12, 24, 21, 37, 32),
+                Some(Fallback::NoTimezone),
+            ),
+            //
+            // No time
+            //
+            (
+                "2017-12-24",
+                utc(2017, 12, 24, 0, 0, 0),
+                Some(Fallback::DateOnly),
+            ),
+            (
+                "Sun, 24 Dec 2017",
+                utc(2017, 12, 24, 0, 0, 0),
+                Some(Fallback::DateOnly),
+            ),
+            (
+                "December 24, 2017",
+                utc(2017, 12, 24, 0, 0, 0),
+                Some(Fallback::DateOnly),
+            ),
+            (
+                "24 décembre 2017",
+                utc(2017, 12, 24, 0, 0, 0),
+                Some(Fallback::DateOnly),
+            ),
+            (
+                "12/24/2017",
+                utc(2017, 12, 24, 0, 0, 0),
+                Some(Fallback::DateOnly),
+            ),
+        ];
+
+        for (s, date_time, fallback) in cases {
+            let parsed = parse(s);
+            assert!(parsed.is_ok(), "Failed to parse: {:?}", s);
+            assert_eq!(
+                ParsedDateTime {
+                    date_time,
+                    fallback,
+                },
+                parsed.unwrap(),
+                "Parsed incorrectly: {:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_date_parser_invalid() {
+        for s in &[
+            "",
+            "not a date",
+            "Sun, 24 Foo 2017 21:37:32 +0000",
+            "Sun, 32 Dec 2017 21:37:32 +0000",
+            "2017-13-24T21:37:32Z",
+            "Sun, 24 Dec 2017 21:37:32 XYZ",
+        ] {
+            let res = parse(s);
+            assert!(res.is_err(), "Unexpectedly parsed: {:?}", s);
+            assert_eq!(
+                format!("Error parsing publishing date {:?} from feed item", s),
+                res.err().unwrap().to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_date_parser_fallback_name() {
+        assert_eq!("rfc2822_repaired", Fallback::Rfc2822Repaired.name());
+        assert_eq!("iso8601", Fallback::Iso8601.name());
+        assert_eq!("known_format", Fallback::KnownFormat.name());
+        assert_eq!("no_timezone", Fallback::NoTimezone.name());
+        assert_eq!("date_only", Fallback::DateOnly.name());
+    }
+}
@@ -1,7 +1,7 @@
 use errors::*;
 use mediators::error_reporter::{Mediator, SentryCredentials};
 
-use http_requester::HttpRequesterLive;
+use http_requester::{HttpRequesterLive, MAX_BODY_SIZE};
 use hyper::Client;
 use hyper_tls::HttpsConnector;
 use slog::Logger;
@@ -41,7 +41,11 @@ pub fn report_error(log: &Logger, error: &Error) -> Result<()> {
                 .connector(HttpsConnector::new(4, &core.handle()).map_err(Error::from)?)
                 .build(&core.handle());
             let creds = url.parse::<SentryCredentials>().unwrap();
-            let mut http_requester = HttpRequesterLive { client, core };
+            let mut http_requester = HttpRequesterLive {
+                client,
+                core,
+                max_body_size: MAX_BODY_SIZE,
+            };
 
             let _res = Mediator {
                 creds: &creds,
@@ -26,6 +26,21 @@ error_chain!{
     }
 
     errors {
+        /// Occurs when content is too large to be stored in the blob store even after it's been
+        /// compressed.
+        BlobTooLarge(sha256_hash: String, size: usize, max_size: usize) {
+            description("Blob too large"),
+            display("Compressed content {} of {} bytes exceeds maximum blob size of {} bytes",
+                sha256_hash, size, max_size),
+        }
+
+        /// Occurs when an HTTP response's body is larger than we're willing to read, either as it
+        /// came off the wire or after being decoded.
+        BodyTooLarge(url: String, max_size: usize) {
+            description("Response body too large"),
+            display("Response body from {} exceeded maximum size of {} bytes", url, max_size),
+        }
+
         /// Occurs when encountering a job in the job queue which we don't know how to handle.
         ///
         /// This is often the result of a deployment mismatch. When new job classes are added, the
@@ -37,6 +52,13 @@ error_chain!{
             display("Unknown job: {}", name),
         }
 
+        /// Occurs when a host responds with a `429` or `503` asking us to come back later. This
+        /// isn't a problem with what we requested, so it's not treated like other failures.
+        Throttled(url: String) {
+            description("Throttled by host"),
+            display("Host asked us to back off when requesting {}", url),
+        }
+
         SentryCredentialParseError {
             description("Invalid Sentry DSN syntax. Expected the form `(http|https)://{public key}:{private key}@{host}:{port}/{project id}`")
         }
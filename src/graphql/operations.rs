@@ -1,4 +1,5 @@
 use errors::*;
+use mediators;
 use model;
 use schema;
 
@@ -9,6 +10,7 @@ use juniper::FieldResult;
 use r2d2::PooledConnection;
 use r2d2_diesel::ConnectionManager;
 use slog::Logger;
+use std::collections::HashMap;
 use std::str::FromStr;
 
 //
@@ -99,6 +101,19 @@ graphql_object!(
                 }
             )?)
         }
+
+        field account_subscriptions_import(&executor,
+            opml: String as "The contents of an OPML file exported from another podcast app."
+        ) -> FieldResult<Vec<resource::OpmlImportFeed>> as "A result for every distinct feed found in the file." {
+            Ok(mutation::account_subscriptions_import::execute(
+                &executor.context().log,
+                &mutation::account_subscriptions_import::Params {
+                    account: &executor.context().account,
+                    conn:    &executor.context().conn(),
+                    opml:    &opml,
+                }
+            )?)
+        }
     }
 );
 
@@ -679,6 +694,111 @@ mod mutation {
             }
         }
     }
+
+    pub mod account_subscriptions_import {
+        use graphql::operations::mutation::*;
+
+        pub struct Params<'a> {
+            pub account: &'a model::Account,
+            pub conn:    &'a PgConnection,
+            pub opml:    &'a str,
+        }
+
+        pub fn execute<'a>(
+            log: &Logger,
+            params: &Params<'a>,
+        ) -> Result<Vec<resource::OpmlImportFeed>> {
+            let res = mediators::opml_importer::Mediator {
+                account: params.account,
+                conn:    params.conn,
+                data:    params.opml.as_bytes(),
+            }.run(log)?;
+            Ok(res.feeds
+                .iter()
+                .map(resource::OpmlImportFeed::from)
+                .collect())
+        }
+
+        //
+        // Tests
+        //
+
+        #[cfg(test)]
+        mod tests {
+            use graphql::operations::mutation::account_subscriptions_import::*;
+            use test_data;
+            use test_helpers;
+
+            use r2d2::PooledConnection;
+            use r2d2_diesel::ConnectionManager;
+
+            #[test]
+            fn test_mutation_account_subscriptions_import() {
+                let bootstrap = TestBootstrap::new();
+
+                let opml = format!(
+                    r#"<opml version="2.0"><body>
+<outline text="Known Podcast" xmlUrl="{}" />
+<outline text="Unknown Podcast" xmlUrl="https://example.com/unknown.xml" />
+</body></opml>"#,
+                    bootstrap.feed_url
+                );
+
+                let feeds = execute(
+                    &bootstrap.log,
+                    &Params {
+                        account: &bootstrap.account,
+                        conn:    &*bootstrap.conn,
+                        opml:    opml.as_str(),
+                    },
+                ).unwrap();
+                assert_eq!(2, feeds.len());
+                assert_eq!(Some(bootstrap.podcast.id.to_string()), feeds[0].podcast_id);
+                assert_eq!("subscribed", feeds[0].status);
+                assert_eq!(None, feeds[1].podcast_id);
+                assert_eq!("enqueued", feeds[1].status);
+            }
+
+            //
+            // Private types/functions
+            //
+
+            struct TestBootstrap {
+                _common:  test_helpers::CommonTestBootstrap,
+                account:  model::Account,
+                conn:     PooledConnection<ConnectionManager<PgConnection>>,
+                feed_url: String,
+                log:      Logger,
+                podcast:  model::Podcast,
+            }
+
+            impl TestBootstrap {
+                fn new() -> TestBootstrap {
+                    let conn = test_helpers::connection();
+                    let log = test_helpers::log();
+
+                    let feed_url = "https://example.com/known.xml".to_owned();
+
+                    TestBootstrap {
+                        _common: test_helpers::CommonTestBootstrap::new(),
+                        account: test_data::account::insert(&log, &conn),
+                        podcast: test_data::podcast::insert_args(
+                            &log,
+                            &conn,
+                            test_data::podcast::Args {
+                                feed_url: Some(feed_url.clone()),
+                            },
+                        ),
+
+                        // Only move these after filling the above
+                        conn:     conn,
+                        feed_url: feed_url,
+                        log:      log,
+                    }
+                }
+            }
+        }
+    }
 }
 
 //
@@ -693,24 +813,135 @@ impl Query {}
 graphql_object!(Query: Context |&self| {
     description: "The root query object of the schema."
 
+    field accountSubscriptionsOpml(&executor) ->
+            FieldResult<String> as "The account's subscriptions as an OPML 2.0 document." {
+        let context = executor.context();
+        let res = mediators::opml_exporter::Mediator {
+            account: &context.account,
+            conn:    context.conn(),
+        }.run(&context.log)?;
+        Ok(res.opml)
+    }
+
     field apiVersion() -> &str {
         "1.0"
     }
 
-    field episode(&executor, podcast_id: String as "The podcast's ID.") ->
-            FieldResult<Vec<resource::Episode>> as "A collection episodes for a podcast." {
+    field categories(&executor) ->
+            FieldResult<Vec<resource::Category>> as "Every category that a podcast can be in." {
+        let context = executor.context();
+        let results = schema::category::table
+            .order(schema::category::name.asc())
+            .load::<model::Category>(&*context.conn)
+            .chain_err(|| "Error loading categories from the database")?
+            .iter()
+            .map(resource::Category::from)
+            .collect::<Vec<_>>();
+        Ok(results)
+    }
+
+    field episode(
+        &executor,
+        podcast_id: String as "The podcast's ID.",
+        include_removed: Option<bool> as "Whether to include episodes removed from the feed.",
+        media_gone: Option<bool> as "Only include episodes whose media is (or isn't) gone."
+    ) -> FieldResult<Vec<resource::Episode>> as "A collection episodes for a podcast." {
         let id = i64::from_str(podcast_id.as_str()).
             chain_err(|| "Error parsing podcast ID")?;
 
         let context = executor.context();
-        let results = schema::episode::table
+        let mut query = schema::episode::table
             .filter(schema::episode::podcast_id.eq(id))
             .order(schema::episode::published_at.desc())
             .limit(50)
+            .into_boxed();
+        if !include_removed.unwrap_or(false) {
+            query = query.filter(schema::episode::removed_at.is_null());
+        }
+        match media_gone {
+            Some(true) => query = query.filter(schema::episode::media_gone_at.is_not_null()),
+            Some(false) => query = query.filter(schema::episode::media_gone_at.is_null()),
+            None => (),
+        }
+        let episodes = query
             .load::<model::Episode>(&*context.conn)
-            .chain_err(|| "Error loading episodes from the database")?
+            .chain_err(|| "Error loading episodes from the database")?;
+
+        // Chapters for every episode are loaded in one go and then divided up.
+        let ids: Vec<i64> = episodes.iter().map(|e| e.id).collect();
+        let mut chapters_by_episode: HashMap<i64, Vec<model::EpisodeChapter>> = HashMap::new();
+        for chapter in schema::episode_chapter::table
+            .filter(schema::episode_chapter::episode_id.eq_any(ids))
+            .order(schema::episode_chapter::start_seconds.asc())
+            .load::<model::EpisodeChapter>(&*context.conn)
+            .chain_err(|| "Error loading episode chapters from the database")?
+        {
+            chapters_by_episode.entry(chapter.episode_id).or_insert_with(Vec::new).push(chapter);
+        }
+
+        let results = episodes
+            .iter()
+            .map(|e| {
+                let chapters = chapters_by_episode.remove(&e.id).unwrap_or_else(Vec::new);
+                resource::Episode::new(e, &model::EpisodeChapter::preferred(chapters))
+            })
+            .collect::<Vec<_>>();
+        Ok(results)
+    }
+
+    field episodeChaptersUrl(&executor, episode_id: String as "The episode's ID.") ->
+            FieldResult<Vec<resource::EpisodeChaptersUrl>> as "Links to an episode's chapter files." {
+        let id = i64::from_str(episode_id.as_str()).
+            chain_err(|| "Error parsing episode ID")?;
+
+        let context = executor.context();
+        let results = schema::episode_chapters_url::table
+            .filter(schema::episode_chapters_url::episode_id.eq(id))
+            .order(schema::episode_chapters_url::id.asc())
+            .load::<model::EpisodeChaptersUrl>(&*context.conn)
+            .chain_err(|| "Error loading episode chapters URLs from the database")?
+            .iter()
+            .map(resource::EpisodeChaptersUrl::from)
+            .collect::<Vec<_>>();
+        Ok(results)
+    }
+
+    field episodeRevision(&executor, podcast_id: String as "The podcast's ID.") ->
+            FieldResult<Vec<resource::EpisodeRevision>> as "Changes to a podcast's episodes." {
+        let id = i64::from_str(podcast_id.as_str()).
+            chain_err(|| "Error parsing podcast ID")?;
+
+        let context = executor.context();
+        let results = schema::episode_revision::table
+            .inner_join(schema::episode::table)
+            .filter(schema::episode::podcast_id.eq(id))
+            .order((
+                schema::episode_revision::created_at.desc(),
+                schema::episode_revision::id.desc(),
+            ))
+            .select(schema::episode_revision::all_columns)
+            .limit(100)
+            .load::<model::EpisodeRevision>(&*context.conn)
+            .chain_err(|| "Error loading episode revisions from the database")?
+            .iter()
+            .map(resource::EpisodeRevision::from)
+            .collect::<Vec<_>>();
+        Ok(results)
+    }
+
+    field episodeTranscript(&executor, episode_id: String as "The episode's ID.") ->
+            FieldResult<Vec<resource::EpisodeTranscript>> as "Links to an episode's transcripts." {
+        let id = i64::from_str(episode_id.as_str()).
+            chain_err(|| "Error parsing episode ID")?;
+
+        let context = executor.context();
+        let results = schema::episode_transcript::table
+            .filter(schema::episode_transcript::episode_id.eq(id))
+            .order(schema::episode_transcript::id.asc())
+            .load::<model::EpisodeTranscript>(&*context.conn)
+            .chain_err(|| "Error loading episode transcripts from the database")?
             .iter()
-            .map(resource::Episode::from)
+            .map(resource::EpisodeTranscript::from)
             .collect::<Vec<_>>();
         Ok(results)
     }
@@ -727,6 +958,90 @@ graphql_object!(Query: Context |&self| {
             .collect::<Vec<_>>();
         Ok(results)
     }
+
+    field podcastsByCategory(&executor, slug: String as "The category's slug (e.g. `arts`).") ->
+            FieldResult<Vec<resource::Podcast>> as "Podcasts in a category or its subcategories." {
+        let context = executor.context();
+        let category: model::Category = schema::category::table
+            .filter(schema::category::slug.eq(slug.as_str()))
+            .first(&*context.conn)
+            .optional()
+            .chain_err(|| "Error loading category from the database")?
+            .ok_or_else(|| {
+                user_errors::not_found_general(format!("Category \"{}\" was not found.", slug))
+            })?;
+
+        let mut category_ids: Vec<i64> = schema::category::table
+            .filter(schema::category::parent_id.eq(category.id))
+            .select(schema::category::id)
+            .load(&*context.conn)
+            .chain_err(|| "Error loading subcategories from the database")?;
+        category_ids.push(category.id);
+
+        let results = schema::podcast::table
+            .filter(
+                schema::podcast::id.eq_any(
+                    schema::podcast_category::table
+                        .filter(schema::podcast_category::category_id.eq_any(category_ids))
+                        .select(schema::podcast_category::podcast_id),
+                ),
+            )
+            .order(schema::podcast::title.asc())
+            .limit(100)
+            .load::<model::Podcast>(&*context.conn)
+            .chain_err(|| "Error loading podcasts from the database")?
+            .iter()
+            .map(resource::Podcast::from)
+            .collect::<Vec<_>>();
+        Ok(results)
+    }
+
+    field podcastFunding(&executor, podcast_id: String as "The podcast's ID.") ->
+            FieldResult<Vec<resource::PodcastFunding>> as "Links for supporting a podcast." {
+        let id = i64::from_str(podcast_id.as_str()).
+            chain_err(|| "Error parsing podcast ID")?;
+
+        let context = executor.context();
+        let results = schema::podcast_funding::table
+            .filter(schema::podcast_funding::podcast_id.eq(id))
+            .order(schema::podcast_funding::id.asc())
+            .load::<model::PodcastFunding>(&*context.conn)
+            .chain_err(|| "Error loading podcast funding from the database")?
+            .iter()
+            .map(resource::PodcastFunding::from)
+            .collect::<Vec<_>>();
+        Ok(results)
+    }
+
+    field podcastPerson(
+        &executor,
+        podcast_id: String as "The podcast's ID.",
+        episode_id: Option<String> as "An episode's ID, or null for the podcast as a whole."
+    ) -> FieldResult<Vec<resource::PodcastPerson>> as "People associated with a podcast." {
+        let id = i64::from_str(podcast_id.as_str()).
+            chain_err(|| "Error parsing podcast ID")?;
+
+        let context = executor.context();
+        let query = schema::podcast_person::table
+            .filter(schema::podcast_person::podcast_id.eq(id))
+            .order(schema::podcast_person::id.asc())
+            .into_boxed();
+        let query = match episode_id {
+            Some(episode_id) => {
+                let episode_id = i64::from_str(episode_id.as_str()).
+                    chain_err(|| "Error parsing episode ID")?;
+                query.filter(schema::podcast_person::episode_id.eq(episode_id))
+            }
+            None => query.filter(schema::podcast_person::episode_id.is_null()),
+        };
+        let results = query
+            .load::<model::PodcastPerson>(&*context.conn)
+            .chain_err(|| "Error loading podcast persons from the database")?
+            .iter()
+            .map(resource::PodcastPerson::from)
+            .collect::<Vec<_>>();
+        Ok(results)
+    }
 });
 
 //
@@ -734,6 +1049,7 @@ graphql_object!(Query: Context |&self| {
 //
 
 mod resource {
+    use mediators::opml_importer;
     use model;
 
     use chrono::{DateTime, Utc};
@@ -786,20 +1102,81 @@ mod resource {
         }
     }
 
+    #[derive(GraphQLObject)]
+    pub struct Category {
+        #[graphql(description = "The category's ID.")]
+        pub id: String,
+
+        #[graphql(description = "The category's name (e.g. \"Society & Culture\").")]
+        pub name: String,
+
+        #[graphql(description = "The ID of the category that this is a subcategory of.")]
+        pub parent_id: Option<String>,
+
+        #[graphql(description = "The category's URL-safe name (e.g. `society-and-culture`).")]
+        pub slug: String,
+    }
+
+    impl<'a> From<&'a model::Category> for Category {
+        fn from(c: &model::Category) -> Self {
+            Category {
+                id:        c.id.to_string(),
+                name:      c.name.to_owned(),
+                parent_id: c.parent_id.map(|id| id.to_string()),
+                slug:      c.slug.to_owned(),
+            }
+        }
+    }
+
     #[derive(GraphQLObject)]
     pub struct Episode {
         #[graphql(description = "The episode's ID.")]
         pub id: String,
 
+        #[graphql(description = "Whether the episode has asked to be hidden from directories.")]
+        pub block: Option<bool>,
+
+        #[graphql(description = "The episode's chapters in order of when they start.")]
+        pub chapters: Vec<EpisodeChapter>,
+
         #[graphql(description = "The episode's description.")]
         pub description: Option<String>,
 
+        // GraphQL's `Int` is only 32 bits, so like the media's content length, this is exposed as a
+        // float, which holds any realistic duration exactly.
+        #[graphql(description = "The episode's duration in seconds.")]
+        pub duration_seconds: Option<f64>,
+
+        #[graphql(description = "The episode's number within its season (or the podcast).")]
+        pub episode_number: Option<i32>,
+
+        #[graphql(description = "The episode's type (i.e. `full`, `trailer`, or `bonus`).")]
+        pub episode_type: Option<String>,
+
         #[graphql(description = "Whether the episode is considered explicit.")]
         pub explicit: Option<bool>,
 
+        #[graphql(description = "The episode's image URL (if it has artwork of its own).")]
+        pub image_url: Option<String>,
+
         #[graphql(description = "The episode's web link.")]
         pub link_url: Option<String>,
 
+        #[graphql(description = "The size of the episode's media in bytes as last probed.")]
+        pub media_content_length: Option<f64>,
+
+        #[graphql(description = "The content type of the episode's media as last probed.")]
+        pub media_content_type: Option<String>,
+
+        #[graphql(description = "When the episode's media was found to be gone (if it is).")]
+        pub media_gone_at: Option<DateTime<Utc>>,
+
+        #[graphql(description = "When the episode's media was last probed.")]
+        pub media_probed_at: Option<DateTime<Utc>>,
+
+        #[graphql(description = "The HTTP status returned when the media was last probed.")]
+        pub media_status: Option<i32>,
+
         #[graphql(description = "The episode's media link (i.e. where the audio can be found).")]
         pub media_url: String,
 
@@ -809,21 +1186,193 @@ mod resource {
         #[graphql(description = "The episode's publishing date and time.")]
         pub published_at: DateTime<Utc>,
 
+        #[graphql(description = "When the episode disappeared from its podcast's feed (if it has).")]
+        pub removed_at: Option<DateTime<Utc>>,
+
+        #[graphql(description = "The episode's season number.")]
+        pub season: Option<i32>,
+
         #[graphql(description = "The episode's title.")]
         pub title: String,
     }
 
-    impl<'a> From<&'a model::Episode> for Episode {
-        fn from(e: &model::Episode) -> Self {
+    impl Episode {
+        pub fn new(e: &model::Episode, chapters: &[model::EpisodeChapter]) -> Self {
             Episode {
-                id:           e.id.to_string(),
-                description:  e.description.clone(),
-                explicit:     e.explicit,
-                link_url:     e.link_url.clone(),
-                media_url:    e.media_url.to_owned(),
-                podcast_id:   e.podcast_id.to_string(),
-                published_at: e.published_at,
-                title:        e.title.to_owned(),
+                id:                   e.id.to_string(),
+                block:                e.block,
+                chapters:             chapters.iter().map(EpisodeChapter::from).collect(),
+                description:          e.description.clone(),
+                duration_seconds:     e.duration_seconds.map(|d| d as f64),
+                episode_number:       e.episode_number,
+                episode_type:         e.episode_type.clone(),
+                explicit:             e.explicit,
+                image_url:            e.image_url.clone(),
+                link_url:             e.link_url.clone(),
+                media_content_length: e.media_content_length.map(|l| l as f64),
+                media_content_type:   e.media_content_type.clone(),
+                media_gone_at:        e.media_gone_at,
+                media_probed_at:      e.media_probed_at,
+                media_status:         e.media_status,
+                media_url:            e.media_url.to_owned(),
+                podcast_id:           e.podcast_id.to_string(),
+                published_at:         e.published_at,
+                removed_at:           e.removed_at,
+                season:               e.season,
+                title:                e.title.to_owned(),
+            }
+        }
+    }
+
+    #[derive(GraphQLObject)]
+    pub struct EpisodeChapter {
+        #[graphql(description = "A link related to the chapter.")]
+        pub href: Option<String>,
+
+        #[graphql(description = "The chapter's image URL.")]
+        pub image_url: Option<String>,
+
+        #[graphql(description = "Where the chapter came from (`json` or `psc`).")]
+        pub source: String,
+
+        #[graphql(description = "The offset into the episode's media that the chapter starts at.")]
+        pub start_seconds: f64,
+
+        #[graphql(description = "The chapter's title.")]
+        pub title: String,
+    }
+
+    impl<'a> From<&'a model::EpisodeChapter> for EpisodeChapter {
+        fn from(c: &model::EpisodeChapter) -> Self {
+            EpisodeChapter {
+                href:          c.href.clone(),
+                image_url:     c.image_url.clone(),
+                source:        c.source.to_owned(),
+                start_seconds: c.start_seconds,
+                title:         c.title.to_owned(),
+            }
+        }
+    }
+
+    #[derive(GraphQLObject)]
+    pub struct EpisodeChaptersUrl {
+        #[graphql(description = "The episode's ID.")]
+        pub episode_id: String,
+
+        #[graphql(description = "The chapter file's media type.")]
+        pub media_type: Option<String>,
+
+        #[graphql(description = "The chapter file's URL.")]
+        pub url: String,
+    }
+
+    impl<'a> From<&'a model::EpisodeChaptersUrl> for EpisodeChaptersUrl {
+        fn from(c: &model::EpisodeChaptersUrl) -> Self {
+            EpisodeChaptersUrl {
+                episode_id: c.episode_id.to_string(),
+                media_type: c.media_type.clone(),
+                url:        c.url.to_owned(),
+            }
+        }
+    }
+
+    #[derive(GraphQLObject)]
+    pub struct EpisodeRevision {
+        #[graphql(description = "The revision's ID.")]
+        pub id: String,
+
+        #[graphql(description = "When the change was found.")]
+        pub created_at: DateTime<Utc>,
+
+        #[graphql(description = "The episode's ID.")]
+        pub episode_id: String,
+
+        #[graphql(description = "The field that changed (e.g. `media_url` or `title`).")]
+        pub field: String,
+
+        #[graphql(description = "The field's value after the change.")]
+        pub new_value: Option<String>,
+
+        #[graphql(description = "The field's value before the change.")]
+        pub old_value: Option<String>,
+    }
+
+    impl<'a> From<&'a model::EpisodeRevision> for EpisodeRevision {
+        fn from(r: &model::EpisodeRevision) -> Self {
+            EpisodeRevision {
+                id:         r.id.to_string(),
+                created_at: r.created_at,
+                episode_id: r.episode_id.to_string(),
+                field:      r.field.to_owned(),
+                new_value:  r.new_value.clone(),
+                old_value:  r.old_value.clone(),
+            }
+        }
+    }
+
+    #[derive(GraphQLObject)]
+    pub struct EpisodeTranscript {
+        #[graphql(description = "The episode's ID.")]
+        pub episode_id: String,
+
+        #[graphql(description = "The transcript's language.")]
+        pub language: Option<String>,
+
+        #[graphql(description = "The transcript's media type (e.g. `text/vtt`).")]
+        pub media_type: String,
+
+        #[graphql(description = "The transcript's relationship (e.g. `captions`).")]
+        pub rel: Option<String>,
+
+        #[graphql(description = "The transcript's URL.")]
+        pub url: String,
+    }
+
+    impl<'a> From<&'a model::EpisodeTranscript> for EpisodeTranscript {
+        fn from(t: &model::EpisodeTranscript) -> Self {
+            EpisodeTranscript {
+                episode_id: t.episode_id.to_string(),
+                language:   t.language.clone(),
+                media_type: t.media_type.to_owned(),
+                rel:        t.rel.clone(),
+                url:        t.url.to_owned(),
+            }
+        }
+    }
+
+    #[derive(GraphQLObject)]
+    pub struct OpmlImportFeed {
+        #[graphql(description = "The feed's URL as given in the OPML file.")]
+        pub feed_url: String,
+
+        #[graphql(description = "Why the feed couldn't be imported (if it couldn't).")]
+        pub message: Option<String>,
+
+        #[graphql(description = "The ID of the podcast that was subscribed to (if any yet).")]
+        pub podcast_id: Option<String>,
+
+        #[graphql(description = "The feed's status (`enqueued`, `invalid`, or `subscribed`).")]
+        pub status: String,
+
+        #[graphql(description = "The feed's title as given in the OPML file.")]
+        pub title: Option<String>,
+    }
+
+    impl<'a> From<&'a opml_importer::FeedResult> for OpmlImportFeed {
+        fn from(f: &opml_importer::FeedResult) -> Self {
+            let (status, message) = match f.status {
+                opml_importer::FeedStatus::Enqueued => ("enqueued", None),
+                opml_importer::FeedStatus::Invalid(ref message) => {
+                    ("invalid", Some(message.clone()))
+                }
+                opml_importer::FeedStatus::Subscribed => ("subscribed", None),
+            };
+            OpmlImportFeed {
+                feed_url: f.feed_url.to_owned(),
+                message,
+                podcast_id: f.podcast.as_ref().map(|p| p.id.to_string()),
+                status: status.to_owned(),
+                title: f.title.clone(),
             }
         }
     }
@@ -835,15 +1384,36 @@ mod resource {
         #[graphql(description = "The podcast's ID.")]
         pub id: String,
 
+        #[graphql(description = "The podcast's author.")]
+        pub author: Option<String>,
+
+        #[graphql(description = "Whether the podcast has asked to be hidden from directories.")]
+        pub block: Option<bool>,
+
+        #[graphql(description = "The podcast's globally unique identifier (`podcast:guid`).")]
+        pub guid: Option<String>,
+
         #[graphql(description = "The podcast's image URL.")]
         pub image_url: Option<String>,
 
+        #[graphql(description = "The podcast's iTunes artwork URL (`itunes:image`).")]
+        pub itunes_image_url: Option<String>,
+
         #[graphql(description = "The podcast's language.")]
         pub language: Option<String>,
 
         #[graphql(description = "The podcast's RSS link URL.")]
         pub link_url: Option<String>,
 
+        #[graphql(description = "Whether the podcast has asked not to be imported elsewhere.")]
+        pub locked: Option<bool>,
+
+        #[graphql(description = "The podcast owner's email.")]
+        pub owner_email: Option<String>,
+
+        #[graphql(description = "The podcast owner's name.")]
+        pub owner_name: Option<String>,
+
         #[graphql(description = "The podcast's title.")]
         pub title: String,
     }
@@ -851,11 +1421,78 @@ mod resource {
     impl<'a> From<&'a model::Podcast> for Podcast {
         fn from(p: &model::Podcast) -> Self {
             Podcast {
-                id:        p.id.to_string(),
-                image_url: p.image_url.clone(),
-                language:  p.language.clone(),
-                link_url:  p.link_url.clone(),
-                title:     p.title.to_owned(),
+                id:               p.id.to_string(),
+                author:           p.author.clone(),
+                block:            p.block,
+                guid:             p.guid.clone(),
+                image_url:        p.image_url.clone(),
+                itunes_image_url: p.itunes_image_url.clone(),
+                language:         p.language.clone(),
+                link_url:         p.link_url.clone(),
+                locked:           p.locked,
+                owner_email:      p.owner_email.clone(),
+                owner_name:       p.owner_name.clone(),
+                title:            p.title.to_owned(),
+            }
+        }
+    }
+
+    #[derive(GraphQLObject)]
+    pub struct PodcastFunding {
+        #[graphql(description = "The podcast's ID.")]
+        pub podcast_id: String,
+
+        #[graphql(description = "The funding link's call to action.")]
+        pub title: Option<String>,
+
+        #[graphql(description = "The funding link's URL.")]
+        pub url: String,
+    }
+
+    impl<'a> From<&'a model::PodcastFunding> for PodcastFunding {
+        fn from(f: &model::PodcastFunding) -> Self {
+            PodcastFunding {
+                podcast_id: f.podcast_id.to_string(),
+                title:      f.title.clone(),
+                url:        f.url.to_owned(),
+            }
+        }
+    }
+
+    #[derive(GraphQLObject)]
+    pub struct PodcastPerson {
+        #[graphql(description = "The ID of the episode that the person appears on (if any).")]
+        pub episode_id: Option<String>,
+
+        #[graphql(description = "The person's group (e.g. `cast`).")]
+        pub group_name: Option<String>,
+
+        #[graphql(description = "A link to more information about the person.")]
+        pub href: Option<String>,
+
+        #[graphql(description = "The person's image URL.")]
+        pub image_url: Option<String>,
+
+        #[graphql(description = "The person's name.")]
+        pub name: String,
+
+        #[graphql(description = "The podcast's ID.")]
+        pub podcast_id: String,
+
+        #[graphql(description = "The person's role (e.g. `host` or `guest`).")]
+        pub role: Option<String>,
+    }
+
+    impl<'a> From<&'a model::PodcastPerson> for PodcastPerson {
+        fn from(p: &model::PodcastPerson) -> Self {
+            PodcastPerson {
+                episode_id: p.episode_id.map(|id| id.to_string()),
+                group_name: p.group_name.clone(),
+                href:       p.href.clone(),
+                image_url:  p.image_url.clone(),
+                name:       p.name.to_owned(),
+                podcast_id: p.podcast_id.to_string(),
+                role:       p.role.clone(),
             }
         }
     }
@@ -6,6 +6,22 @@ use html5ever::parse_document;
 use html5ever::rcdom::{Handle, NodeData, RcDom};
 use html5ever::tendril::TendrilSink;
 
+/// Finds the URLs of feeds that an HTML document advertises with `<link
+/// rel="alternate">` tags (commonly known as RSS autodiscovery), in the order
+/// that they appear. URLs are returned as written, so they may be relative.
+pub fn feed_links(s: &str) -> Vec<String> {
+    let mut buf = BufReader::new(s.as_bytes());
+    let mut links = Vec::new();
+
+    let dom = parse_document(RcDom::default(), Default::default())
+        .from_utf8()
+        .read_from(&mut buf)
+        .unwrap();
+    walk_feed_links(dom.document, &mut links);
+
+    links
+}
+
 pub fn sanitize(s: &str) -> String {
     let mut buf = BufReader::new(s.as_bytes());
     let mut out = String::new();
@@ -26,6 +42,9 @@ pub fn sanitize(s: &str) -> String {
 // Private functions
 //
 
+// The media types of feeds that we'll pick up through autodiscovery.
+const FEED_TYPES: &[&str] = &["application/atom+xml", "application/rss+xml"];
+
 fn walk(handle: Handle, out: &mut String) {
     let node = handle;
     let mut close_tag: Option<String> = None;
@@ -123,10 +142,91 @@ fn walk(handle: Handle, out: &mut String) {
 // Private functions
 //
 
+fn walk_feed_links(handle: Handle, links: &mut Vec<String>) {
+    if let NodeData::Element {
+        ref name,
+        ref attrs,
+        ..
+    } = handle.data
+    {
+        if name.ns == ns!(html) && name.local.as_ref() == "link" {
+            let mut href: Option<String> = None;
+            let mut is_alternate = false;
+            let mut is_feed = false;
+
+            for attr in attrs.borrow().iter() {
+                if attr.name.ns != ns!() {
+                    continue;
+                }
+
+                let value: &str = attr.value.as_ref();
+                match attr.name.local.as_ref() {
+                    "href" => href = Some(value.trim().to_owned()),
+                    "rel" => {
+                        is_alternate = value
+                            .split_whitespace()
+                            .any(|rel| rel.eq_ignore_ascii_case("alternate"))
+                    }
+                    "type" => {
+                        is_feed = FEED_TYPES.contains(&value.trim().to_lowercase().as_str())
+                    }
+                    _ => (),
+                }
+            }
+
+            if let Some(href) = href {
+                if is_alternate && is_feed && !href.is_empty() {
+                    links.push(href);
+                }
+            }
+        }
+    }
+
+    for child in handle.children.borrow().iter() {
+        walk_feed_links(child.clone(), links);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use html::*;
 
+    #[test]
+    fn test_feed_links() {
+        // No links
+        assert_eq!(Vec::<String>::new(), feed_links("<p>x</p>"));
+
+        // RSS and Atom, in document order
+        assert_eq!(
+            vec!["/feed.xml".to_owned(), "https://example.com/atom.xml".to_owned()],
+            feed_links(
+                "<html><head>\
+                 <link rel=\"alternate\" type=\"application/rss+xml\" href=\"/feed.xml\">\
+                 <link rel=\"alternate\" type=\"application/atom+xml\" \
+                 href=\"https://example.com/atom.xml\">\
+                 </head></html>"
+            )
+        );
+
+        // Case and extra `rel` values don't matter
+        assert_eq!(
+            vec!["/feed.xml".to_owned()],
+            feed_links(
+                "<LINK REL=\"Alternate home\" TYPE=\"Application/RSS+XML\" HREF=\"/feed.xml\">"
+            )
+        );
+
+        // Links that aren't feeds
+        assert_eq!(
+            Vec::<String>::new(),
+            feed_links(
+                "<link rel=\"stylesheet\" type=\"text/css\" href=\"/main.css\">\
+                 <link rel=\"alternate\" type=\"text/html\" href=\"/fr/\">\
+                 <link rel=\"alternate\" type=\"application/rss+xml\">"
+            )
+        );
+    }
+
     #[test]
     fn test_sanitize() {
         // No HTML
@@ -4,10 +4,14 @@ use flate2::read::GzDecoder;
 use futures::Stream;
 use hyper::client::HttpConnector;
 use hyper::header::{qitem, AcceptEncoding, ContentEncoding, Encoding, Location, UserAgent};
-use hyper::{Body, Client, Request, StatusCode, Uri};
+use hyper::{Body, Chunk, Client, Headers, Request, StatusCode, Uri};
 use hyper_tls::HttpsConnector;
 use slog::Logger;
+use std::cell::Cell;
+use std::cmp;
+use std::io;
 use std::io::prelude::*;
+use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::Arc;
 use tokio_core::reactor::Core;
@@ -32,11 +36,17 @@ pub trait HttpRequesterFactory: Send {
 }
 
 #[derive(Clone, Debug)]
-pub struct HttpRequesterFactoryLive {}
+pub struct HttpRequesterFactoryLive {
+    /// The maximum size of response bodies for requesters that this factory
+    /// creates. See `HttpRequesterLive`.
+    pub max_body_size: usize,
+}
 
 impl HttpRequesterFactory for HttpRequesterFactoryLive {
     fn clone_box(&self) -> Box<HttpRequesterFactory> {
-        Box::new(Self {})
+        Box::new(Self {
+            max_body_size: self.max_body_size,
+        })
     }
 
     fn create(&self) -> Box<HttpRequester> {
@@ -44,7 +54,11 @@ impl HttpRequesterFactory for HttpRequesterFactoryLive {
         let client = Client::configure()
             .connector(HttpsConnector::new(4, &core.handle()).unwrap())
             .build(&core.handle());
-        Box::new(HttpRequesterLive { client, core })
+        Box::new(HttpRequesterLive {
+            client,
+            core,
+            max_body_size: self.max_body_size,
+        })
     }
 }
 
@@ -71,26 +85,183 @@ impl HttpRequesterFactory for HttpRequesterFactoryPassThrough {
 // HttpRequester trait + implementations
 //
 
+/// The default maximum size of a response body in bytes. Some feeds run to
+/// hundreds of megabytes, so we stop reading once a body gets bigger than
+/// this. Feeds are stored compressed, and `blob_store::MAX_CONTENT_ZSTD_SIZE`
+/// is the same size, so any feed that's read can also be stored. For that
+/// reason this is also the largest maximum that may be configured.
+pub const MAX_BODY_SIZE: usize = 50 * 1024 * 1024;
+
 // Maximum number of redirects that we'll follow.
 const REDIRECT_LIMIT: i64 = 5;
 
 pub trait HttpRequester {
-    fn execute(&mut self, log: &Logger, req: Request) -> Result<(StatusCode, Vec<u8>, String)>;
+    fn execute(&mut self, log: &Logger, req: Request) -> Result<Response>;
+
+    /// Like `execute`, but the response's body is handed back as a reader so
+    /// that it can be processed as it comes off the wire instead of being held
+    /// in memory in its entirety.
+    ///
+    /// By default this just wraps the body that `execute` read, which is fine
+    /// for requesters that don't talk to the network.
+    fn execute_streaming<'a>(
+        &'a mut self,
+        log: &Logger,
+        req: Request,
+    ) -> Result<StreamingResponse<'a>> {
+        let res = self.execute(log, req)?;
+        Ok(StreamingResponse {
+            body:               BodyReader::new(
+                res.final_url.as_str(),
+                io::Cursor::new(res.body),
+                false,
+                usize::MAX,
+            ),
+            final_url:          res.final_url,
+            headers:            res.headers,
+            last_url:           res.last_url,
+            permanent_redirect: res.permanent_redirect,
+            status:             res.status,
+        })
+    }
+}
+
+/// The result of executing an HTTP request through an `HttpRequester`.
+#[derive(Debug)]
+pub struct Response {
+    /// The response's body. If it was compressed on the wire, it's already
+    /// been decoded.
+    pub body: Vec<u8>,
+
+    /// The URL that should be used to make this request in the future. This is
+    /// usually the request's URL, but may be different if the request was
+    /// permanently redirected.
+    pub final_url: String,
+
+    /// Headers from the final response (i.e. after any redirects).
+    pub headers: Headers,
+
+    /// The URL that the response was actually served from after following
+    /// every redirect, permanent or temporary. Unlike `final_url`, this isn't
+    /// necessarily one that should be requested again.
+    pub last_url: String,
+
+    /// True if `final_url` was reached by following one or more permanent
+    /// redirects (301 or 308) and the resource should be considered to have
+    /// moved. Temporary redirects (302, 303, 307) never set this.
+    pub permanent_redirect: bool,
+
+    pub status: StatusCode,
+}
+
+/// The result of executing an HTTP request through
+/// `HttpRequester::execute_streaming`. This is the same as `Response` except
+/// that its body hasn't been read yet.
+pub struct StreamingResponse<'a> {
+    pub body: BodyReader<'a>,
+
+    /// See `Response`.
+    pub final_url: String,
+
+    pub headers: Headers,
+
+    /// See `Response`.
+    pub last_url: String,
+
+    /// See `Response`.
+    pub permanent_redirect: bool,
+
+    pub status: StatusCode,
+}
+
+/// A response body that's read as it comes off the wire and decoded along the
+/// way if it was compressed. Reading fails as soon as either the encoded or
+/// decoded body exceeds its maximum size.
+pub struct BodyReader<'a> {
+    inner: LimitedReader<Box<Read + 'a>>,
+    url:   String,
+}
+
+impl<'a> BodyReader<'a> {
+    fn new<R: Read + 'a>(url: &str, wire: R, gzipped: bool, max_size: usize) -> BodyReader<'a> {
+        // Both limits share a flag so that whichever one is hit can be reported
+        // the same way.
+        let exceeded = Rc::new(Cell::new(false));
+        let wire = LimitedReader {
+            exceeded: Rc::clone(&exceeded),
+            inner: wire,
+            max_size,
+            num_read: 0,
+        };
+        let decoded: Box<Read + 'a> = if gzipped {
+            Box::new(GzDecoder::new(wire))
+        } else {
+            Box::new(wire)
+        };
+        BodyReader {
+            inner: LimitedReader {
+                exceeded,
+                inner: decoded,
+                max_size,
+                num_read: 0,
+            },
+            url:   url.to_owned(),
+        }
+    }
+
+    /// Fails if reading was cut off because the body exceeded its maximum
+    /// size. Anything that consumes the body (like a parser) is likely to turn
+    /// the error that reading failed with into one of its own, so this should
+    /// be checked first whenever one of them fails.
+    pub fn check_size(&self) -> Result<()> {
+        if self.inner.exceeded.get() {
+            bail!(ErrorKind::BodyTooLarge(
+                self.url.clone(),
+                self.inner.max_size
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reads whatever's left of the body into memory.
+    pub fn read_all(&mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        let res = self.read_to_end(&mut data);
+        self.check_size()?;
+        res.chain_err(|| format!("Error reading body from URL: {}", self.url))?;
+        Ok(data)
+    }
+}
+
+impl<'a> Read for BodyReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
 }
 
 #[derive(Debug)]
 pub struct HttpRequesterLive {
     pub client: Client<HttpsConnector<HttpConnector>, Body>,
     pub core:   Core,
+
+    /// The maximum size of a response body in bytes. This applies both to the
+    /// body as it comes off the wire and to the body after it's been decoded,
+    /// so a small gzip payload that expands into something enormous is caught
+    /// as well. Exceeding it fails the request.
+    pub max_body_size: usize,
 }
 
 impl HttpRequesterLive {
-    fn execute_inner(
+    // Sends a request and follows any redirects, returning the final response
+    // without reading its body. Also returned are the URL that should be used to
+    // make the request in the future, the URL that the response came from, and
+    // whether it was reached through permanent redirects (see `Response`).
+    fn send(
         &mut self,
         log: &Logger,
         mut req: Request,
         redirect_depth: i64,
-    ) -> Result<(StatusCode, Vec<u8>, String)> {
+    ) -> Result<(::hyper::Response, String, String, bool)> {
         if redirect_depth >= REDIRECT_LIMIT {
             return Err(Error::from("Hit HTTP redirect limit and not continuing"));
         }
@@ -107,62 +278,103 @@ impl HttpRequesterLive {
         let method = req.method().clone();
         let uri = req.uri().to_string();
 
+        // Carry headers across redirects so that things like conditional GET
+        // validators aren't lost.
+        let headers = req.headers().clone();
+
         let res = self.core
             .run(self.client.request(req))
             .chain_err(|| format!("Error fetching feed URL: {}", uri))?;
         let status = res.status();
 
-        // Follow redirects.
-        if status.is_redirection() {
-            let new_uri = match res.headers().get::<Location>() {
-                Some(uri) => Uri::from_str(uri).map_err(Error::from),
-                None => Err(Error::from(
-                    "Received redirection without `Location` header",
-                )),
-            }?;
-
-            let new_req = Request::new(method, new_uri);
-            let (status, body, last_uri) = self.execute_inner(log, new_req, redirect_depth + 1)?;
-
-            // If we got a permanent redirect we return the final URI so that it can be
-            // persisted for next time we need to make this request. Otherwise,
-            // we return the original URI that came in with the request.
-            let uri = if status == StatusCode::PermanentRedirect {
-                last_uri
-            } else {
-                uri
-            };
+        // Follow redirects. Hyper considers any 3xx to be a redirection, but a
+        // 304 from a conditional GET, a 300, or a 305 don't point to a new
+        // location that we should go and fetch, so those are handed back to the
+        // caller like any other response. So are redirects that don't include a
+        // `Location` to follow.
+        let location = match status {
+            StatusCode::MovedPermanently
+            | StatusCode::Found
+            | StatusCode::SeeOther
+            | StatusCode::TemporaryRedirect
+            | StatusCode::PermanentRedirect => {
+                res.headers().get::<Location>().map(|l| l.to_string())
+            }
+            _ => None,
+        };
+
+        if let Some(location) = location {
+            let new_uri = Uri::from_str(&location)?;
+            let mut new_req = Request::new(method, new_uri);
+            *new_req.headers_mut() = headers;
+            let (res, final_url, last_url, _) = self.send(log, new_req, redirect_depth + 1)?;
 
-            return Ok((status, body, uri));
+            // If this hop was a permanent redirect we return the URI that the rest of
+            // the chain resolved to so that it can be persisted for next time we
+            // need to make this request. A temporary redirect anywhere in the chain
+            // means that everything before it stays put, so in that case we
+            // return the original URI that came in with the request.
+            if status == StatusCode::MovedPermanently || status == StatusCode::PermanentRedirect {
+                return Ok((res, final_url, last_url, true));
+            } else {
+                return Ok((res, uri, last_url, false));
+            }
         }
 
+        Ok((res, uri.clone(), uri, false))
+    }
+}
+
+impl HttpRequester for HttpRequesterLive {
+    fn execute(&mut self, log: &Logger, req: Request) -> Result<Response> {
+        let mut res = self.execute_streaming(log, req)?;
+        let body = res.body.read_all()?;
+        Ok(Response {
+            body,
+            final_url: res.final_url,
+            headers: res.headers,
+            last_url: res.last_url,
+            permanent_redirect: res.permanent_redirect,
+            status: res.status,
+        })
+    }
+
+    fn execute_streaming<'a>(
+        &'a mut self,
+        log: &Logger,
+        req: Request,
+    ) -> Result<StreamingResponse<'a>> {
+        let (res, final_url, last_url, permanent_redirect) = self.send(log, req, 0)?;
+        let status = res.status();
+
         let gzipped = match res.headers().get::<ContentEncoding>() {
             Some(e) => e.contains(&Encoding::Gzip),
             None => false,
         };
 
-        let body_chunk = self.core
-            .run(res.body().concat2())
-            .chain_err(|| format!("Error reading body from URL: {}", uri))?;
-
-        let mut body = (*body_chunk).to_vec();
+        let headers = res.headers().clone();
         if gzipped {
-            info!(log, "Decoding gzip-encoded body"; "body_length" => body.len());
-            let mut body_decoded: Vec<u8> = Vec::new();
-            {
-                let mut decoder = GzDecoder::new(body.as_slice());
-                decoder.read_to_end(&mut body_decoded)?;
-            }
-            body = body_decoded;
+            info!(log, "Decoding gzip-encoded body");
         }
 
-        Ok((status, body, uri))
-    }
-}
+        // The body is read a chunk at a time as the caller asks for it so that
+        // we can give up as soon as it gets too big. Gzip is decoded as chunks
+        // come in so that we never hold the encoded body in memory either.
+        let chunks = ChunkReader {
+            chunk:  Chunk::from(Vec::new()),
+            core:   &mut self.core,
+            pos:    0,
+            stream: Some(res.body()),
+        };
 
-impl HttpRequester for HttpRequesterLive {
-    fn execute(&mut self, log: &Logger, req: Request) -> Result<(StatusCode, Vec<u8>, String)> {
-        self.execute_inner(log, req, 0)
+        Ok(StreamingResponse {
+            body: BodyReader::new(final_url.as_str(), chunks, gzipped, self.max_body_size),
+            final_url,
+            headers,
+            last_url,
+            permanent_redirect,
+            status,
+        })
     }
 }
 
@@ -172,8 +384,201 @@ pub struct HttpRequesterPassThrough {
 }
 
 impl HttpRequester for HttpRequesterPassThrough {
-    fn execute(&mut self, _log: &Logger, req: Request) -> Result<(StatusCode, Vec<u8>, String)> {
-        let uri = req.uri().to_string();
-        Ok((StatusCode::Ok, (*self.data).clone(), uri))
+    fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+        Ok(Response {
+            body:               (*self.data).clone(),
+            final_url:          req.uri().to_string(),
+            headers:            Headers::new(),
+            last_url:           req.uri().to_string(),
+            permanent_redirect: false,
+            status:             StatusCode::Ok,
+        })
+    }
+}
+
+//
+// Private types
+//
+
+// Reads a body as it comes off the wire by running the event loop until each
+// of its chunks arrives.
+struct ChunkReader<'a> {
+    chunk:  Chunk,
+    core:   &'a mut Core,
+    pos:    usize,
+    stream: Option<Body>,
+}
+
+impl<'a> Read for ChunkReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.pos < self.chunk.len() {
+                let num_read = cmp::min(buf.len(), self.chunk.len() - self.pos);
+                buf[..num_read].copy_from_slice(&self.chunk[self.pos..self.pos + num_read]);
+                self.pos += num_read;
+                return Ok(num_read);
+            }
+
+            let stream = match self.stream.take() {
+                Some(stream) => stream,
+                None => return Ok(0),
+            };
+            match self.core.run(stream.into_future()) {
+                Ok((Some(chunk), rest)) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                    self.stream = Some(rest);
+                }
+                Ok((None, _)) => return Ok(0),
+                Err((e, _)) => return Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
+            }
+        }
+    }
+}
+
+// A reader that fails once more than a maximum number of bytes have been read
+// through it.
+struct LimitedReader<R: Read> {
+    exceeded: Rc<Cell<bool>>,
+    inner:    R,
+    max_size: usize,
+    num_read: usize,
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let num_read = self.inner.read(buf)?;
+        self.num_read += num_read;
+        if self.num_read > self.max_size {
+            self.exceeded.set(true);
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Body exceeded maximum size",
+            ));
+        }
+        Ok(num_read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http_requester::*;
+
+    use test_helpers;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use hyper::Method;
+    use std::io::BufReader;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_http_requester_not_modified() {
+        let url = serve_once(
+            "HTTP/1.1 304 Not Modified\r\n\
+             ETag: \"abc\"\r\n\
+             Connection: close\r\n\
+             \r\n",
+        );
+
+        let mut requester = HttpRequesterFactoryLive {
+            max_body_size: MAX_BODY_SIZE,
+        }.create();
+        let res = requester
+            .execute(
+                &test_helpers::log(),
+                Request::new(Method::Get, Uri::from_str(&url).unwrap()),
+            )
+            .unwrap();
+
+        assert_eq!(StatusCode::NotModified, res.status);
+        assert_eq!(url, res.final_url);
+        assert!(res.body.is_empty());
+        assert!(!res.permanent_redirect);
+    }
+
+    #[test]
+    fn test_http_requester_body_reader() {
+        let mut body = BodyReader::new(
+            "https://example.com/feed.xml",
+            &b"hello feed"[..],
+            false,
+            10,
+        );
+        assert_eq!(b"hello feed".to_vec(), body.read_all().unwrap());
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello feed").unwrap();
+        let encoded = encoder.finish().unwrap();
+
+        let mut body = BodyReader::new(
+            "https://example.com/feed.xml",
+            encoded.as_slice(),
+            true,
+            100,
+        );
+        assert_eq!(b"hello feed".to_vec(), body.read_all().unwrap());
+    }
+
+    #[test]
+    fn test_http_requester_body_reader_too_large() {
+        let mut body = BodyReader::new(
+            "https://example.com/feed.xml",
+            &b"hello feed!"[..],
+            false,
+            10,
+        );
+        let e = body.read_all().err().unwrap();
+        assert_eq!(
+            "Response body from https://example.com/feed.xml exceeded maximum size of 10 bytes",
+            e.to_string()
+        );
+
+        // A small gzip payload that decodes to something too large is caught as
+        // well.
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&[b'a'; 1000]).unwrap();
+        let encoded = encoder.finish().unwrap();
+        assert!(encoded.len() < 100);
+
+        let mut body = BodyReader::new(
+            "https://example.com/feed.xml",
+            encoded.as_slice(),
+            true,
+            100,
+        );
+        match body.read_all().err().unwrap().kind() {
+            &ErrorKind::BodyTooLarge(_, 100) => (),
+            e => panic!("Unexpected error: {}", e),
+        }
+    }
+
+    //
+    // Private functions
+    //
+
+    // Starts a server on a local port that answers a single request with the
+    // given raw HTTP response, and returns a URL that points to it.
+    fn serve_once(response: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/feed.xml", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            // Read up to the blank line that terminates the request's headers.
+            {
+                let mut reader = BufReader::new(&mut stream);
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+            }
+
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        url
     }
 }
@@ -25,6 +25,374 @@ macro_rules! enqueue {
         }
     };
 }
+
+pub mod episode_chapters_fetcher {
+    use errors::*;
+    use http_requester::HttpRequester;
+    use mediators::episode_chapters_fetcher;
+    use model;
+
+    use diesel::pg::PgConnection;
+    use r2d2::Pool;
+    use r2d2_diesel::ConnectionManager;
+    use slog::Logger;
+
+    //
+    // Public constants
+    //
+
+    pub const NAME: &str = "episode_chapters_fetcher";
+
+    //
+    // Public types
+    //
+
+    /// Fetches a JSON chapters file linked from an episode. These are
+    /// retrieved in the background so that a podcast update doesn't have to
+    /// make a request for every one of its episodes.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Args {
+        pub episode_id: i64,
+        pub url:        String,
+    }
+
+    pub struct Job<'a> {
+        pub args:      Args,
+        pub pool:      &'a Pool<ConnectionManager<PgConnection>>,
+        pub requester: &'a mut HttpRequester,
+    }
+
+    impl<'a> Job<'a> {
+        pub fn run(&mut self, log: &Logger) -> Result<()> {
+            let conn = self.pool.get()?;
+            episode_chapters_fetcher::Mediator {
+                conn:           &*conn,
+                episode_id:     self.args.episode_id,
+                http_requester: &mut *self.requester,
+                url:            self.args.url.clone(),
+            }.run(log)?;
+            Ok(())
+        }
+    }
+
+    //
+    // Public functions
+    //
+
+    enqueue!();
+}
+
+pub mod episode_media_prober {
+    use errors::*;
+    use http_requester::HttpRequester;
+    use mediators::episode_media_prober;
+    use model;
+
+    use diesel::pg::PgConnection;
+    use r2d2::Pool;
+    use r2d2_diesel::ConnectionManager;
+    use slog::Logger;
+
+    //
+    // Public constants
+    //
+
+    pub const NAME: &str = "episode_media_prober";
+
+    //
+    // Public types
+    //
+
+    /// Probes an episode's media to check that it still exists. These are
+    /// enqueued for new episodes (and ones whose media has moved) by the
+    /// podcast updater.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Args {
+        pub episode_id: i64,
+    }
+
+    pub struct Job<'a> {
+        pub args:      Args,
+        pub pool:      &'a Pool<ConnectionManager<PgConnection>>,
+        pub requester: &'a mut HttpRequester,
+    }
+
+    impl<'a> Job<'a> {
+        pub fn run(&mut self, log: &Logger) -> Result<()> {
+            let conn = self.pool.get()?;
+            episode_media_prober::Mediator {
+                conn:           &*conn,
+                episode_id:     self.args.episode_id,
+                http_requester: &mut *self.requester,
+            }.run(log)?;
+            Ok(())
+        }
+    }
+
+    //
+    // Public functions
+    //
+
+    enqueue!();
+}
+
+pub mod feed_subscriber {
+    use errors::*;
+    use http_requester::HttpRequester;
+    use mediators::account_podcast_subscriber;
+    use mediators::podcast_updater;
+    use model;
+    use schema;
+    use time_helpers;
+
+    use diesel::pg::PgConnection;
+    use diesel::prelude::*;
+    use r2d2::Pool;
+    use r2d2_diesel::ConnectionManager;
+    use slog::Logger;
+
+    //
+    // Public constants
+    //
+
+    pub const NAME: &str = "feed_subscriber";
+
+    //
+    // Public types
+    //
+
+    /// Retrieves a feed that we haven't seen before and subscribes an account
+    /// to its podcast. This is used when importing subscriptions for feeds
+    /// that would take too long to retrieve all at once.
+    ///
+    /// A feed that can't be retrieved fails the job, which gets it retried
+    /// with backoff by the job worker and the error recorded against it. The
+    /// account isn't subscribed to anything in the meantime.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Args {
+        pub account_id: i64,
+        pub feed_url:   String,
+    }
+
+    pub struct Job<'a> {
+        pub args:      Args,
+        pub pool:      &'a Pool<ConnectionManager<PgConnection>>,
+        pub requester: &'a mut HttpRequester,
+    }
+
+    impl<'a> Job<'a> {
+        pub fn run(&mut self, log: &Logger) -> Result<()> {
+            let conn = self.pool.get()?;
+            subscribe(log, &*conn, &mut *self.requester, &self.args)
+        }
+    }
+
+    //
+    // Public functions
+    //
+
+    enqueue!();
+
+    //
+    // Private functions
+    //
+
+    fn subscribe(
+        log: &Logger,
+        conn: &PgConnection,
+        requester: &mut HttpRequester,
+        args: &Args,
+    ) -> Result<()> {
+        let account = select_account(log, conn, args.account_id)?;
+
+        let res = podcast_updater::Mediator {
+            conn,
+            disable_shortcut: false,
+            enqueue_jobs: true,
+            feed_url: args.feed_url.clone(),
+            follow_pages: false,
+            http_requester: requester,
+        }.run(log)
+            .chain_err(|| format!("Error retrieving imported feed: {}", args.feed_url))?;
+
+        account_podcast_subscriber::Mediator {
+            account:    &account,
+            conn,
+            podcast:    &res.podcast,
+            subscribed: true,
+        }.run(log)?;
+        Ok(())
+    }
+
+    fn select_account(
+        log: &Logger,
+        conn: &PgConnection,
+        account_id: i64,
+    ) -> Result<model::Account> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_account")), |_log| {
+            schema::account::table
+                .filter(schema::account::id.eq(account_id))
+                .first(conn)
+                .chain_err(|| "Error selecting account")
+        })
+    }
+
+    //
+    // Tests
+    //
+
+    #[cfg(test)]
+    mod tests {
+        use http_requester::{HttpRequesterPassThrough, Response};
+        use jobs::feed_subscriber::*;
+        use test_data;
+        use test_helpers;
+
+        use hyper::{Headers, Request, StatusCode};
+        use r2d2::{Pool, PooledConnection};
+        use r2d2_diesel::ConnectionManager;
+        use std::sync::Arc;
+
+        #[test]
+        fn test_job_feed_subscriber_subscribe() {
+            let _common = test_helpers::CommonTestBootstrap::new();
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+            let account = test_data::account::insert(&log, &*conn);
+
+            subscribe(
+                &log,
+                &*conn,
+                &mut HttpRequesterPassThrough {
+                    data: Arc::new(test_helpers::MINIMAL_FEED.to_vec()),
+                },
+                &Args {
+                    account_id: account.id,
+                    feed_url:   "https://example.com/feed.xml".to_owned(),
+                },
+            ).unwrap();
+
+            let account_podcasts: Vec<model::AccountPodcast> = schema::account_podcast::table
+                .filter(schema::account_podcast::account_id.eq(account.id))
+                .load(&*conn)
+                .unwrap();
+            assert_eq!(1, account_podcasts.len());
+        }
+
+        #[test]
+        fn test_job_feed_subscriber_subscribe_fetch_error() {
+            let _common = test_helpers::CommonTestBootstrap::new();
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+            let account = test_data::account::insert(&log, &*conn);
+
+            let e = subscribe(
+                &log,
+                &*conn,
+                &mut HttpRequesterNotFound {},
+                &Args {
+                    account_id: account.id,
+                    feed_url:   "https://example.com/feed.xml".to_owned(),
+                },
+            ).err()
+                .unwrap();
+
+            // The error names the feed so that it's clear from the job's exception
+            // which one of an import's feeds was the problem.
+            assert_eq!(
+                "Error retrieving imported feed: https://example.com/feed.xml",
+                e.to_string()
+            );
+
+            assert_eq!(
+                Ok(0),
+                schema::account_podcast::table
+                    .filter(schema::account_podcast::account_id.eq(account.id))
+                    .count()
+                    .first(&*conn)
+            );
+        }
+
+        // Ignored because the job commits to the database with a connection of its
+        // own, like the other tests that use a pool.
+        #[ignore]
+        #[test]
+        fn test_job_feed_subscriber_run() {
+            let mut bootstrap = TestBootstrap::new();
+            let account = test_data::account::insert(&bootstrap.log, &*bootstrap.conn);
+
+            {
+                let log = bootstrap.log.clone();
+                let mut job = Job {
+                    args:      Args {
+                        account_id: account.id,
+                        feed_url:   "https://example.com/feed.xml".to_owned(),
+                    },
+                    pool:      &bootstrap.pool,
+                    requester: &mut bootstrap.requester,
+                };
+                job.run(&log).unwrap();
+            }
+
+            let account_podcasts: Vec<model::AccountPodcast> = schema::account_podcast::table
+                .filter(schema::account_podcast::account_id.eq(account.id))
+                .load(&*bootstrap.conn)
+                .unwrap();
+            assert_eq!(1, account_podcasts.len());
+        }
+
+        //
+        // Private types/functions
+        //
+
+        // An `HttpRequester` for a feed that's gone.
+        struct HttpRequesterNotFound {}
+
+        impl HttpRequester for HttpRequesterNotFound {
+            fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+                Ok(Response {
+                    body:               Vec::new(),
+                    final_url:          req.uri().to_string(),
+                    headers:            Headers::new(),
+                    last_url:           req.uri().to_string(),
+                    permanent_redirect: false,
+                    status:             StatusCode::NotFound,
+                })
+            }
+        }
+
+        struct TestBootstrap {
+            _common:   test_helpers::CommonTestBootstrap,
+            conn:      PooledConnection<ConnectionManager<PgConnection>>,
+            log:       Logger,
+            pool:      Pool<ConnectionManager<PgConnection>>,
+            requester: HttpRequesterPassThrough,
+        }
+
+        impl TestBootstrap {
+            fn new() -> Self {
+                let pool = test_helpers::pool();
+                let conn = pool.get().map_err(Error::from).unwrap();
+                TestBootstrap {
+                    _common:   test_helpers::CommonTestBootstrap::new(),
+                    conn:      conn,
+                    log:       test_helpers::log_sync(),
+                    pool:      pool,
+                    requester: HttpRequesterPassThrough {
+                        data: Arc::new(test_helpers::MINIMAL_FEED.to_vec()),
+                    },
+                }
+            }
+        }
+
+        impl Drop for TestBootstrap {
+            fn drop(&mut self) {
+                test_helpers::clean_database(&self.log, &*self.conn);
+            }
+        }
+    }
+}
+
 pub mod no_op {
     use errors::*;
 
@@ -227,3 +595,65 @@ pub mod verification_mailer {
         }
     }
 }
+
+pub mod websub_subscriber {
+    use errors::*;
+    use http_requester::HttpRequester;
+    use mediators::websub_subscriber;
+    use model;
+
+    use diesel::pg::PgConnection;
+    use r2d2::Pool;
+    use r2d2_diesel::ConnectionManager;
+    use slog::Logger;
+
+    //
+    // Public constants
+    //
+
+    pub const NAME: &str = "websub_subscriber";
+
+    //
+    // Public types
+    //
+
+    /// Asks a WebSub hub for a subscription to a podcast's feed. These are
+    /// enqueued by the podcast updater when it finds a feed advertising a hub
+    /// and when an existing subscription is close to expiring.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Args {
+        pub subscription_id: i64,
+    }
+
+    pub struct Job<'a> {
+        pub args:       Args,
+        pub pool:       &'a Pool<ConnectionManager<PgConnection>>,
+        pub public_url: Option<&'a str>,
+        pub requester:  &'a mut HttpRequester,
+    }
+
+    impl<'a> Job<'a> {
+        pub fn run(&mut self, log: &Logger) -> Result<()> {
+            // Hubs need a callback URL that they can reach, which we can't make up.
+            let public_url = match self.public_url {
+                Some(url) => url,
+                None => bail!("A public URL is needed to subscribe to WebSub hubs"),
+            };
+
+            let conn = self.pool.get()?;
+            websub_subscriber::Mediator {
+                conn:            &*conn,
+                http_requester:  &mut *self.requester,
+                public_url:      public_url.to_owned(),
+                subscription_id: self.args.subscription_id,
+            }.run(log)?;
+            Ok(())
+        }
+    }
+
+    //
+    // Public functions
+    //
+
+    enqueue!();
+}
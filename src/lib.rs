@@ -13,6 +13,8 @@ extern crate crypto;
 #[macro_use]
 extern crate diesel;
 
+extern crate encoding_rs;
+
 #[macro_use]
 extern crate error_chain;
 
@@ -62,9 +64,13 @@ extern crate time;
 extern crate tokio_core;
 extern crate url;
 extern crate uuid;
+extern crate zstd;
 
 pub mod api;
+mod blob_store;
+mod crawl_scheduler;
 pub mod database_helpers;
+mod date_parser;
 pub mod error_helpers;
 pub mod errors;
 
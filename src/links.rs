@@ -1,6 +1,10 @@
 use errors::*;
 use model;
 
+pub fn link_category(category: &model::Category) -> String {
+    format!("/categories/{}", category.slug).to_owned()
+}
+
 pub fn link_directory_podcast(dir_podcast: &model::DirectoryPodcast) -> String {
     format!(
         "/directory-podcasts/{}",
@@ -20,6 +24,27 @@ pub fn link_podcast(podcast: &model::Podcast) -> String {
     format!("/podcasts/{}", slug_id(podcast.id, &podcast.title)).to_owned()
 }
 
+/// The path at which a WebSub hub calls us back about a subscription.
+pub fn link_websub_subscription(subscription: &model::PodcastWebsubSubscription) -> String {
+    format!("/websub/{}", subscription.id).to_owned()
+}
+
+/// Produces a slug for a category name like `society-and-culture` for
+/// "Society & Culture".
+///
+/// Unlike other resources, categories are identified by their slug alone (and
+/// feeds refer to them by name), so this doesn't try to shorten anything. It
+/// should produce the same result for a name regardless of its capitalization
+/// or whether it uses "&" or "and".
+pub fn slug_category(name: &str) -> String {
+    name.to_lowercase()
+        .replace('&', " and ")
+        .split(|c| !char::is_alphanumeric(c))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join(SLUG_SEPARATOR)
+}
+
 /// "Unslugs" an ID by extracting any digits found in the beginning of a string
 /// and discarding the rest.
 ///
@@ -138,15 +163,30 @@ fn slug_id(id: i64, title: &str) -> String {
 #[cfg(test)]
 mod test {
     use links::*;
+    use schema;
     use test_data;
     use test_helpers;
 
     use diesel::pg::PgConnection;
+    use diesel::prelude::*;
     use r2d2::PooledConnection;
     use r2d2_diesel::ConnectionManager;
     use slog::Logger;
     use std;
 
+    #[test]
+    fn test_links_link_category() {
+        let bootstrap = TestBootstrap::new();
+        let category: model::Category = schema::category::table
+            .filter(schema::category::slug.eq("society-and-culture"))
+            .first(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(
+            "/categories/society-and-culture",
+            link_category(&category).as_str()
+        );
+    }
+
     #[test]
     fn test_links_link_directory_podcast() {
         let bootstrap = TestBootstrap::new();
@@ -232,6 +272,22 @@ mod test {
         assert_eq!(SLUG_MAX_LENGTH, slug(&unbroken_long_str).unwrap().len());
     }
 
+    #[test]
+    fn test_links_slug_category() {
+        assert_eq!("arts", slug_category("Arts").as_str());
+        assert_eq!(
+            "society-and-culture",
+            slug_category("Society & Culture").as_str()
+        );
+        assert_eq!(
+            "society-and-culture",
+            slug_category("society and culture").as_str()
+        );
+        assert_eq!("tv-and-film", slug_category("TV&Film").as_str());
+        assert_eq!("stand-up", slug_category("Stand-Up").as_str());
+        assert_eq!("", slug_category("  ").as_str());
+    }
+
     #[test]
     fn test_links_slug_id() {
         assert_eq!("123-hello-world", slug_id(123, "hello, world").as_str());
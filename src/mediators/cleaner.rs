@@ -31,6 +31,17 @@ impl Mediator {
                 .map_err(Error::from)?
         };
 
+        let content_blob_thread = {
+            let thread_name = "content_blob_cleaner".to_owned();
+            let log = log.new(o!("thread" => thread_name.clone()));
+            let pool_clone = self.pool.clone();
+
+            thread::Builder::new()
+                .name(thread_name)
+                .spawn(move || work(&log, &pool_clone, &delete_content_blob_batch))
+                .map_err(Error::from)?
+        };
+
         let directory_podcast_thread = {
             let thread_name = "directory_podcast_cleaner".to_owned();
             let log = log.new(o!("thread" => thread_name.clone()));
@@ -81,6 +92,7 @@ impl Mediator {
         // return a `Result<_>` which may contain an error that we've set which
         // is what the `?` is checking for.
         let num_account_cleaned = account_thread.join().unwrap()?;
+        let num_content_blob_cleaned = content_blob_thread.join().unwrap()?;
         let num_directory_podcast_cleaned = directory_podcast_thread.join().unwrap()?;
         let num_directory_search_cleaned = directory_search_thread.join().unwrap()?;
         let num_key_cleaned = key_thread.join().unwrap()?;
@@ -88,11 +100,12 @@ impl Mediator {
 
         Ok(RunResult {
             // total number of cleaned resources
-            num_cleaned: num_account_cleaned + num_directory_podcast_cleaned
-                + num_directory_search_cleaned + num_key_cleaned
-                + num_podcast_feed_content_cleaned,
+            num_cleaned: num_account_cleaned + num_content_blob_cleaned
+                + num_directory_podcast_cleaned + num_directory_search_cleaned
+                + num_key_cleaned + num_podcast_feed_content_cleaned,
 
             num_account_cleaned,
+            num_content_blob_cleaned,
             num_directory_podcast_cleaned,
             num_directory_search_cleaned,
             num_key_cleaned,
@@ -106,6 +119,7 @@ pub struct RunResult {
     pub num_cleaned: i64,
 
     pub num_account_cleaned:              i64,
+    pub num_content_blob_cleaned:         i64,
     pub num_directory_podcast_cleaned:    i64,
     pub num_directory_search_cleaned:     i64,
     pub num_key_cleaned:                  i64,
@@ -119,6 +133,12 @@ pub struct RunResult {
 // Target horizon beyond which we start to remove ephemeral accounts.
 static ACCOUNT_DELETE_HORIZON: &'static str = "1 month";
 
+// Target horizon beyond which we start to remove content blobs that are no
+// longer referenced by any podcast feed content. Blobs are only removed some
+// time after they were last used so that we don't race a podcast update that's
+// about to reference a blob again.
+static CONTENT_BLOB_DELETE_HORIZON: &'static str = "1 day";
+
 // The maximum number of objects to try and delete as part of one batch. It's a
 // good idea to constrain batch sizes so that we don't have any queries in the
 // system that are too long-lived and affect replication and other critical
@@ -170,6 +190,19 @@ fn delete_account_batch(log: &Logger, conn: &PgConnection) -> Result<DeleteResul
     )
 }
 
+fn delete_content_blob_batch(log: &Logger, conn: &PgConnection) -> Result<DeleteResults> {
+    time_helpers::log_timed(
+        &log.new(o!("step" => "delete_content_blob_batch", "limit" => DELETE_LIMIT)),
+        |_log| {
+            diesel::sql_query(include_str!("../static/sql/cleaner_content_blob.sql"))
+                .bind::<Text, _>(CONTENT_BLOB_DELETE_HORIZON)
+                .bind::<BigInt, _>(DELETE_LIMIT)
+                .get_result::<DeleteResults>(conn)
+                .chain_err(|| "Error deleting content blob batch")
+        },
+    )
+}
+
 fn delete_directory_podcast_batch(log: &Logger, conn: &PgConnection) -> Result<DeleteResults> {
     time_helpers::log_timed(
         &log.new(o!("step" => "delete_directory_podcast_batch", "limit" => DELETE_LIMIT)),
@@ -256,6 +289,7 @@ fn work(
 mod tests {
     extern crate rand;
 
+    use blob_store;
     use mediators::cleaner::*;
     use model;
     use model::insertable;
@@ -264,12 +298,9 @@ mod tests {
     use test_helpers;
 
     use chrono::Utc;
-    use flate2::write::GzEncoder;
-    use flate2::Compression;
     use r2d2::PooledConnection;
     use rand::distributions::Alphanumeric;
     use rand::Rng;
-    use std::io::prelude::*;
     use std::iter;
     use time::Duration;
 
@@ -365,6 +396,47 @@ mod tests {
         assert_eq!(0, res.num_cleaned);
     }
 
+    #[test]
+    #[ignore]
+    fn test_clean_content_blob_cleans() {
+        let mut bootstrap = TestBootstrap::new();
+
+        // A blob that nothing references and that hasn't been used in a while.
+        let sha256_hash = "a".repeat(64);
+        blob_store::store(&bootstrap.log, &*bootstrap.conn, sha256_hash.as_str(), b"feed body")
+            .unwrap();
+        diesel::update(schema::content_blob::table)
+            .filter(schema::content_blob::sha256_hash.eq(sha256_hash.as_str()))
+            .set(schema::content_blob::last_used_at.eq(Utc::now() - Duration::weeks(1)))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        assert_eq!(1, res.num_content_blob_cleaned);
+        assert_eq!(1, res.num_cleaned);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_clean_content_blob_ignores() {
+        let mut bootstrap = TestBootstrap::new();
+
+        // A podcast's content blob is still referenced.
+        let _podcast = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+
+        // And an orphaned blob was used too recently to be removed.
+        blob_store::store(&bootstrap.log, &*bootstrap.conn, "a".repeat(64).as_str(), b"feed body")
+            .unwrap();
+
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        assert_eq!(0, res.num_content_blob_cleaned);
+        assert_eq!(0, res.num_cleaned);
+    }
+
     #[test]
     #[ignore]
     fn test_clean_directory_podcast_cleans() {
@@ -653,15 +725,11 @@ mod tests {
             .unwrap()
     }
 
-    fn insert_podcast_feed_content(_log: &Logger, conn: &PgConnection, podcast: &model::Podcast) {
-        let body = "feed body".to_owned();
+    fn insert_podcast_feed_content(log: &Logger, conn: &PgConnection, podcast: &model::Podcast) {
         let mut rng = rand::thread_rng();
 
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write(body.as_bytes()).unwrap();
-
         let content_ins = insertable::PodcastFeedContent {
-            content_gzip: encoder.finish().unwrap(),
+            page_number:  1,
             podcast_id:   podcast.id,
             retrieved_at: Utc::now(),
 
@@ -673,6 +741,8 @@ mod tests {
                 .collect(),
         };
 
+        blob_store::store(log, conn, content_ins.sha256_hash.as_str(), b"feed body").unwrap();
+
         diesel::insert_into(schema::podcast_feed_content::table)
             .values(&content_ins)
             .execute(conn)
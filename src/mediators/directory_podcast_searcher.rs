@@ -87,28 +87,26 @@ impl<'a> Mediator<'a> {
             .finish();
         info!(log, "Encoded query"; "query" => encoded.clone());
 
-        let (status, body, _final_url) =
-            time_helpers::log_timed(&log.new(o!("step" => "fetch_results")), |log| {
-                self.http_requester.execute(
-                    log,
-                    Request::new(
-                        Method::Get,
-                        Uri::from_str(
-                            format!("https://itunes.apple.com/search?{}", encoded).as_str(),
-                        ).map_err(Error::from)?,
-                    ),
-                )
-            })?;
-        common::log_body_sample(log, status, &body);
-
-        if status != StatusCode::Ok {
+        let res = time_helpers::log_timed(&log.new(o!("step" => "fetch_results")), |log| {
+            self.http_requester.execute(
+                log,
+                Request::new(
+                    Method::Get,
+                    Uri::from_str(format!("https://itunes.apple.com/search?{}", encoded).as_str())
+                        .map_err(Error::from)?,
+                ),
+            )
+        })?;
+        common::log_body_sample(log, res.status, &res.body);
+
+        if res.status != StatusCode::Ok {
             bail!(
                 "Unexpected status while fetching search results: {}",
-                status
+                res.status
             )
         }
 
-        Ok(body)
+        Ok(res.body)
     }
 
     fn insert_directory_search(
@@ -31,7 +31,9 @@ impl<'a> Mediator<'a> {
         let res = podcast_updater::Mediator {
             conn:             self.conn,
             disable_shortcut: false,
+            enqueue_jobs:     true,
             feed_url:         self.dir_podcast.feed_url.clone(),
+            follow_pages:     false,
             http_requester:   self.http_requester,
         }.run(log);
 
@@ -0,0 +1,269 @@
+use errors::*;
+use http_requester::HttpRequester;
+use mediators::common;
+use model;
+use model::insertable;
+use schema;
+use time_helpers;
+
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use hyper::{Method, Request, StatusCode, Uri};
+use serde_json;
+use slog::Logger;
+use std::str::FromStr;
+
+/// Fetches a JSON chapters file (see the Podcasting 2.0 namespace) linked from
+/// an episode through `podcast:chapters` and stores its chapters. Chapters
+/// previously fetched for the episode are replaced.
+pub struct Mediator<'a> {
+    pub conn:           &'a PgConnection,
+    pub episode_id:     i64,
+    pub http_requester: &'a mut HttpRequester,
+
+    /// The URL of the chapters file.
+    pub url: String,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        // The request happens outside of the transaction so that we're not holding
+        // one open while waiting on a remote host.
+        let body = self.fetch_chapters(log)?;
+
+        let file: json_chapters::File =
+            serde_json::from_slice(body.as_slice()).chain_err(|| "Error parsing chapters file")?;
+        let chapters_ins: Vec<insertable::EpisodeChapter> = file.chapters
+            .iter()
+            .filter_map(|c| validate_chapter(c, self.episode_id))
+            .collect();
+
+        let chapters = self.conn
+            .transaction::<_, Error, _>(|| self.replace_chapters(log, &chapters_ins))?;
+        Ok(RunResult { chapters })
+    }
+
+    //
+    // Steps
+    //
+
+    fn fetch_chapters(&mut self, log: &Logger) -> Result<Vec<u8>> {
+        let res = time_helpers::log_timed(&log.new(o!("step" => "fetch_chapters")), |log| {
+            self.http_requester.execute(
+                log,
+                Request::new(
+                    Method::Get,
+                    Uri::from_str(self.url.as_str()).map_err(Error::from)?,
+                ),
+            )
+        })?;
+        common::log_body_sample(log, res.status, &res.body);
+
+        if res.status != StatusCode::Ok {
+            bail!(
+                "Error fetching chapters file. Host responded with status: {}",
+                res.status
+            )
+        }
+
+        Ok(res.body)
+    }
+
+    fn replace_chapters(
+        &mut self,
+        log: &Logger,
+        chapters_ins: &[insertable::EpisodeChapter],
+    ) -> Result<Vec<model::EpisodeChapter>> {
+        time_helpers::log_timed(&log.new(o!("step" => "replace_chapters")), |_log| {
+            diesel::delete(
+                schema::episode_chapter::table
+                    .filter(schema::episode_chapter::episode_id.eq(self.episode_id))
+                    .filter(schema::episode_chapter::source.eq(model::CHAPTER_SOURCE_JSON)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting episode chapters")?;
+
+            if chapters_ins.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            diesel::insert_into(schema::episode_chapter::table)
+                .values(chapters_ins)
+                .get_results(self.conn)
+                .chain_err(|| "Error inserting episode chapters")
+        })
+    }
+}
+
+pub struct RunResult {
+    pub chapters: Vec<model::EpisodeChapter>,
+}
+
+/// Contains types for deserializing a JSON chapters file. Like with JSON Feed,
+/// everything is optional so that one bad chapter doesn't sink the rest.
+mod json_chapters {
+    #[derive(Debug, Deserialize)]
+    pub struct Chapter {
+        pub img:   Option<String>,
+        pub title: Option<String>,
+        pub url:   Option<String>,
+
+        #[serde(rename = "startTime")]
+        pub start_time: Option<f64>,
+
+        // Chapters with this set to false are meant to be used for things like
+        // changing artwork mid-episode and shouldn't be shown in a listing.
+        pub toc: Option<bool>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct File {
+        #[serde(default)]
+        pub chapters: Vec<Chapter>,
+    }
+}
+
+//
+// Private functions
+//
+
+fn validate_chapter(
+    raw: &json_chapters::Chapter,
+    episode_id: i64,
+) -> Option<insertable::EpisodeChapter> {
+    if raw.toc == Some(false) {
+        return None;
+    }
+
+    let start_seconds = raw.start_time?;
+    if start_seconds < 0.0 {
+        return None;
+    }
+
+    Some(insertable::EpisodeChapter {
+        episode_id,
+        href: raw.url.clone(),
+        image_url: raw.img.clone(),
+        source: model::CHAPTER_SOURCE_JSON.to_owned(),
+        start_seconds,
+        title: raw.title.clone()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use http_requester::HttpRequesterPassThrough;
+    use mediators::episode_chapters_fetcher::*;
+    use test_data;
+    use test_helpers;
+
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_episode_chapters_fetch() {
+        let mut bootstrap = TestBootstrap::new(
+            br#"{
+  "version": "1.2.0",
+  "chapters": [
+    {"startTime": 0, "title": "Intro"},
+    {
+      "startTime": 65.5,
+      "title": "Topic",
+      "url": "https://example.com/topic",
+      "img": "https://example.com/topic.jpg"
+    },
+    {"startTime": 70, "title": "Artwork change", "toc": false},
+    {"startTime": 80}
+  ]
+}"#,
+        );
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+
+            // Run twice to make sure that chapters are replaced rather than duplicated.
+            mediator.run(&log).unwrap();
+            mediator.run(&log).unwrap()
+        };
+        assert_eq!(2, res.chapters.len());
+
+        let chapters: Vec<model::EpisodeChapter> = schema::episode_chapter::table
+            .order(schema::episode_chapter::start_seconds)
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(2, chapters.len());
+        assert_eq!(bootstrap.episode.id, chapters[0].episode_id);
+        assert_eq!("Intro", chapters[0].title);
+        assert_eq!(model::CHAPTER_SOURCE_JSON, chapters[0].source);
+        assert_eq!(65.5, chapters[1].start_seconds);
+        assert_eq!(
+            Some("https://example.com/topic".to_owned()),
+            chapters[1].href
+        );
+        assert_eq!(
+            Some("https://example.com/topic.jpg".to_owned()),
+            chapters[1].image_url
+        );
+    }
+
+    #[test]
+    fn test_episode_chapters_fetch_invalid() {
+        let mut bootstrap = TestBootstrap::new(b"not json");
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log);
+
+        assert!(res.is_err());
+        assert_eq!("Error parsing chapters file", res.err().unwrap().to_string());
+    }
+
+    //
+    // Private types/functions
+    //
+
+    struct TestBootstrap {
+        _common:        test_helpers::CommonTestBootstrap,
+        conn:           PooledConnection<ConnectionManager<PgConnection>>,
+        episode:        model::Episode,
+        http_requester: HttpRequesterPassThrough,
+        log:            Logger,
+    }
+
+    impl TestBootstrap {
+        fn new(data: &[u8]) -> TestBootstrap {
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+
+            let podcast = test_data::podcast::insert(&log, &conn);
+
+            TestBootstrap {
+                _common:        test_helpers::CommonTestBootstrap::new(),
+                episode:        test_data::episode::first(&log, &conn, &podcast),
+                http_requester: HttpRequesterPassThrough {
+                    data: Arc::new(data.to_vec()),
+                },
+
+                // Only move these after filling the above
+                conn: conn,
+                log:  log,
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    conn:           &*self.conn,
+                    episode_id:     self.episode.id,
+                    http_requester: &mut self.http_requester,
+                    url:            "https://example.com/chapters.json".to_owned(),
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
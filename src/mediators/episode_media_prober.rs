@@ -0,0 +1,393 @@
+use errors::*;
+use http_requester::HttpRequester;
+use model;
+use schema;
+use time_helpers;
+
+use chrono::Utc;
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use hyper::header::{ByteRangeSpec, ContentLength, ContentRange, ContentRangeSpec, ContentType,
+                    Headers, Range};
+use hyper::{Method, Request, StatusCode, Uri};
+use slog::Logger;
+use std::str::FromStr;
+
+/// Checks that an episode's media still exists with a `HEAD` request, and
+/// records its real size and content type along the way. Feeds are often wrong
+/// about both, and without this a dead enclosure only shows up as a broken
+/// player.
+///
+/// Some hosts (notably signed S3 URLs) don't allow `HEAD`, in which case we
+/// fall back to a `GET` for only the first byte of the media.
+pub struct Mediator<'a> {
+    pub conn:           &'a PgConnection,
+    pub episode_id:     i64,
+    pub http_requester: &'a mut HttpRequester,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let episode = self.select_episode(log)?;
+
+        // The requests happen outside of a transaction so that we're not holding
+        // one open while waiting on a remote host.
+        let probe = self.probe(log, episode.media_url.as_str());
+
+        let episode = self.conn
+            .transaction::<_, Error, _>(|| self.update_episode(log, &episode, &probe))?;
+        Ok(RunResult { episode, probe })
+    }
+
+    //
+    // Steps
+    //
+
+    // Makes a request and returns its response's status and headers. The body
+    // is never read, because a host that ignores `Range` would otherwise have
+    // us download all of the media.
+    fn execute(
+        &mut self,
+        log: &Logger,
+        method: Method,
+        url: &str,
+    ) -> Result<(StatusCode, Headers)> {
+        let mut req = Request::new(method, Uri::from_str(url).map_err(Error::from)?);
+        if *req.method() == Method::Get {
+            req.headers_mut()
+                .set(Range::Bytes(vec![ByteRangeSpec::FromTo(0, 0)]));
+        }
+        let res = self.http_requester.execute_streaming(log, req)?;
+        Ok((res.status, res.headers))
+    }
+
+    // Probes the media at the given URL. Failing to make a request at all (say
+    // because the host doesn't resolve) doesn't fail the probe, but rather
+    // produces one without a status so that it's still recorded.
+    fn probe(&mut self, log: &Logger, url: &str) -> Probe {
+        time_helpers::log_timed(&log.new(o!("step" => "probe")), |log| {
+            let res = match self.execute(log, Method::Head, url) {
+                Ok((status, _)) if needs_ranged_get(status) => {
+                    info!(log, "Host refused HEAD request -- retrying with ranged GET";
+                        "status" => status.as_u16());
+                    self.execute(log, Method::Get, url)
+                }
+                res => res,
+            };
+
+            match res {
+                Ok((status, headers)) => probe_from_response(status, &headers),
+                Err(e) => {
+                    info!(log, "Error probing media"; "error" => e.to_string());
+                    Probe {
+                        content_length: None,
+                        content_type:   None,
+                        status:         None,
+                    }
+                }
+            }
+        })
+    }
+
+    fn select_episode(&mut self, log: &Logger) -> Result<model::Episode> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_episode")), |_log| {
+            schema::episode::table
+                .filter(schema::episode::id.eq(self.episode_id))
+                .first(self.conn)
+                .chain_err(|| "Error selecting episode")
+        })
+    }
+
+    fn update_episode(
+        &mut self,
+        log: &Logger,
+        episode: &model::Episode,
+        probe: &Probe,
+    ) -> Result<model::Episode> {
+        // A failed request tells us nothing about whether the media is there, so
+        // leave whatever we knew before in place.
+        let media_gone_at = if probe.is_gone() {
+            episode.media_gone_at.or_else(|| Some(Utc::now()))
+        } else if probe.status.is_some() {
+            None
+        } else {
+            episode.media_gone_at
+        };
+
+        time_helpers::log_timed(&log.new(o!("step" => "update_episode")), |_log| {
+            diesel::update(schema::episode::table.filter(schema::episode::id.eq(episode.id)))
+                .set((
+                    schema::episode::media_content_length.eq(probe.content_length),
+                    schema::episode::media_content_type.eq(probe.content_type.clone()),
+                    schema::episode::media_gone_at.eq(media_gone_at),
+                    schema::episode::media_probed_at.eq(Some(Utc::now())),
+                    schema::episode::media_probed_url.eq(Some(episode.media_url.clone())),
+                    schema::episode::media_status.eq(probe.status),
+                ))
+                .get_result(self.conn)
+                .chain_err(|| "Error updating episode")
+        })
+    }
+}
+
+pub struct RunResult {
+    pub episode: model::Episode,
+    pub probe:   Probe,
+}
+
+/// What we learned about an episode's media.
+#[derive(Debug)]
+pub struct Probe {
+    /// The size of the media in bytes. Only set if the request succeeded.
+    pub content_length: Option<i64>,
+
+    /// The media's content type. Only set if the request succeeded.
+    pub content_type: Option<String>,
+
+    /// The final response's status, or `None` if no request could be made.
+    pub status: Option<i32>,
+}
+
+impl Probe {
+    /// Whether the host told us that the media doesn't exist anymore. Other
+    /// errors (like a 500) are too likely to be temporary to count.
+    pub fn is_gone(&self) -> bool {
+        match self.status {
+            Some(404) | Some(410) => true,
+            _ => false,
+        }
+    }
+}
+
+//
+// Private functions
+//
+
+// Whether a response to a `HEAD` request looks like the host just doesn't
+// support them, which is worth trying again with a `GET`.
+fn needs_ranged_get(status: StatusCode) -> bool {
+    match status {
+        StatusCode::Forbidden | StatusCode::MethodNotAllowed | StatusCode::NotImplemented => true,
+        _ => false,
+    }
+}
+
+fn probe_from_response(status: StatusCode, headers: &Headers) -> Probe {
+    if !status.is_success() {
+        return Probe {
+            content_length: None,
+            content_type:   None,
+            status:         Some(i32::from(status.as_u16())),
+        };
+    }
+
+    // The response to a ranged `GET` only carries the full size of the media in
+    // its `Content-Range`. A host that ignored the range sends all of it.
+    let content_length = if status == StatusCode::PartialContent {
+        match headers.get::<ContentRange>() {
+            Some(&ContentRange(ContentRangeSpec::Bytes {
+                instance_length: Some(length),
+                ..
+            })) => Some(length as i64),
+            _ => None,
+        }
+    } else {
+        headers.get::<ContentLength>().map(|h| h.0 as i64)
+    };
+
+    Probe {
+        content_length,
+        content_type: headers.get::<ContentType>().map(|h| h.to_string()),
+        status: Some(i32::from(status.as_u16())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http_requester::Response;
+    use mediators::episode_media_prober::*;
+    use test_data;
+    use test_helpers;
+
+    use hyper::Headers;
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+
+    #[test]
+    fn test_episode_media_probe_head() {
+        let mut bootstrap = TestBootstrap::new(HttpRequesterMedia {
+            head_status:     StatusCode::Ok,
+            ignore_range:    false,
+            requested_verbs: Vec::new(),
+        });
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        assert_eq!(Some(200), res.episode.media_status);
+        assert_eq!(Some(12345), res.episode.media_content_length);
+        assert_eq!(
+            Some("audio/mpeg".to_owned()),
+            res.episode.media_content_type
+        );
+        assert!(res.episode.media_gone_at.is_none());
+        assert!(res.episode.media_probed_at.is_some());
+        assert!(res.episode.is_media_probed());
+        assert_eq!(vec![Method::Head], bootstrap.http_requester.requested_verbs);
+    }
+
+    #[test]
+    fn test_episode_media_probe_ranged_get() {
+        let mut bootstrap = TestBootstrap::new(HttpRequesterMedia {
+            head_status:     StatusCode::Forbidden,
+            ignore_range:    false,
+            requested_verbs: Vec::new(),
+        });
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        assert_eq!(Some(206), res.episode.media_status);
+        assert_eq!(Some(12345), res.episode.media_content_length);
+        assert_eq!(
+            vec![Method::Head, Method::Get],
+            bootstrap.http_requester.requested_verbs
+        );
+    }
+
+    #[test]
+    fn test_episode_media_probe_ranged_get_ignored() {
+        let mut bootstrap = TestBootstrap::new(HttpRequesterMedia {
+            head_status:     StatusCode::Forbidden,
+            ignore_range:    true,
+            requested_verbs: Vec::new(),
+        });
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        // The host sent all of the media, but only its headers were needed.
+        assert_eq!(Some(200), res.episode.media_status);
+        assert_eq!(Some(12345), res.episode.media_content_length);
+        assert!(res.episode.media_gone_at.is_none());
+    }
+
+    #[test]
+    fn test_episode_media_probe_gone() {
+        let mut bootstrap = TestBootstrap::new(HttpRequesterMedia {
+            head_status:     StatusCode::NotFound,
+            ignore_range:    false,
+            requested_verbs: Vec::new(),
+        });
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        assert_eq!(Some(404), res.episode.media_status);
+        assert!(res.episode.media_content_length.is_none());
+        assert!(res.episode.media_gone_at.is_some());
+
+        // Media that comes back is no longer gone.
+        bootstrap.http_requester.head_status = StatusCode::Ok;
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert!(res.episode.media_gone_at.is_none());
+    }
+
+    //
+    // Private types/functions
+    //
+
+    // An `HttpRequester` that answers `HEAD` requests with a configurable status
+    // and `GET` requests as if the host supported ranges (unless it's been told
+    // to ignore them, in which case all of the media is sent).
+    struct HttpRequesterMedia {
+        head_status:     StatusCode,
+        ignore_range:    bool,
+        requested_verbs: Vec<Method>,
+    }
+
+    impl HttpRequester for HttpRequesterMedia {
+        fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+            self.requested_verbs.push(req.method().clone());
+
+            let mut headers = Headers::new();
+            headers.set_raw("Content-Type", "audio/mpeg");
+
+            let mut body = Vec::new();
+            let status = if *req.method() == Method::Head {
+                headers.set_raw("Content-Length", "12345");
+                self.head_status
+            } else if self.ignore_range {
+                headers.set_raw("Content-Length", "12345");
+                body = vec![0; 12345];
+                StatusCode::Ok
+            } else {
+                headers.set_raw("Content-Range", "bytes 0-0/12345");
+                StatusCode::PartialContent
+            };
+
+            Ok(Response {
+                body:               body,
+                final_url:          req.uri().to_string(),
+                headers:            headers,
+                last_url:           req.uri().to_string(),
+                permanent_redirect: false,
+                status:             status,
+            })
+        }
+    }
+
+    struct TestBootstrap {
+        _common:        test_helpers::CommonTestBootstrap,
+        conn:           PooledConnection<ConnectionManager<PgConnection>>,
+        episode:        model::Episode,
+        http_requester: HttpRequesterMedia,
+        log:            Logger,
+    }
+
+    impl TestBootstrap {
+        fn new(http_requester: HttpRequesterMedia) -> TestBootstrap {
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+
+            let podcast = test_data::podcast::insert(&log, &conn);
+
+            TestBootstrap {
+                _common:        test_helpers::CommonTestBootstrap::new(),
+                episode:        test_data::episode::first(&log, &conn, &podcast),
+                http_requester: http_requester,
+
+                // Only move these after filling the above
+                conn: conn,
+                log:  log,
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    conn:           &*self.conn,
+                    episode_id:     self.episode.id,
+                    http_requester: &mut self.http_requester,
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use errors::*;
+use model;
+use schema;
+use time_helpers;
+
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use slog::Logger;
+
+/// Loads a podcast's change log: every recorded change to the fields of its
+/// episodes, most recent first. This is mostly useful for support to answer
+/// questions about what a publisher changed in their feed and when.
+pub struct Mediator<'a> {
+    pub conn:       &'a PgConnection,
+    pub podcast_id: i64,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let podcast = self.select_podcast(log)?;
+        let revisions = self.select_episode_revisions(log)?;
+        Ok(RunResult { podcast, revisions })
+    }
+
+    //
+    // Steps
+    //
+
+    fn select_episode_revisions(
+        &mut self,
+        log: &Logger,
+    ) -> Result<Vec<(model::EpisodeRevision, model::Episode)>> {
+        time_helpers::log_timed(
+            &log.new(o!("step" => "select_episode_revisions")),
+            |_log| {
+                schema::episode_revision::table
+                    .inner_join(schema::episode::table)
+                    .filter(schema::episode::podcast_id.eq(self.podcast_id))
+                    .order((
+                        schema::episode_revision::created_at.desc(),
+                        schema::episode_revision::id.desc(),
+                    ))
+                    .load::<(model::EpisodeRevision, model::Episode)>(self.conn)
+                    .chain_err(|| "Error selecting episode revisions")
+            },
+        )
+    }
+
+    fn select_podcast(&mut self, log: &Logger) -> Result<model::Podcast> {
+        let podcast: Option<model::Podcast> =
+            time_helpers::log_timed(&log.new(o!("step" => "select_podcast")), |_log| {
+                schema::podcast::table
+                    .filter(schema::podcast::id.eq(self.podcast_id))
+                    .first(self.conn)
+                    .optional()
+                    .chain_err(|| "Error selecting podcast")
+            })?;
+        podcast.ok_or_else(|| format!("No podcast with ID {}", self.podcast_id).into())
+    }
+}
+
+pub struct RunResult {
+    pub podcast: model::Podcast,
+
+    /// Revisions along with the episodes that they belong to.
+    pub revisions: Vec<(model::EpisodeRevision, model::Episode)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use mediators::episode_revision_lister::*;
+    use model::insertable;
+    use test_data;
+    use test_helpers;
+
+    use diesel;
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+
+    #[test]
+    fn test_episode_revision_list() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let episode =
+            test_data::episode::first(&bootstrap.log, &*bootstrap.conn, &bootstrap.podcast);
+        diesel::insert_into(schema::episode_revision::table)
+            .values(&insertable::EpisodeRevision {
+                episode_id: episode.id,
+                field:      "media_url".to_owned(),
+                new_value:  Some("https://example.com/item-1-fixed".to_owned()),
+                old_value:  Some(episode.media_url.clone()),
+            })
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        // A revision for another podcast's episode shouldn't be included.
+        let other_podcast = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        let other_episode =
+            test_data::episode::first(&bootstrap.log, &*bootstrap.conn, &other_podcast);
+        diesel::insert_into(schema::episode_revision::table)
+            .values(&insertable::EpisodeRevision {
+                episode_id: other_episode.id,
+                field:      "title".to_owned(),
+                new_value:  Some("New Title".to_owned()),
+                old_value:  Some(other_episode.title.clone()),
+            })
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        assert_eq!(1, res.revisions.len());
+        let (ref revision, ref revision_episode) = res.revisions[0];
+        assert_eq!("media_url", revision.field);
+        assert_eq!(episode.id, revision_episode.id);
+    }
+
+    #[test]
+    fn test_episode_revision_list_no_podcast() {
+        let mut bootstrap = TestBootstrap::new();
+        let (mut mediator, log) = bootstrap.mediator();
+        mediator.podcast_id = 0;
+        let res = mediator.run(&log);
+
+        assert!(res.is_err());
+        assert_eq!("No podcast with ID 0", res.err().unwrap().to_string());
+    }
+
+    //
+    // Private types/functions
+    //
+
+    struct TestBootstrap {
+        _common: test_helpers::CommonTestBootstrap,
+        conn:    PooledConnection<ConnectionManager<PgConnection>>,
+        log:     Logger,
+        podcast: model::Podcast,
+    }
+
+    impl TestBootstrap {
+        fn new() -> TestBootstrap {
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+
+            TestBootstrap {
+                _common: test_helpers::CommonTestBootstrap::new(),
+                podcast: test_data::podcast::insert(&log, &conn),
+
+                // Only move these after filling the above
+                conn: conn,
+                log:  log,
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    conn:       &*self.conn,
+                    podcast_id: self.podcast.id,
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
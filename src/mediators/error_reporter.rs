@@ -278,15 +278,15 @@ fn build_stack_trace(error: &Error) -> Option<StackTrace> {
 }
 
 fn post_error(log: &Logger, http_requester: &mut HttpRequester, req: Request) -> Result<()> {
-    let (status, body, _final_url) = time_helpers::log_timed(
+    let res = time_helpers::log_timed(
         &log.new(o!("step" => "post_error")),
         |log| http_requester.execute(log, req),
     )?;
-    common::log_body_sample(log, status, &body);
+    common::log_body_sample(log, res.status, &res.body);
     ensure!(
-        status == StatusCode::Ok,
+        res.status == StatusCode::Ok,
         "Unexpected status while reporting error: {}",
-        status
+        res.status
     );
     Ok(())
 }
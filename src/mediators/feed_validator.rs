@@ -0,0 +1,156 @@
+use errors::*;
+use http_requester::HttpRequester;
+use mediators::common;
+use mediators::podcast_updater;
+use time_helpers;
+
+use hyper::header::ContentType;
+use hyper::{Method, Request, StatusCode, Uri};
+use slog::Logger;
+use std::fs::File;
+use std::io::Read;
+use std::str::FromStr;
+
+/// Checks a feed for problems without adding it to the database. This is
+/// meant to help explain to a podcast's owner why their feed is being
+/// rejected.
+pub struct Mediator<'a> {
+    pub http_requester: &'a mut HttpRequester,
+
+    /// Either a URL to fetch the feed from or a path to a file containing it.
+    pub source: String,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let (body, content_type) =
+            if self.source.starts_with("http://") || self.source.starts_with("https://") {
+                self.fetch_feed(log)?
+            } else {
+                self.read_feed(log)?
+            };
+
+        let content_type = content_type.as_ref().map(String::as_str);
+        let report = time_helpers::log_timed(&log.new(o!("step" => "validate_feed")), |log| {
+            podcast_updater::validate_feed(log, body.as_slice(), content_type)
+        });
+
+        Ok(RunResult { report })
+    }
+
+    //
+    // Steps
+    //
+
+    fn fetch_feed(&mut self, log: &Logger) -> Result<(Vec<u8>, Option<String>)> {
+        let res = time_helpers::log_timed(&log.new(o!("step" => "fetch_feed")), |log| {
+            self.http_requester.execute(
+                log,
+                Request::new(
+                    Method::Get,
+                    Uri::from_str(self.source.as_str()).map_err(Error::from)?,
+                ),
+            )
+        })?;
+        common::log_body_sample(log, res.status, &res.body);
+
+        if res.status != StatusCode::Ok {
+            bail!("Error fetching feed. Host responded with status: {}", res.status)
+        }
+
+        let content_type = res.headers.get::<ContentType>().map(|h| h.to_string());
+        Ok((res.body, content_type))
+    }
+
+    fn read_feed(&mut self, log: &Logger) -> Result<(Vec<u8>, Option<String>)> {
+        time_helpers::log_timed(&log.new(o!("step" => "read_feed")), |_log| {
+            let mut body = Vec::new();
+            File::open(self.source.as_str())
+                .and_then(|mut f| f.read_to_end(&mut body))
+                .chain_err(|| format!("Error reading feed from file: {}", self.source))?;
+            Ok((body, None))
+        })
+    }
+}
+
+pub struct RunResult {
+    pub report: podcast_updater::FeedReport,
+}
+
+#[cfg(test)]
+mod tests {
+    use http_requester::HttpRequesterPassThrough;
+    use mediators::feed_validator::*;
+    use test_helpers;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn test_feed_validate_url() {
+        let mut bootstrap = TestBootstrap::new("https://example.com/feed.xml");
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        assert!(res.report.is_valid());
+        assert_eq!(1, res.report.num_episodes);
+    }
+
+    #[test]
+    fn test_feed_validate_file() {
+        let mut bootstrap = TestBootstrap::new("src/test_documents/feed_json_ideal.json");
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        assert!(res.report.is_valid());
+        assert_eq!(Some("Title".to_owned()), res.report.podcast_title);
+    }
+
+    #[test]
+    fn test_feed_validate_missing_file() {
+        let mut bootstrap = TestBootstrap::new("src/test_documents/does_not_exist.xml");
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log);
+
+        assert!(res.is_err());
+        assert_eq!(
+            "Error reading feed from file: src/test_documents/does_not_exist.xml",
+            res.err().unwrap().to_string()
+        );
+    }
+
+    //
+    // Private types/functions
+    //
+
+    struct TestBootstrap {
+        http_requester: HttpRequesterPassThrough,
+        log:            Logger,
+        source:         &'static str,
+    }
+
+    impl TestBootstrap {
+        fn new(source: &'static str) -> TestBootstrap {
+            TestBootstrap {
+                http_requester: HttpRequesterPassThrough {
+                    data: Arc::new(test_helpers::MINIMAL_FEED.to_vec()),
+                },
+                log:            test_helpers::log(),
+                source:         source,
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    http_requester: &mut self.http_requester,
+                    source:         self.source.to_owned(),
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
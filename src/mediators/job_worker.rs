@@ -33,6 +33,10 @@ pub struct Mediator {
     pub pool:                   Pool<ConnectionManager<PgConnection>>,
     pub http_requester_factory: Box<HttpRequesterFactory>,
 
+    // The URL at which the web server can be reached from the outside world. Jobs that need to
+    // give a remote service a URL to call us back at (like WebSub subscriptions) fail without it.
+    pub public_url: Option<String>,
+
     // Tells the worker to run for only one batch of jobs instead of looping continuously forever.
     pub run_once: bool,
 }
@@ -55,6 +59,7 @@ impl Mediator {
                     log.new(o!("thread" => thread_name.clone(), "num_threads" => self.num_workers));
                 let pool_clone = self.pool.clone();
                 let factory_clone = self.http_requester_factory.clone_box();
+                let public_url_clone = self.public_url.clone();
                 let res_send_clone = res_send.clone();
                 let work_recv_clone = work_recv.clone();
 
@@ -65,6 +70,7 @@ impl Mediator {
                             &log,
                             &pool_clone,
                             &*factory_clone,
+                            public_url_clone.as_ref().map(String::as_str),
                             &work_recv_clone,
                             &res_send_clone,
                         )
@@ -393,10 +399,11 @@ fn work(
     log: &Logger,
     pool: &Pool<ConnectionManager<PgConnection>>,
     http_requester_factory: &HttpRequesterFactory,
+    public_url: Option<&str>,
     work_recv: &Receiver<model::Job>,
     res_send: &Sender<JobResult>,
 ) -> Result<()> {
-    let requester = http_requester_factory.create();
+    let mut requester = http_requester_factory.create();
 
     loop {
         chan_select! {
@@ -410,7 +417,7 @@ fn work(
                 };
 
                 let res = time_helpers::log_timed(&log.new(o!("step" => "work_job", "job_id" => job.id)), |log| {
-                    work_job(log, pool, &*requester, &job)
+                    work_job(log, pool, &mut *requester, public_url, &job)
                 });
 
                 debug!(log, "Worked a job");
@@ -430,10 +437,26 @@ fn work(
 fn work_job(
     log: &Logger,
     pool: &Pool<ConnectionManager<PgConnection>>,
-    requester: &HttpRequester,
+    requester: &mut HttpRequester,
+    public_url: Option<&str>,
     job: &model::Job,
 ) -> Result<()> {
     match job.name.as_str() {
+        jobs::episode_chapters_fetcher::NAME => jobs::episode_chapters_fetcher::Job {
+            args: serde_json::from_value(job.args.clone())?,
+            pool,
+            requester,
+        }.run(log),
+        jobs::episode_media_prober::NAME => jobs::episode_media_prober::Job {
+            args: serde_json::from_value(job.args.clone())?,
+            pool,
+            requester,
+        }.run(log),
+        jobs::feed_subscriber::NAME => jobs::feed_subscriber::Job {
+            args: serde_json::from_value(job.args.clone())?,
+            pool,
+            requester,
+        }.run(log),
         jobs::no_op::NAME => jobs::no_op::Job {
             args: serde_json::from_value(job.args.clone())?,
         }.run(log),
@@ -442,6 +465,12 @@ fn work_job(
             pool,
             requester,
         }.run(log),
+        jobs::websub_subscriber::NAME => jobs::websub_subscriber::Job {
+            args: serde_json::from_value(job.args.clone())?,
+            pool,
+            public_url,
+            requester,
+        }.run(log),
         _ => Err(errors::job_unknown(job.name.clone())),
     }
 }
@@ -584,9 +613,10 @@ mod tests {
         work_job(
             &bootstrap.log,
             &bootstrap.pool,
-            &HttpRequesterPassThrough {
+            &mut HttpRequesterPassThrough {
                 data: Arc::new(Vec::new()),
             },
+            None,
             &new_job(),
         ).unwrap();
     }
@@ -646,6 +676,7 @@ mod tests {
                     http_requester_factory: Box::new(HttpRequesterFactoryPassThrough {
                         data: Arc::new(Vec::new()),
                     }),
+                    public_url:             None,
                     run_once:               true,
                 },
                 self.log.clone(),
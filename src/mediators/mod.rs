@@ -10,11 +10,22 @@ pub mod account_podcast_subscriber;
 pub mod cleaner;
 pub mod directory_podcast_searcher;
 pub mod directory_podcast_updater;
+pub mod episode_chapters_fetcher;
+pub mod episode_media_prober;
+pub mod episode_revision_lister;
 pub mod error_reporter;
+pub mod feed_validator;
 pub mod job_worker;
 pub mod key_creator;
+pub mod opml_exporter;
+pub mod opml_importer;
 pub mod podcast_crawler;
+pub mod podcast_feed_content_migrator;
 pub mod podcast_feed_location_upgrader;
+pub mod podcast_merger;
 pub mod podcast_reingester;
 pub mod podcast_updater;
 pub mod verification_code_creator;
+pub mod websub_content_receiver;
+pub mod websub_intent_verifier;
+pub mod websub_subscriber;
@@ -0,0 +1,298 @@
+use errors::*;
+use model;
+use schema;
+use time_helpers;
+
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use slog::Logger;
+use std::collections::HashMap;
+
+/// Renders an account's subscriptions as an OPML 2.0 document so that they can
+/// be taken to another podcast app.
+pub struct Mediator<'a> {
+    pub account: &'a model::Account,
+    pub conn:    &'a PgConnection,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let podcasts = self.select_podcasts(log)?;
+        let feed_urls = self.select_feed_urls(log, &podcasts)?;
+
+        let mut opml = String::new();
+        opml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        opml.push_str("\n<opml version=\"2.0\">\n");
+        opml.push_str("  <head>\n");
+        opml.push_str("    <title>Podcast Subscriptions</title>\n");
+        opml.push_str("  </head>\n");
+        opml.push_str("  <body>\n");
+        for podcast in &podcasts {
+            // Every podcast should have at least one location, but skip any that
+            // don't rather than produce an outline that's useless to an importer.
+            let feed_url = match feed_urls.get(&podcast.id) {
+                Some(feed_url) => feed_url,
+                None => continue,
+            };
+
+            let title = escape_attribute(podcast.title.as_str());
+            opml.push_str(&format!(
+                "    <outline type=\"rss\" text=\"{}\" title=\"{}\" xmlUrl=\"{}\"",
+                title,
+                title,
+                escape_attribute(feed_url.as_str())
+            ));
+            if let Some(ref link_url) = podcast.link_url {
+                opml.push_str(&format!(" htmlUrl=\"{}\"", escape_attribute(link_url)));
+            }
+            opml.push_str(" />\n");
+        }
+        opml.push_str("  </body>\n");
+        opml.push_str("</opml>\n");
+
+        Ok(RunResult { opml, podcasts })
+    }
+
+    //
+    // Steps
+    //
+
+    // Selects the URL that each podcast's feed is currently found at. A location
+    // that's been superseded by another is only used if it's all we have.
+    fn select_feed_urls(
+        &mut self,
+        log: &Logger,
+        podcasts: &[model::Podcast],
+    ) -> Result<HashMap<i64, String>> {
+        let podcast_ids: Vec<i64> = podcasts.iter().map(|p| p.id).collect();
+        let locations: Vec<model::PodcastFeedLocation> =
+            time_helpers::log_timed(&log.new(o!("step" => "select_feed_locations")), |_log| {
+                schema::podcast_feed_location::table
+                    .filter(schema::podcast_feed_location::podcast_id.eq_any(podcast_ids))
+                    .order(schema::podcast_feed_location::last_retrieved_at.desc())
+                    .load(self.conn)
+                    .chain_err(|| "Error selecting podcast feed locations")
+            })?;
+
+        let mut feed_urls = HashMap::new();
+        for location in locations.iter().filter(|l| l.superseded_at.is_none()) {
+            feed_urls
+                .entry(location.podcast_id)
+                .or_insert_with(|| location.feed_url.clone());
+        }
+        for location in &locations {
+            feed_urls
+                .entry(location.podcast_id)
+                .or_insert_with(|| location.feed_url.clone());
+        }
+        Ok(feed_urls)
+    }
+
+    fn select_podcasts(&mut self, log: &Logger) -> Result<Vec<model::Podcast>> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_podcasts")), |_log| {
+            schema::podcast::table
+                .filter(
+                    schema::podcast::id.eq_any(
+                        schema::account_podcast::table
+                            .filter(schema::account_podcast::account_id.eq(self.account.id))
+                            .filter(schema::account_podcast::unsubscribed_at.is_null())
+                            .select(schema::account_podcast::podcast_id),
+                    ),
+                )
+                .order(schema::podcast::title)
+                .load(self.conn)
+                .chain_err(|| "Error selecting podcasts")
+        })
+    }
+}
+
+pub struct RunResult {
+    /// The rendered OPML document.
+    pub opml: String,
+
+    /// The podcasts that the account is subscribed to, in the order that they
+    /// appear in the document.
+    pub podcasts: Vec<model::Podcast>,
+}
+
+//
+// Private functions
+//
+
+fn escape_attribute(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use mediators::opml_exporter::*;
+    use mediators::opml_importer;
+    use model::insertable;
+    use test_data;
+    use test_helpers;
+
+    use chrono::{Duration, Utc};
+    use diesel;
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+
+    #[test]
+    fn test_opml_export() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let podcast = test_data::podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::podcast::Args {
+                feed_url: Some("https://example.com/feed.xml?a=1&b=2".to_owned()),
+            },
+        );
+        let _account_podcast = test_data::account_podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::account_podcast::Args {
+                account: Some(&bootstrap.account),
+                podcast: Some(&podcast),
+            },
+        );
+
+        // Unsubscribed podcasts are left out.
+        let unsubscribed = test_data::account_podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::account_podcast::Args {
+                account: Some(&bootstrap.account),
+                podcast: None,
+            },
+        );
+        diesel::update(schema::account_podcast::table)
+            .filter(schema::account_podcast::id.eq(unsubscribed.id))
+            .set(schema::account_podcast::unsubscribed_at.eq(Utc::now()))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        assert_eq!(1, res.podcasts.len());
+        assert_eq!(podcast.id, res.podcasts[0].id);
+        assert!(
+            res.opml
+                .contains(r#"xmlUrl="https://example.com/feed.xml?a=1&amp;b=2""#)
+        );
+        assert!(res.opml.contains(&format!(r#"title="{}""#, podcast.title)));
+
+        // Exported documents should import cleanly back into the same account.
+        let res = opml_importer::Mediator {
+            account: &bootstrap.account,
+            conn:    &*bootstrap.conn,
+            data:    res.opml.as_bytes(),
+        }.run(&bootstrap.log)
+            .unwrap();
+        assert_eq!(1, res.feeds.len());
+        assert_eq!(opml_importer::FeedStatus::Subscribed, res.feeds[0].status);
+    }
+
+    #[test]
+    fn test_opml_export_superseded_location() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let podcast = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        let _account_podcast = test_data::account_podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::account_podcast::Args {
+                account: Some(&bootstrap.account),
+                podcast: Some(&podcast),
+            },
+        );
+
+        // A location that was retrieved more recently but has since been superseded
+        // shouldn't be exported.
+        diesel::update(schema::podcast_feed_location::table)
+            .filter(schema::podcast_feed_location::podcast_id.eq(podcast.id))
+            .set(schema::podcast_feed_location::superseded_at.eq(Utc::now()))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+        diesel::insert_into(schema::podcast_feed_location::table)
+            .values(&insertable::PodcastFeedLocation {
+                first_retrieved_at: Utc::now(),
+                feed_url:           "https://example.com/new-feed.xml".to_owned(),
+                last_retrieved_at:  Utc::now() - Duration::days(1),
+                podcast_id:         podcast.id,
+                etag:               None,
+                last_modified:      None,
+                superseded_at:      None,
+            })
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert!(res.opml.contains(r#"xmlUrl="https://example.com/new-feed.xml""#));
+    }
+
+    #[test]
+    fn test_opml_export_escape_attribute() {
+        assert_eq!(
+            "Tom &amp; Jerry&apos;s &lt;&quot;Show&quot;&gt;",
+            escape_attribute(r#"Tom & Jerry's <"Show">"#)
+        );
+    }
+
+    //
+    // Private types/functions
+    //
+
+    struct TestBootstrap {
+        _common: test_helpers::CommonTestBootstrap,
+        account: model::Account,
+        conn:    PooledConnection<ConnectionManager<PgConnection>>,
+        log:     Logger,
+    }
+
+    impl TestBootstrap {
+        fn new() -> TestBootstrap {
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+
+            TestBootstrap {
+                _common: test_helpers::CommonTestBootstrap::new(),
+                account: test_data::account::insert(&log, &conn),
+
+                // Only move these after filling the above
+                conn: conn,
+                log:  log,
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    account: &self.account,
+                    conn:    &*self.conn,
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
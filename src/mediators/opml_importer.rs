@@ -0,0 +1,384 @@
+use errors::*;
+use jobs;
+use mediators::account_podcast_subscriber;
+use model;
+use schema;
+use time_helpers;
+
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+use slog::Logger;
+use std::collections::HashSet;
+use url::Url;
+
+/// Imports an account's subscriptions from an OPML file, which is the format
+/// that just about every other podcast app can export to.
+///
+/// Feeds that we already know about are subscribed to right away. Feeds that
+/// we don't are retrieved in the background by a job that subscribes the
+/// account once it's done, because retrieving hundreds of feeds inline would
+/// take far too long.
+pub struct Mediator<'a> {
+    pub account: &'a model::Account,
+    pub conn:    &'a PgConnection,
+
+    /// The raw contents of the OPML file.
+    pub data: &'a [u8],
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| {
+            self.conn.transaction::<_, Error, _>(|| self.run_inner(log))
+        })
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let outlines = parse_opml(log, self.data)?;
+
+        // The same feed often appears more than once when an app exports its
+        // folders or categories as nested outlines.
+        let mut feed_urls = HashSet::new();
+        let mut feeds = Vec::new();
+        for outline in outlines {
+            if !feed_urls.insert(outline.feed_url.clone()) {
+                continue;
+            }
+
+            let (podcast, status) = self.import_feed(log, outline.feed_url.as_str())?;
+            feeds.push(FeedResult {
+                feed_url: outline.feed_url,
+                podcast,
+                status,
+                title: outline.title,
+            });
+        }
+
+        Ok(RunResult { feeds })
+    }
+
+    //
+    // Steps
+    //
+
+    fn enqueue_feed_subscriber(&mut self, log: &Logger, feed_url: &str) -> Result<()> {
+        time_helpers::log_timed(
+            &log.new(o!("step" => "enqueue_feed_subscriber")),
+            |log| {
+                jobs::feed_subscriber::enqueue(
+                    log,
+                    self.conn,
+                    &jobs::feed_subscriber::Args {
+                        account_id: self.account.id,
+                        feed_url:   feed_url.to_owned(),
+                    },
+                )
+            },
+        )?;
+        Ok(())
+    }
+
+    fn import_feed(
+        &mut self,
+        log: &Logger,
+        feed_url: &str,
+    ) -> Result<(Option<model::Podcast>, FeedStatus)> {
+        let log = log.new(o!("feed_url" => feed_url.to_owned()));
+
+        match Url::parse(feed_url) {
+            Ok(ref url) if url.scheme() == "http" || url.scheme() == "https" => (),
+            _ => {
+                info!(log, "Skipping invalid feed URL");
+                return Ok((
+                    None,
+                    FeedStatus::Invalid("Feed URL isn't a valid HTTP or HTTPS URL".to_owned()),
+                ));
+            }
+        }
+
+        match self.select_podcast(&log, feed_url)? {
+            Some(podcast) => {
+                account_podcast_subscriber::Mediator {
+                    account:    self.account,
+                    conn:       self.conn,
+                    podcast:    &podcast,
+                    subscribed: true,
+                }.run(&log)?;
+                Ok((Some(podcast), FeedStatus::Subscribed))
+            }
+            None => {
+                self.enqueue_feed_subscriber(&log, feed_url)?;
+                Ok((None, FeedStatus::Enqueued))
+            }
+        }
+    }
+
+    fn select_podcast(&mut self, log: &Logger, feed_url: &str) -> Result<Option<model::Podcast>> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_podcast")), |_log| {
+            schema::podcast::table
+                .filter(
+                    schema::podcast::id.eq_any(
+                        schema::podcast_feed_location::table
+                            .filter(schema::podcast_feed_location::feed_url.eq(feed_url))
+                            .select(schema::podcast_feed_location::podcast_id),
+                    ),
+                )
+                .first(self.conn)
+                .optional()
+                .chain_err(|| "Error selecting podcast")
+        })
+    }
+}
+
+pub struct RunResult {
+    /// A result for every distinct feed found in the file, in the order that
+    /// they appeared.
+    pub feeds: Vec<FeedResult>,
+}
+
+/// The result of importing a single feed from an OPML file.
+#[derive(Debug)]
+pub struct FeedResult {
+    pub feed_url: String,
+
+    /// The podcast that the account was subscribed to. Only set if the feed
+    /// was subscribed to right away.
+    pub podcast: Option<model::Podcast>,
+
+    pub status: FeedStatus,
+
+    /// The title given to the feed in the OPML file, if there was one.
+    pub title: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FeedStatus {
+    /// The feed wasn't one that we knew about, so it's been queued to be
+    /// retrieved and subscribed to in the background.
+    Enqueued,
+
+    /// The feed couldn't be imported for the contained reason.
+    Invalid(String),
+
+    /// The account is now subscribed to the feed's podcast.
+    Subscribed,
+}
+
+//
+// Private types
+//
+
+// A feed found in an OPML file.
+struct Outline {
+    feed_url: String,
+    title:    Option<String>,
+}
+
+//
+// Private functions
+//
+
+// Collects every outline with a feed URL from an OPML document. Outlines can
+// be nested arbitrarily deep (usually to represent folders), but we don't
+// care about the structure, so they're flattened.
+fn parse_opml(log: &Logger, data: &[u8]) -> Result<Vec<Outline>> {
+    time_helpers::log_timed(&log.new(o!("step" => "parse_opml")), |log| {
+        let mut buf = Vec::new();
+        let mut found_opml = false;
+        let mut outlines = Vec::new();
+
+        let mut reader = Reader::from_reader(data);
+        reader.trim_text(true).expand_empty_elements(true);
+
+        loop {
+            match reader
+                .read_event(&mut buf)
+                .map_err(|_e| user_errors::bad_request("Error parsing OPML file"))?
+            {
+                Event::Start(ref e) => match e.name() {
+                    b"opml" => found_opml = true,
+                    b"outline" => {
+                        let mut feed_url: Option<String> = None;
+                        let mut text: Option<String> = None;
+                        let mut title: Option<String> = None;
+
+                        for attr in e.attributes().with_checks(false) {
+                            let attr = match attr {
+                                Ok(attr) => attr,
+                                Err(_) => continue,
+                            };
+                            let value = match attr.unescape_and_decode_value(&reader) {
+                                Ok(value) => value.trim().to_owned(),
+                                Err(_) => continue,
+                            };
+                            if value.is_empty() {
+                                continue;
+                            }
+
+                            match attr.key {
+                                b"text" => text = Some(value),
+                                b"title" => title = Some(value),
+                                b"xmlUrl" => feed_url = Some(value),
+                                _ => (),
+                            }
+                        }
+
+                        // Outlines without a feed URL are usually folders.
+                        if let Some(feed_url) = feed_url {
+                            outlines.push(Outline {
+                                feed_url,
+                                title: title.or(text),
+                            });
+                        }
+                    }
+                    _ => (),
+                },
+                Event::Eof => break,
+                _ => (),
+            }
+            buf.clear();
+        }
+
+        if !found_opml {
+            bail!(user_errors::bad_request(
+                "That file doesn't appear to be OPML."
+            ));
+        }
+
+        info!(log, "Parsed OPML"; "num_outlines" => outlines.len());
+        Ok(outlines)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use mediators::opml_importer::*;
+    use test_data;
+    use test_helpers;
+
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+
+    #[test]
+    fn test_opml_import() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let podcast = test_data::podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::podcast::Args {
+                feed_url: Some("https://example.com/known.xml".to_owned()),
+            },
+        );
+
+        let data = br#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+  <head>
+    <title>Subscriptions</title>
+  </head>
+  <body>
+    <outline text="Known Podcast" type="rss" xmlUrl="https://example.com/known.xml" />
+    <outline text="Folder">
+      <outline text="Unknown Podcast" type="rss" xmlUrl="https://example.com/unknown.xml" />
+      <outline text="Known Again" type="rss" xmlUrl="https://example.com/known.xml" />
+    </outline>
+    <outline text="Invalid Podcast" type="rss" xmlUrl="ftp://example.com/invalid.xml" />
+  </body>
+</opml>"#;
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator(data);
+            mediator.run(&log).unwrap()
+        };
+
+        assert_eq!(3, res.feeds.len());
+
+        assert_eq!("https://example.com/known.xml", res.feeds[0].feed_url);
+        assert_eq!(Some("Known Podcast".to_owned()), res.feeds[0].title);
+        assert_eq!(FeedStatus::Subscribed, res.feeds[0].status);
+        assert_eq!(podcast.id, res.feeds[0].podcast.as_ref().unwrap().id);
+
+        assert_eq!("https://example.com/unknown.xml", res.feeds[1].feed_url);
+        assert_eq!(FeedStatus::Enqueued, res.feeds[1].status);
+        assert!(res.feeds[1].podcast.is_none());
+
+        assert_eq!("ftp://example.com/invalid.xml", res.feeds[2].feed_url);
+        match res.feeds[2].status {
+            FeedStatus::Invalid(_) => (),
+            ref status => panic!("Unexpected status: {:?}", status),
+        }
+
+        // The known podcast was subscribed to immediately.
+        let account_podcast: model::AccountPodcast = schema::account_podcast::table
+            .filter(schema::account_podcast::account_id.eq(bootstrap.account.id))
+            .first(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(podcast.id, account_podcast.podcast_id);
+
+        // And the unknown one was left for a job.
+        let job: model::Job = schema::job::table
+            .filter(schema::job::name.eq(jobs::feed_subscriber::NAME))
+            .first(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(
+            json!({
+                "account_id": bootstrap.account.id,
+                "feed_url": "https://example.com/unknown.xml",
+            }),
+            job.args
+        );
+    }
+
+    #[test]
+    fn test_opml_import_not_opml() {
+        let mut bootstrap = TestBootstrap::new();
+        let (mut mediator, log) = bootstrap.mediator(test_helpers::MINIMAL_FEED);
+        let res = mediator.run(&log);
+
+        assert!(res.is_err());
+        assert_eq!(
+            "Bad request: That file doesn't appear to be OPML.",
+            res.err().unwrap().to_string()
+        );
+    }
+
+    //
+    // Private types/functions
+    //
+
+    struct TestBootstrap {
+        _common: test_helpers::CommonTestBootstrap,
+        account: model::Account,
+        conn:    PooledConnection<ConnectionManager<PgConnection>>,
+        log:     Logger,
+    }
+
+    impl TestBootstrap {
+        fn new() -> TestBootstrap {
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+
+            TestBootstrap {
+                _common: test_helpers::CommonTestBootstrap::new(),
+                account: test_data::account::insert(&log, &conn),
+
+                // Only move these after filling the above
+                conn: conn,
+                log:  log,
+            }
+        }
+
+        fn mediator<'a>(&'a mut self, data: &'a [u8]) -> (Mediator<'a>, Logger) {
+            (
+                Mediator {
+                    account: &self.account,
+                    conn:    &*self.conn,
+                    data,
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
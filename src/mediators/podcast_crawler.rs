@@ -1,26 +1,39 @@
 use error_helpers;
 use errors::*;
-use http_requester::HttpRequesterFactory;
+use http_requester::{HttpRequester, HttpRequesterFactory, Response, StreamingResponse};
 use mediators::common;
 use mediators::podcast_updater;
+use schema;
 use time_helpers;
 
 use chan;
 use chan::{Receiver, Sender};
+use chrono::{DateTime, Duration, Utc};
 use diesel;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use diesel::sql_types::{BigInt, Text};
+use hyper::header::{Headers, RetryAfter};
+use hyper::{Request, StatusCode};
 use r2d2::Pool;
 use r2d2_diesel::ConnectionManager;
 use slog::Logger;
+use std::cmp;
+use std::collections::{HashMap, VecDeque};
 use std::thread;
+use std::time::SystemTime;
+use url::Url;
 
 pub struct Mediator {
     // Number of workers to use. Should generally be the size of the thread pool minus one for the
     // control process.
     pub num_workers: u32,
 
+    /// Limits on how hard any one host gets hit. Without them a hosting
+    /// provider with thousands of feeds could have every worker requesting
+    /// from it at once.
+    pub host_limits: HostLimits,
+
     pub pool:                   Pool<ConnectionManager<PgConnection>>,
     pub http_requester_factory: Box<HttpRequesterFactory>,
 }
@@ -33,8 +46,9 @@ impl Mediator {
     pub fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
         let mut workers = vec![];
 
-        let num_podcasts = {
+        let res = {
             let (work_send, work_recv) = chan::sync(100);
+            let (done_send, done_recv) = chan::async();
             for i in 0..self.num_workers {
                 let thread_name = common::thread_name(i);
                 let log =
@@ -42,14 +56,23 @@ impl Mediator {
                 let pool_clone = self.pool.clone();
                 let factory_clone = self.http_requester_factory.clone_box();
                 let work_recv_clone = work_recv.clone();
+                let done_send_clone = done_send.clone();
 
                 workers.push(thread::Builder::new()
                     .name(thread_name)
-                    .spawn(move || work(&log, &pool_clone, &*factory_clone, &work_recv_clone))
+                    .spawn(move || {
+                        work(
+                            &log,
+                            &pool_clone,
+                            &*factory_clone,
+                            &work_recv_clone,
+                            &done_send_clone,
+                        )
+                    })
                     .map_err(Error::from)?);
             }
 
-            self.page_podcasts(log, &work_send)?
+            self.page_podcasts(log, &work_send, &done_recv)?
 
             // `work_send` is dropped, which unblocks our threads' select, passes them a
             // `None` result, and lets them to drop back to main
@@ -60,39 +83,123 @@ impl Mediator {
             let _ = worker.join();
         }
 
-        info!(log, "Finished crawling"; "num_podcast" => num_podcasts);
-        Ok(RunResult { num_podcasts })
+        info!(log, "Finished crawling";
+            "num_deferred" => res.num_deferred,
+            "num_podcast" => res.num_podcasts,
+            "num_throttled" => res.num_throttled);
+        Ok(res)
     }
 
     //
     // Steps
     //
 
-    fn page_podcasts(&mut self, log: &Logger, work_send: &Sender<PodcastTuple>) -> Result<i64> {
+    fn defer_podcasts(
+        log: &Logger,
+        conn: &PgConnection,
+        podcast_ids: &[i64],
+        until: DateTime<Utc>,
+    ) -> Result<()> {
+        time_helpers::log_timed(&log.new(o!("step" => "defer_podcasts")), |log| {
+            let num_rows_updated: usize = diesel::update(
+                schema::podcast::table.filter(schema::podcast::id.eq_any(podcast_ids)),
+            ).set(schema::podcast::next_crawl_at.eq(until))
+                .execute(conn)
+                .chain_err(|| "Error deferring podcasts")?;
+            info!(log, "Deferred podcasts";
+                "num_rows_updated" => num_rows_updated, "until" => until.to_rfc3339());
+            Ok(())
+        })
+    }
+
+    // Hands podcasts out to workers while staying within each host's limits.
+    //
+    // Podcasts are grouped by host as they're selected. A host's next podcast is
+    // only handed out if the host has a token in its bucket and isn't already at
+    // its maximum number of requests in flight. If all the podcasts that we have
+    // are waiting on their hosts we select more, and if we already have plenty,
+    // we wait for a worker to finish or a bucket to refill.
+    fn page_podcasts(
+        &mut self,
+        log: &Logger,
+        work_send: &Sender<PodcastTuple>,
+        done_recv: &Receiver<Done>,
+    ) -> Result<RunResult> {
         let log = log.new(o!("thread" => "control"));
         time_helpers::log_timed(&log.new(o!("step" => "page_podcasts")), |log| {
             let conn = &*(self.pool.get().map_err(Error::from))?;
 
+            let mut hosts = Hosts::new(&self.host_limits);
+            let mut exhausted = false;
             let mut last_id = 0i64;
-            let mut num_podcasts = 0i64;
-            loop {
-                let podcasts = Self::select_podcasts(log, &*conn, last_id)?;
+            let mut res = RunResult {
+                num_deferred:  0,
+                num_podcasts:  0,
+                num_throttled: 0,
+            };
 
-                // If no results came back, we're done
-                if podcasts.is_empty() {
-                    info!(log, "All podcasts consumed -- finishing");
-                    break;
+            loop {
+                // Collect anything that's finished so that in-flight counts are as up to
+                // date as possible.
+                while let Some(done) = try_recv_done(done_recv) {
+                    hosts.finish(done, Utc::now());
                 }
 
-                last_id = podcasts[podcasts.len() - 1].id;
-                num_podcasts += podcasts.len() as i64;
+                // A host that's told us to back off has any podcasts of its that we're
+                // holding pushed back until it said that it'd be ready.
+                for (until, podcast_ids) in hosts.take_deferred(Utc::now()) {
+                    res.num_deferred += podcast_ids.len() as i64;
+                    Self::defer_podcasts(log, &*conn, &podcast_ids, until)?;
+                }
 
-                for podcast in podcasts.into_iter() {
+                if let Some((podcast, throttled)) = hosts.pop_ready(Utc::now()) {
+                    if throttled {
+                        res.num_throttled += 1;
+                    }
                     work_send.send(podcast);
+                    continue;
+                }
+
+                if !exhausted && hosts.num_queued() < MAX_QUEUED {
+                    let podcasts = Self::select_podcasts(log, &*conn, last_id)?;
+
+                    // If no results came back, there's nothing left to select
+                    if podcasts.is_empty() {
+                        info!(log, "All podcasts consumed -- finishing");
+                        exhausted = true;
+                    } else {
+                        last_id = podcasts[podcasts.len() - 1].id;
+                        res.num_podcasts += podcasts.len() as i64;
+
+                        for podcast in podcasts.into_iter() {
+                            hosts.push(podcast, Utc::now());
+                        }
+                    }
+                    continue;
+                }
+
+                if exhausted && hosts.is_idle() {
+                    break;
+                }
+
+                // Everything that we're holding is waiting on its host. Sleep until a
+                // worker finishes or a bucket has a token for us.
+                let max_wait = Duration::milliseconds(MAX_WAIT_MILLISECONDS);
+                let wait = hosts
+                    .next_token_in()
+                    .map_or(max_wait, |wait| cmp::min(wait, max_wait));
+                let timeout = chan::after(wait.to_std().unwrap());
+                chan_select! {
+                    done_recv.recv() -> done => {
+                        if let Some(done) = done {
+                            hosts.finish(done, Utc::now());
+                        }
+                    },
+                    timeout.recv() => {},
                 }
             }
 
-            Ok(num_podcasts)
+            Ok(res)
         })
     }
 
@@ -107,10 +214,7 @@ impl Mediator {
                 // We select into a custom type because Diesel's query DSL cannot handle
                 // subselects.
                 diesel::sql_query(include_str!("../static/sql/podcast_crawler_select.sql"))
-                    .bind::<Text, _>(REFRESH_INTERVAL_SHORT)
-                    .bind::<Text, _>(REFRESH_INTERVAL_LONG)
                     .bind::<BigInt, _>(start_id)
-                    .bind::<BigInt, _>(JITTER_MINUTES)
                     .bind::<BigInt, _>(PAGE_SIZE)
                     .load::<PodcastTuple>(conn)
             },
@@ -120,23 +224,63 @@ impl Mediator {
     }
 }
 
+/// Limits on how hard the crawler will hit any one host.
+#[derive(Clone, Debug)]
+pub struct HostLimits {
+    /// The number of requests that a host will get in a burst before being
+    /// held to `requests_per_second`.
+    pub burst: u32,
+
+    /// The maximum number of requests to a host that can be in flight at once.
+    pub max_in_flight: u32,
+
+    /// The rate at which a host's bucket refills with tokens. Each request
+    /// takes one.
+    pub requests_per_second: f64,
+}
+
+impl Default for HostLimits {
+    fn default() -> Self {
+        HostLimits {
+            burst:               5,
+            max_in_flight:       2,
+            requests_per_second: 2.0,
+        }
+    }
+}
+
 pub struct RunResult {
+    /// Number of podcasts whose crawl was pushed back because their host
+    /// responded to one of our requests with a `429` or `503`. This includes
+    /// the podcasts that got those responses.
+    pub num_deferred: i64,
+
+    /// Number of podcasts that were selected for crawling, including any that
+    /// were deferred.
     pub num_podcasts: i64,
+
+    /// Number of podcasts that had to wait on their host's limits before
+    /// being crawled.
+    pub num_throttled: i64,
 }
 
 //
 // Private constants
 //
 
-// A random number of minutes applied to timestamps when calculating what
-// podcasts are ready to be refreshed.
-//
-// Jitter is integrated to space out big clumps of podcasts that might
-// otherwise all get worked at once and generally try to even out the crawler's
-// workload as much as possible. This is particularly useful if the `reingest`
-// command is ever used because without some jitter all podcast timestamps
-// would stay in hourly lockstep.
-const JITTER_MINUTES: i64 = 10;
+// The maximum number of podcasts that will be held waiting on their hosts. If
+// one host dominates the podcasts that are due, selecting more gives other
+// hosts a chance to be worked while it's throttled, but we have to stop
+// somewhere.
+const MAX_QUEUED: usize = 5_000;
+
+// The longest that a host will be deferred for, regardless of what its
+// `Retry-After` says.
+const MAX_RETRY_AFTER_HOURS: i64 = 24;
+
+// The longest that the control thread will sleep before checking again on
+// hosts that are waiting.
+const MAX_WAIT_MILLISECONDS: i64 = 1_000;
 
 // Work is chunked so that the database fetcher and workers and able to get
 // some parallelism. It's also useful for protecting us against the degenerate
@@ -145,29 +289,227 @@ const JITTER_MINUTES: i64 = 10;
 // amount of time to come back.
 const PAGE_SIZE: i64 = 100;
 
-// Target interval at which we want to refresh podcast feeds that are updated
-// infrequently. We back of these a little bit so that we're not incessantly
-// crawling feeds that are almost never updated (and which in some cases may
-// never be updated again). This interval shouldn't be *too* long because there
-// are some high-quality podcasts that almost never see updates, but which we'd
-// still like to see new episodes of as soon as possible (e.g., "Hardcore
-// History").
-//
-// Should be formatted as a string that's coercable to the Postgres interval
-// type.
-static REFRESH_INTERVAL_LONG: &'static str = "1 day";
-
-// Target interval at which we want to refresh podcast feeds that are updated
-// relatively frequently.
-//
-// Should be formatted as a string that's coercable to the Postgres interval
-// type.
-static REFRESH_INTERVAL_SHORT: &'static str = "1 hour";
+// How long to defer a host that's asked us to back off without saying for how
+// long.
+const RETRY_AFTER_DEFAULT_SECONDS: i64 = 5 * 60;
 
 //
 // Private types
 //
 
+// A worker's report that it's finished with a podcast.
+struct Done {
+    podcast: PodcastTuple,
+
+    // Set if the host asked us to back off, in which case the podcast wasn't
+    // crawled.
+    retry_after: Option<Duration>,
+}
+
+// The state needed to be polite to a single host.
+struct Host {
+    // Set when the host has asked us to back off. Any of its podcasts that are
+    // queued before then are deferred.
+    deferred_until: Option<DateTime<Utc>>,
+
+    num_in_flight: u32,
+    queue:         VecDeque<Queued>,
+
+    // A token bucket. Tokens are added continuously at the host's rate as long
+    // as there's room for them and each request takes one.
+    tokens:            f64,
+    tokens_updated_at: DateTime<Utc>,
+}
+
+impl Host {
+    fn new(limits: &HostLimits, now: DateTime<Utc>) -> Host {
+        Host {
+            deferred_until:    None,
+            num_in_flight:     0,
+            queue:             VecDeque::new(),
+            tokens:            f64::from(limits.burst),
+            tokens_updated_at: now,
+        }
+    }
+
+    fn is_deferred(&self, now: DateTime<Utc>) -> bool {
+        self.deferred_until.map_or(false, |until| until > now)
+    }
+
+    fn refill(&mut self, limits: &HostLimits, now: DateTime<Utc>) {
+        let elapsed = now.signed_duration_since(self.tokens_updated_at);
+        if elapsed > Duration::zero() {
+            let added = elapsed.num_milliseconds() as f64 / 1_000.0 * limits.requests_per_second;
+            self.tokens = (self.tokens + added).min(f64::from(limits.burst));
+            self.tokens_updated_at = now;
+        }
+    }
+}
+
+// Podcasts that have been selected for crawling grouped by host.
+struct Hosts<'a> {
+    hosts:  HashMap<String, Host>,
+    limits: &'a HostLimits,
+}
+
+impl<'a> Hosts<'a> {
+    fn new(limits: &'a HostLimits) -> Hosts<'a> {
+        Hosts {
+            hosts: HashMap::new(),
+            limits,
+        }
+    }
+
+    fn finish(&mut self, done: Done, now: DateTime<Utc>) {
+        if let Some(host) = self.hosts.get_mut(&host(done.podcast.feed_url.as_str())) {
+            host.num_in_flight = host.num_in_flight.saturating_sub(1);
+
+            // A podcast that the host wouldn't serve goes back in its queue so that
+            // it's deferred along with the rest of them.
+            if let Some(retry_after) = done.retry_after {
+                let until = now + retry_after;
+                host.deferred_until = Some(match host.deferred_until {
+                    Some(deferred_until) => cmp::max(deferred_until, until),
+                    None => until,
+                });
+                host.queue.push_front(Queued {
+                    podcast:   done.podcast,
+                    throttled: false,
+                });
+            }
+        }
+    }
+
+    // Whether there's nothing left waiting or in flight.
+    fn is_idle(&self) -> bool {
+        self.hosts
+            .values()
+            .all(|host| host.queue.is_empty() && host.num_in_flight == 0)
+    }
+
+    // How long until one of the hosts that's waiting for a token gets one. `None`
+    // if no host is waiting on one.
+    fn next_token_in(&self) -> Option<Duration> {
+        self.hosts
+            .values()
+            .filter(|host| {
+                !host.queue.is_empty() && host.deferred_until.is_none()
+                    && host.num_in_flight < self.limits.max_in_flight
+                    && host.tokens < 1.0
+            })
+            .map(|host| {
+                let seconds = (1.0 - host.tokens) / self.limits.requests_per_second;
+                Duration::milliseconds((seconds * 1_000.0).ceil() as i64)
+            })
+            .min()
+    }
+
+    fn num_queued(&self) -> usize {
+        self.hosts.values().map(|host| host.queue.len()).sum()
+    }
+
+    // Takes the next podcast that's ready to be crawled along with whether it had
+    // to wait on its host. Of the hosts that are ready, the one whose next
+    // podcast has the lowest ID goes first so that work proceeds roughly in the
+    // order that it was selected.
+    fn pop_ready(&mut self, now: DateTime<Utc>) -> Option<(PodcastTuple, bool)> {
+        let limits = self.limits;
+
+        let mut next: Option<(i64, String)> = None;
+        for (name, host) in &mut self.hosts {
+            if host.queue.is_empty() || host.is_deferred(now) {
+                continue;
+            }
+
+            host.refill(limits, now);
+            if host.num_in_flight >= limits.max_in_flight || host.tokens < 1.0 {
+                host.queue[0].throttled = true;
+                continue;
+            }
+
+            let id = host.queue[0].podcast.id;
+            if next.as_ref().map_or(true, |&(next_id, _)| id < next_id) {
+                next = Some((id, name.clone()));
+            }
+        }
+
+        let (_, name) = next?;
+        let host = self.hosts.get_mut(&name).unwrap();
+        host.num_in_flight += 1;
+        host.tokens -= 1.0;
+        host.queue
+            .pop_front()
+            .map(|queued| (queued.podcast, queued.throttled))
+    }
+
+    fn push(&mut self, podcast: PodcastTuple, now: DateTime<Utc>) {
+        let limits = self.limits;
+        self.hosts
+            .entry(host(podcast.feed_url.as_str()))
+            .or_insert_with(|| Host::new(limits, now))
+            .queue
+            .push_back(Queued {
+                podcast,
+                throttled: false,
+            });
+    }
+
+    // Removes the podcasts of any host that's asked us to back off, returning
+    // their IDs along with when the host will be ready for them. Hosts whose
+    // deferral has run out go back to normal.
+    fn take_deferred(&mut self, now: DateTime<Utc>) -> Vec<(DateTime<Utc>, Vec<i64>)> {
+        let mut deferred = Vec::new();
+        for host in self.hosts.values_mut() {
+            match host.deferred_until {
+                Some(until) if until > now => {
+                    if !host.queue.is_empty() {
+                        let podcast_ids = host.queue.drain(..).map(|q| q.podcast.id).collect();
+                        deferred.push((until, podcast_ids));
+                    }
+                }
+                Some(_) => host.deferred_until = None,
+                None => (),
+            }
+        }
+        deferred
+    }
+}
+
+// Watches the responses that pass through it for a host asking us to back off,
+// and fails the request if one does so that the updater doesn't treat it like
+// a broken feed.
+//
+// The updater streams feeds, so `execute_streaming` is passed through as well
+// rather than falling back to the default that buffers the whole body.
+struct HttpRequesterRetryAfter<'a> {
+    http_requester: &'a mut HttpRequester,
+    retry_after:    Option<Duration>,
+}
+
+impl<'a> HttpRequester for HttpRequesterRetryAfter<'a> {
+    fn execute(&mut self, log: &Logger, req: Request) -> Result<Response> {
+        let res = self.http_requester.execute(log, req)?;
+        if let Some(retry_after) = throttled(log, res.status, &res.headers) {
+            self.retry_after = Some(retry_after);
+            bail!(ErrorKind::Throttled(res.final_url));
+        }
+        Ok(res)
+    }
+
+    fn execute_streaming<'b>(
+        &'b mut self,
+        log: &Logger,
+        req: Request,
+    ) -> Result<StreamingResponse<'b>> {
+        let res = self.http_requester.execute_streaming(log, req)?;
+        if let Some(retry_after) = throttled(log, res.status, &res.headers) {
+            self.retry_after = Some(retry_after);
+            bail!(ErrorKind::Throttled(res.final_url));
+        }
+        Ok(res)
+    }
+}
+
 // Exists because `sql_query` doesn't support querying into a tuple, only a
 // struct.
 #[derive(Debug, QueryableByName)]
@@ -180,15 +522,76 @@ struct PodcastTuple {
     feed_url: String,
 }
 
+struct Queued {
+    podcast: PodcastTuple,
+
+    // Set if the podcast was ever next in line for its host, but had to wait.
+    throttled: bool,
+}
+
 //
 // Private functions
 //
 
+// Gets the host that a feed is served from. Podcasts are grouped by it so that
+// limits apply across all of a hosting provider's feeds.
+fn host(feed_url: &str) -> String {
+    Url::parse(feed_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned))
+        .unwrap_or_else(|| feed_url.to_owned())
+}
+
+// Gets how long a host wants us to wait from a response's `Retry-After`
+// header, which is either a number of seconds or a date.
+fn retry_after(headers: &Headers) -> Duration {
+    let retry_after = match headers.get::<RetryAfter>() {
+        Some(&RetryAfter::Delay(delay)) => Duration::from_std(delay).ok(),
+        Some(&RetryAfter::DateTime(date)) => SystemTime::from(date)
+            .duration_since(SystemTime::now())
+            .ok()
+            .and_then(|delay| Duration::from_std(delay).ok())
+            .or_else(|| Some(Duration::zero())),
+        None => None,
+    };
+
+    cmp::min(
+        retry_after.unwrap_or_else(|| Duration::seconds(RETRY_AFTER_DEFAULT_SECONDS)),
+        Duration::hours(MAX_RETRY_AFTER_HOURS),
+    )
+}
+
+// Checks whether a response is a host asking us to back off, and if so, for
+// how long.
+fn throttled(log: &Logger, status: StatusCode, headers: &Headers) -> Option<Duration> {
+    if status != StatusCode::TooManyRequests && status != StatusCode::ServiceUnavailable {
+        return None;
+    }
+
+    let retry_after = retry_after(headers);
+    info!(log, "Host asked us to back off";
+        "retry_after_seconds" => retry_after.num_seconds(),
+        "status" => status.as_u16());
+    Some(retry_after)
+}
+
+// Receives a worker's report if there's one waiting, but doesn't block if
+// there isn't.
+fn try_recv_done(done_recv: &Receiver<Done>) -> Option<Done> {
+    let mut done = None;
+    chan_select! {
+        default => {},
+        done_recv.recv() -> value => done = value,
+    }
+    done
+}
+
 fn work(
     log: &Logger,
     pool: &Pool<ConnectionManager<PgConnection>>,
     http_requester_factory: &HttpRequesterFactory,
     work_recv: &Receiver<PodcastTuple>,
+    done_send: &Sender<Done>,
 ) -> Result<()> {
     debug!(log, "Thread waiting for a connection");
     let conn = pool.get()?;
@@ -207,23 +610,46 @@ fn work(
                 };
 
                 let feed_url = podcast.feed_url.to_string();
+                let mut requester = HttpRequesterRetryAfter {
+                    http_requester: &mut *http_requester,
+                    retry_after:    None,
+                };
 
                 let res = podcast_updater::Mediator {
                     conn: &*conn,
                     // Allow the updater to short circuit if it turns out the podcast doesn't need
                     // to be updated
                     disable_shortcut: false,
+                    enqueue_jobs: true,
                     feed_url,
-                    http_requester: &mut *http_requester,
+                    follow_pages: false,
+                    http_requester: &mut requester,
                 }.run(log);
 
                 if let Err(e) = res {
-                    error_helpers::print_error(log, &e);
+                    match *e.kind() {
+                        // Not an error as such. The podcast gets deferred until the host is
+                        // ready for us.
+                        ErrorKind::Throttled(_) => {
+                            info!(log, "Host throttled podcast update -- deferring");
+                        }
+                        _ => {
+                            error_helpers::print_error(log, &e);
 
-                    if let Err(inner_e) = error_helpers::report_error(log, &e) {
-                        error_helpers::print_error(log, &inner_e);
+                            if let Err(inner_e) = error_helpers::report_error(log, &e) {
+                                error_helpers::print_error(log, &inner_e);
+                            }
+                        }
                     }
                 }
+
+                // Always reported, even on error, so that the host's request is no
+                // longer counted as in flight.
+                let retry_after = requester.retry_after;
+                done_send.send(Done {
+                    podcast,
+                    retry_after,
+                });
             },
         }
     }
@@ -232,21 +658,24 @@ fn work(
 
 #[cfg(test)]
 mod tests {
-    use http_requester::HttpRequesterFactoryPassThrough;
+    use crawl_scheduler;
+    use http_requester::{HttpRequesterFactoryPassThrough, HttpRequesterPassThrough};
     use mediators::podcast_crawler::*;
     use schema;
     use test_data;
     use test_helpers;
 
     use chrono::Utc;
+    use hyper::{Headers, Method, Uri};
     use r2d2::{Pool, PooledConnection};
     use r2d2_diesel::ConnectionManager;
+    use std::str::FromStr;
     use std::sync::Arc;
     use time::Duration;
 
     #[test]
     #[ignore]
-    fn test_crawler_short_interval_update() {
+    fn test_crawler_update() {
         let mut bootstrap = TestBootstrap::new();
 
         // Insert lots of data to be crawled
@@ -255,9 +684,9 @@ mod tests {
             test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
         }
 
-        // Mark all podcasts as stale so that the crawler will find them
+        // Mark all podcasts as due so that the crawler will find them
         diesel::update(schema::podcast::table)
-            .set(schema::podcast::last_retrieved_at.eq(Utc::now() - Duration::hours(24)))
+            .set(schema::podcast::next_crawl_at.eq(Utc::now() - Duration::minutes(1)))
             .execute(&*bootstrap.conn)
             .unwrap();
 
@@ -270,12 +699,12 @@ mod tests {
 
     #[test]
     #[ignore]
-    fn test_crawler_short_interval_no_update() {
+    fn test_crawler_no_update() {
         let mut bootstrap = TestBootstrap::new();
 
         // Just add one podcast given no data will be crawled anyway: any inserted
-        // podcasts are marked as last_retrieved_at too recently, so the
-        // crawler will ignore them
+        // podcasts have their next crawl scheduled in the future, so the crawler
+        // will ignore them
         test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
 
         let (mut mediator, log) = bootstrap.mediator();
@@ -290,17 +719,15 @@ mod tests {
 
         test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
 
-        diesel::update(schema::podcast_feed_content::table)
-            .set(schema::podcast_feed_content::retrieved_at.eq(Utc::now() - Duration::weeks(52)))
-            .execute(&*bootstrap.conn)
-            .unwrap();
-
-        // Mark podcast as *very* stale so that the crawler will find them despite it
-        // not having been updated in a long time.
-        diesel::update(schema::podcast::table)
-            .set(schema::podcast::last_retrieved_at.eq(Utc::now() - Duration::weeks(1)))
-            .execute(&*bootstrap.conn)
-            .unwrap();
+        // A podcast that hasn't published anything in a long time is only checked
+        // rarely, but it's been long enough since it was last crawled that it's
+        // due again.
+        set_next_crawl_at(
+            &*bootstrap.conn,
+            Utc::now() - Duration::weeks(1) - Duration::days(1),
+            &dormant_published_at(),
+            false,
+        );
 
         debug!(&bootstrap.log, "Finished setup (starting the real test)");
 
@@ -316,19 +743,36 @@ mod tests {
 
         test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
 
-        diesel::update(schema::podcast_feed_content::table)
-            .set(schema::podcast_feed_content::retrieved_at.eq(Utc::now() - Duration::weeks(52)))
-            .execute(&*bootstrap.conn)
-            .unwrap();
+        // Long enough ago that an active podcast would be due, but not a dormant
+        // one.
+        set_next_crawl_at(
+            &*bootstrap.conn,
+            Utc::now() - Duration::hours(12),
+            &dormant_published_at(),
+            false,
+        );
 
-        // Mark podcast as somewhere between the refresh intervals of our long and
-        // short intervals (with a good bit of padding to avoid non-determinism
-        // that might be caused by jitter). not having been updated in a long
-        // time.
-        diesel::update(schema::podcast::table)
-            .set(schema::podcast::last_retrieved_at.eq(Utc::now() - Duration::hours(12)))
-            .execute(&*bootstrap.conn)
-            .unwrap();
+        debug!(&bootstrap.log, "Finished setup (starting the real test)");
+
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+        assert_eq!(0, res.num_podcasts);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_crawler_pushed_no_update() {
+        let mut bootstrap = TestBootstrap::new();
+
+        test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+
+        // Long enough ago to be crawled if its updates weren't being pushed to us.
+        set_next_crawl_at(
+            &*bootstrap.conn,
+            Utc::now() - Duration::hours(24),
+            &[],
+            true,
+        );
 
         debug!(&bootstrap.log, "Finished setup (starting the real test)");
 
@@ -337,10 +781,334 @@ mod tests {
         assert_eq!(0, res.num_podcasts);
     }
 
+    #[test]
+    #[ignore]
+    fn test_crawler_retry_after() {
+        let mut bootstrap = TestBootstrap::new();
+
+        // Test podcasts are all served from the same host.
+        let num_podcasts = 5;
+        for _i in 0..num_podcasts {
+            test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        }
+
+        diesel::update(schema::podcast::table)
+            .set(schema::podcast::next_crawl_at.eq(Utc::now() - Duration::minutes(1)))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        debug!(&bootstrap.log, "Finished setup (starting the real test)");
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.host_limits.max_in_flight = 1;
+            mediator.http_requester_factory = Box::new(HttpRequesterFactoryTooManyRequests {});
+            mediator.run(&log).unwrap()
+        };
+
+        // The first request tells us to back off, so it and everything else is
+        // deferred.
+        assert_eq!(num_podcasts, res.num_podcasts);
+        assert_eq!(num_podcasts, res.num_deferred);
+        assert_eq!(
+            Ok(num_podcasts),
+            schema::podcast::table
+                .filter(schema::podcast::next_crawl_at.gt(Utc::now() + Duration::seconds(50)))
+                .filter(schema::podcast::next_crawl_at.lt(Utc::now() + Duration::seconds(70)))
+                .count()
+                .first(&*bootstrap.conn)
+        );
+
+        // Being throttled isn't a problem with the feed, so nothing is recorded.
+        assert_eq!(
+            Ok(0),
+            schema::podcast_exception::table
+                .count()
+                .first(&*bootstrap.conn)
+        );
+    }
+
+    #[test]
+    fn test_crawler_hosts_in_flight() {
+        let limits = HostLimits {
+            burst:               10,
+            max_in_flight:       2,
+            requests_per_second: 1.0,
+        };
+        let mut hosts = Hosts::new(&limits);
+        let now = Utc::now();
+
+        hosts.push(podcast(1, "https://a.example.com/1.xml"), now);
+        hosts.push(podcast(2, "https://a.example.com/2.xml"), now);
+        hosts.push(podcast(3, "https://a.example.com/3.xml"), now);
+        hosts.push(podcast(4, "https://b.example.com/4.xml"), now);
+
+        assert_eq!(Some((1, false)), pop_ready(&mut hosts, now));
+        assert_eq!(Some((2, false)), pop_ready(&mut hosts, now));
+
+        // `a.example.com` is at its limit, so `b.example.com` goes next.
+        assert_eq!(Some((4, false)), pop_ready(&mut hosts, now));
+        assert_eq!(None, pop_ready(&mut hosts, now));
+        assert!(!hosts.is_idle());
+
+        hosts.finish(done(1, "https://a.example.com/1.xml", None), now);
+        assert_eq!(Some((3, true)), pop_ready(&mut hosts, now));
+
+        hosts.finish(done(2, "https://a.example.com/2.xml", None), now);
+        hosts.finish(done(3, "https://a.example.com/3.xml", None), now);
+        hosts.finish(done(4, "https://b.example.com/4.xml", None), now);
+        assert!(hosts.is_idle());
+    }
+
+    #[test]
+    fn test_crawler_hosts_tokens() {
+        let limits = HostLimits {
+            burst:               1,
+            max_in_flight:       10,
+            requests_per_second: 2.0,
+        };
+        let mut hosts = Hosts::new(&limits);
+        let now = Utc::now();
+
+        hosts.push(podcast(1, "https://example.com/1.xml"), now);
+        hosts.push(podcast(2, "https://example.com/2.xml"), now);
+
+        assert_eq!(Some((1, false)), pop_ready(&mut hosts, now));
+        assert_eq!(None, pop_ready(&mut hosts, now));
+        assert_eq!(Some(Duration::milliseconds(500)), hosts.next_token_in());
+
+        let now = now + Duration::milliseconds(500);
+        assert_eq!(Some((2, true)), pop_ready(&mut hosts, now));
+        assert_eq!(None, hosts.next_token_in());
+    }
+
+    #[test]
+    fn test_crawler_hosts_deferred() {
+        let limits = HostLimits::default();
+        let mut hosts = Hosts::new(&limits);
+        let now = Utc::now();
+
+        hosts.push(podcast(1, "https://example.com/1.xml"), now);
+        hosts.push(podcast(2, "https://example.com/2.xml"), now);
+        hosts.push(podcast(3, "https://example.com/3.xml"), now);
+        assert_eq!(Some((1, false)), pop_ready(&mut hosts, now));
+
+        // The podcast that the host wouldn't serve is deferred too.
+        hosts.finish(
+            done(1, "https://example.com/1.xml", Some(Duration::seconds(60))),
+            now,
+        );
+        assert_eq!(
+            vec![(now + Duration::seconds(60), vec![1, 2, 3])],
+            hosts.take_deferred(now)
+        );
+        assert!(hosts.is_idle());
+
+        // Podcasts that come along during the deferral are deferred too.
+        hosts.push(podcast(4, "https://example.com/4.xml"), now);
+        assert_eq!(None, pop_ready(&mut hosts, now));
+        assert_eq!(
+            vec![(now + Duration::seconds(60), vec![4])],
+            hosts.take_deferred(now)
+        );
+
+        // But once it's over, the host is back to normal.
+        let now = now + Duration::seconds(61);
+        hosts.push(podcast(5, "https://example.com/5.xml"), now);
+        assert!(hosts.take_deferred(now).is_empty());
+        assert_eq!(Some((5, false)), pop_ready(&mut hosts, now));
+    }
+
+    #[test]
+    fn test_crawler_host() {
+        assert_eq!("example.com", host("https://Example.com/feed.xml"));
+        assert_eq!("example.com", host("http://example.com:8080/feed.xml"));
+        assert_eq!("not a url", host("not a url"));
+    }
+
+    #[test]
+    fn test_crawler_retry_after_header() {
+        let response = |retry_after: Option<RetryAfter>| {
+            let mut headers = Headers::new();
+            if let Some(retry_after) = retry_after {
+                headers.set(retry_after);
+            }
+            headers
+        };
+
+        assert_eq!(
+            Duration::seconds(120),
+            retry_after(&response(Some(RetryAfter::Delay(
+                Duration::seconds(120).to_std().unwrap()
+            ))))
+        );
+        assert_eq!(
+            Duration::seconds(RETRY_AFTER_DEFAULT_SECONDS),
+            retry_after(&response(None))
+        );
+        assert_eq!(
+            Duration::hours(MAX_RETRY_AFTER_HOURS),
+            retry_after(&response(Some(RetryAfter::Delay(
+                Duration::days(365).to_std().unwrap()
+            ))))
+        );
+    }
+
+    #[test]
+    fn test_crawler_retry_after_streaming() {
+        let log = test_helpers::log();
+
+        // Feeds come through the streaming path, which never buffers them.
+        {
+            let mut http_requester = HttpRequesterStreamingOnly {
+                inner: HttpRequesterPassThrough {
+                    data: Arc::new(test_helpers::MINIMAL_FEED.to_vec()),
+                },
+            };
+            let mut requester = HttpRequesterRetryAfter {
+                http_requester: &mut http_requester,
+                retry_after:    None,
+            };
+            let mut res = requester
+                .execute_streaming(&log, request())
+                .unwrap();
+            assert_eq!(test_helpers::MINIMAL_FEED.to_vec(), res.body.read_all().unwrap());
+        }
+
+        // A host asking us to back off is still noticed.
+        {
+            let mut http_requester = HttpRequesterTooManyRequests {};
+            let mut requester = HttpRequesterRetryAfter {
+                http_requester: &mut http_requester,
+                retry_after:    None,
+            };
+            let e = requester
+                .execute_streaming(&log, request())
+                .err()
+                .unwrap();
+            match *e.kind() {
+                ErrorKind::Throttled(_) => (),
+                _ => panic!("Unexpected error: {}", e),
+            }
+            assert_eq!(Some(Duration::seconds(60)), requester.retry_after);
+        }
+    }
+
     //
     // Private types/functions
     //
 
+    // Answers every request with a `429` asking us to come back in a minute.
+    #[derive(Clone, Debug)]
+    struct HttpRequesterFactoryTooManyRequests {}
+
+    impl HttpRequesterFactory for HttpRequesterFactoryTooManyRequests {
+        fn clone_box(&self) -> Box<HttpRequesterFactory> {
+            Box::new(Self {})
+        }
+
+        fn create(&self) -> Box<HttpRequester> {
+            Box::new(HttpRequesterTooManyRequests {})
+        }
+    }
+
+    struct HttpRequesterTooManyRequests {}
+
+    impl HttpRequester for HttpRequesterTooManyRequests {
+        fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+            let mut headers = Headers::new();
+            headers.set(RetryAfter::Delay(Duration::seconds(60).to_std().unwrap()));
+            Ok(Response {
+                body:               Vec::new(),
+                final_url:          req.uri().to_string(),
+                headers:            headers,
+                last_url:           req.uri().to_string(),
+                permanent_redirect: false,
+                status:             StatusCode::TooManyRequests,
+            })
+        }
+    }
+
+    // Serves a feed, but only through `execute_streaming`. Buffering the body
+    // with `execute` fails.
+    struct HttpRequesterStreamingOnly {
+        inner: HttpRequesterPassThrough,
+    }
+
+    impl HttpRequester for HttpRequesterStreamingOnly {
+        fn execute(&mut self, _log: &Logger, _req: Request) -> Result<Response> {
+            bail!("Request body should have been streamed")
+        }
+
+        fn execute_streaming<'a>(
+            &'a mut self,
+            log: &Logger,
+            req: Request,
+        ) -> Result<StreamingResponse<'a>> {
+            self.inner.execute_streaming(log, req)
+        }
+    }
+
+    // Publication times of a podcast that hasn't released anything in a long
+    // time.
+    fn dormant_published_at() -> Vec<DateTime<Utc>> {
+        vec![
+            Utc::now() - Duration::weeks(52),
+            Utc::now() - Duration::weeks(53),
+        ]
+    }
+
+    fn done(id: i64, feed_url: &str, retry_after: Option<Duration>) -> Done {
+        Done {
+            podcast: podcast(id, feed_url),
+            retry_after,
+        }
+    }
+
+    fn podcast(id: i64, feed_url: &str) -> PodcastTuple {
+        PodcastTuple {
+            id,
+            feed_url: feed_url.to_owned(),
+        }
+    }
+
+    // Pops the next podcast that's ready, returning just its ID so that it's easy
+    // to compare.
+    fn pop_ready(hosts: &mut Hosts, now: DateTime<Utc>) -> Option<(i64, bool)> {
+        hosts
+            .pop_ready(now)
+            .map(|(podcast, throttled)| (podcast.id, throttled))
+    }
+
+    fn request() -> Request {
+        Request::new(
+            Method::Get,
+            Uri::from_str("https://example.com/feed.xml").unwrap(),
+        )
+    }
+
+    // Schedules every podcast's next crawl as if it had last been crawled at
+    // `crawled_at` with the given publishing history.
+    fn set_next_crawl_at(
+        conn: &PgConnection,
+        crawled_at: DateTime<Utc>,
+        published_at: &[DateTime<Utc>],
+        pushed: bool,
+    ) {
+        let next_crawl_at = crawl_scheduler::next_crawl_at(
+            crawled_at,
+            &crawl_scheduler::History {
+                num_exceptions: 0,
+                published_at,
+                pushed,
+            },
+        );
+        diesel::update(schema::podcast::table)
+            .set(schema::podcast::next_crawl_at.eq(next_crawl_at))
+            .execute(conn)
+            .unwrap();
+    }
+
     struct TestBootstrap {
         _common: test_helpers::CommonTestBootstrap,
         conn:    PooledConnection<ConnectionManager<PgConnection>>,
@@ -367,6 +1135,13 @@ mod tests {
                     // minus another one for a connection that a test case
                     // might be using for setup.
                     num_workers:            test_helpers::MAX_NUM_CONNECTIONS - 1 - 1,
+                    // Test podcasts are all served from the same host, so its limits are
+                    // generous enough that they don't slow tests down.
+                    host_limits:            HostLimits {
+                        burst:               1_000,
+                        max_in_flight:       1_000,
+                        requests_per_second: 1_000.0,
+                    },
                     pool:                   self.pool.clone(),
                     http_requester_factory: Box::new(HttpRequesterFactoryPassThrough {
                         data: Arc::new(test_helpers::MINIMAL_FEED.to_vec()),
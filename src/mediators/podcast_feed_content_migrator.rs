@@ -0,0 +1,442 @@
+use blob_store;
+use errors::*;
+use model;
+use schema;
+use time_helpers;
+
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use flate2::read::GzDecoder;
+use slog::Logger;
+use std::io::prelude::*;
+
+/// Moves podcast feed content that was stored inline as gzip over to the blob
+/// store. Optionally trains a new compression dictionary first, in which case
+/// blobs that were compressed without it are rewritten as well.
+pub struct Mediator<'a> {
+    pub conn: &'a PgConnection,
+
+    /// Train a new dictionary from a sample of existing content before
+    /// migrating.
+    pub train_dictionary: bool,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        // Training happens first so that migrated content gets compressed with the
+        // new dictionary.
+        let dictionary = if self.train_dictionary {
+            let samples = self.select_samples(log)?;
+            Some(blob_store::train_dictionary(log, self.conn, &samples)?)
+        } else {
+            None
+        };
+
+        // Every batch nulls out the content that it migrated, so keep going until
+        // there's nothing left.
+        let mut num_migrated = 0;
+        loop {
+            let num_batch = self.conn
+                .transaction::<_, Error, _>(|| self.migrate_content_batch(log))?;
+            if num_batch < 1 {
+                break;
+            }
+
+            num_migrated += num_batch;
+            info!(log, "Migrated batch"; "num_migrated" => num_migrated);
+        }
+
+        let mut num_recompressed = 0;
+        if let Some(ref dictionary) = dictionary {
+            let mut last_id = 0i64;
+            loop {
+                let blobs = self.select_blob_batch(log, dictionary, last_id)?;
+                if blobs.is_empty() {
+                    break;
+                }
+
+                last_id = blobs[blobs.len() - 1].id;
+                num_recompressed += self.conn
+                    .transaction::<_, Error, _>(|| self.recompress_blob_batch(log, &blobs))?;
+                info!(log, "Recompressed batch"; "num_recompressed" => num_recompressed);
+            }
+        }
+
+        Ok(RunResult {
+            dictionary,
+            num_migrated,
+            num_recompressed,
+        })
+    }
+
+    //
+    // Steps
+    //
+
+    fn migrate_content_batch(&mut self, log: &Logger) -> Result<i64> {
+        let contents: Vec<model::PodcastFeedContent> = time_helpers::log_timed(
+            &log.new(o!("step" => "select_podcast_feed_content_batch", "limit" => BATCH_SIZE)),
+            |_log| {
+                schema::podcast_feed_content::table
+                    .filter(schema::podcast_feed_content::content_gzip.is_not_null())
+                    .order(schema::podcast_feed_content::id)
+                    .limit(BATCH_SIZE)
+                    .load(self.conn)
+                    .chain_err(|| "Error selecting podcast feed content batch")
+            },
+        )?;
+
+        for content in &contents {
+            let data = gunzip(content.content_gzip.as_ref().unwrap())?;
+            blob_store::store(log, self.conn, content.sha256_hash.as_str(), data.as_slice())?;
+        }
+
+        let ids: Vec<i64> = contents.iter().map(|c| c.id).collect();
+        time_helpers::log_timed(
+            &log.new(o!("step" => "update_podcast_feed_content_batch")),
+            |_log| {
+                diesel::update(
+                    schema::podcast_feed_content::table
+                        .filter(schema::podcast_feed_content::id.eq_any(ids)),
+                ).set(schema::podcast_feed_content::content_gzip.eq(None::<Vec<u8>>))
+                    .execute(self.conn)
+                    .chain_err(|| "Error updating podcast feed content batch")
+            },
+        )?;
+
+        Ok(contents.len() as i64)
+    }
+
+    fn recompress_blob_batch(&mut self, log: &Logger, blobs: &[model::ContentBlob]) -> Result<i64> {
+        time_helpers::log_timed(&log.new(o!("step" => "recompress_blob_batch")), |log| {
+            let mut num_recompressed = 0;
+            for blob in blobs {
+                if blob_store::recompress(log, self.conn, blob)? {
+                    num_recompressed += 1;
+                }
+            }
+            Ok(num_recompressed)
+        })
+    }
+
+    fn select_blob_batch(
+        &mut self,
+        log: &Logger,
+        dictionary: &model::ContentDictionary,
+        start_id: i64,
+    ) -> Result<Vec<model::ContentBlob>> {
+        time_helpers::log_timed(
+            &log.new(o!("step" => "select_blob_batch", "start_id" => start_id)),
+            |_log| {
+                schema::content_blob::table
+                    .filter(schema::content_blob::id.gt(start_id))
+                    .filter(
+                        schema::content_blob::dictionary_id
+                            .is_null()
+                            .or(schema::content_blob::dictionary_id.ne(dictionary.id)),
+                    )
+                    .order(schema::content_blob::id)
+                    .limit(BATCH_SIZE)
+                    .load(self.conn)
+                    .chain_err(|| "Error selecting content blob batch")
+            },
+        )
+    }
+
+    // Selects the most recent content of a sample of podcasts to train a
+    // dictionary with. Only first pages are used because they're the most
+    // representative of what we'll be storing in the future. Feeds can be huge,
+    // so content is loaded one sample at a time and we stop once we have as
+    // many bytes as training can make good use of.
+    fn select_samples(&mut self, log: &Logger) -> Result<Vec<Vec<u8>>> {
+        let contents: Vec<(i64, String)> = time_helpers::log_timed(
+            &log.new(o!("step" => "select_samples", "limit" => DICTIONARY_NUM_SAMPLES)),
+            |_log| {
+                schema::podcast_feed_content::table
+                    .filter(schema::podcast_feed_content::page_number.eq(1))
+                    .order(schema::podcast_feed_content::retrieved_at.desc())
+                    .select((
+                        schema::podcast_feed_content::id,
+                        schema::podcast_feed_content::sha256_hash,
+                    ))
+                    .limit(DICTIONARY_NUM_SAMPLES)
+                    .load(self.conn)
+                    .chain_err(|| "Error selecting podcast feed content samples")
+            },
+        )?;
+
+        let mut samples = Vec::new();
+        let mut samples_size = 0;
+        for &(id, ref sha256_hash) in &contents {
+            let remaining = DICTIONARY_MAX_SAMPLES_SIZE - samples_size;
+            if remaining < 1 {
+                break;
+            }
+
+            let content_gzip: Option<Vec<u8>> = time_helpers::log_timed(
+                &log.new(o!("step" => "select_sample_content_gzip", "id" => id)),
+                |_log| {
+                    schema::podcast_feed_content::table
+                        .find(id)
+                        .select(schema::podcast_feed_content::content_gzip)
+                        .first(self.conn)
+                        .chain_err(|| "Error selecting podcast feed content sample")
+                },
+            )?;
+
+            // A sample that would take us over the limit is truncated, which still
+            // leaves plenty that's representative in it.
+            let data = match content_gzip {
+                Some(ref content_gzip) => {
+                    let mut data = Vec::new();
+                    GzDecoder::new(content_gzip.as_slice())
+                        .take(remaining as u64)
+                        .read_to_end(&mut data)?;
+                    Some(data)
+                }
+                None => blob_store::load(log, self.conn, sha256_hash.as_str())?,
+            };
+            if let Some(mut data) = data {
+                data.truncate(remaining);
+                samples_size += data.len();
+                samples.push(data);
+            }
+        }
+
+        info!(log, "Selected samples";
+            "num_samples" => samples.len(), "samples_size" => samples_size);
+        Ok(samples)
+    }
+}
+
+pub struct RunResult {
+    /// The dictionary that was trained, if one was requested.
+    pub dictionary: Option<model::ContentDictionary>,
+
+    pub num_migrated:     i64,
+    pub num_recompressed: i64,
+}
+
+//
+// Private constants
+//
+
+// The number of rows to migrate in a single transaction.
+const BATCH_SIZE: i64 = 100;
+
+// The maximum total size in bytes of the samples to train a dictionary with.
+// Zstandard suggests that a good dictionary needs on the order of a hundred
+// times its own size in samples, and more than that only makes training slower
+// and hungrier for memory.
+const DICTIONARY_MAX_SAMPLES_SIZE: usize = 100 * blob_store::DICTIONARY_MAX_SIZE;
+
+// The maximum number of samples to train a dictionary with. Feeds are usually
+// small enough that this is what limits a sample rather than its total size.
+const DICTIONARY_NUM_SAMPLES: i64 = 1000;
+
+//
+// Private functions
+//
+
+fn gunzip(content_gzip: &[u8]) -> Result<Vec<u8>> {
+    let mut decoder = GzDecoder::new(content_gzip);
+    let mut content: Vec<u8> = Vec::new();
+    decoder.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use mediators::podcast_feed_content_migrator::*;
+    use test_data;
+    use test_helpers;
+
+    use model::insertable;
+
+    use chrono::Utc;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+
+    #[test]
+    fn test_podcast_feed_content_migrate() {
+        let mut bootstrap = TestBootstrap::new();
+
+        // Turn the podcast's content back into what it looked like before the
+        // blob store existed.
+        let content: model::PodcastFeedContent = schema::podcast_feed_content::table
+            .filter(schema::podcast_feed_content::podcast_id.eq(bootstrap.podcast.id))
+            .first(&*bootstrap.conn)
+            .unwrap();
+        diesel::delete(schema::content_blob::table)
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(test_helpers::MINIMAL_FEED).unwrap();
+        diesel::update(schema::podcast_feed_content::table)
+            .filter(schema::podcast_feed_content::id.eq(content.id))
+            .set(schema::podcast_feed_content::content_gzip.eq(encoder.finish().unwrap()))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert!(res.dictionary.is_none());
+        assert_eq!(1, res.num_migrated);
+        assert_eq!(0, res.num_recompressed);
+
+        let content: model::PodcastFeedContent = schema::podcast_feed_content::table
+            .filter(schema::podcast_feed_content::id.eq(content.id))
+            .first(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(None, content.content_gzip);
+        assert_eq!(
+            Some(test_helpers::MINIMAL_FEED.to_vec()),
+            blob_store::load(&bootstrap.log, &*bootstrap.conn, content.sha256_hash.as_str())
+                .unwrap()
+        );
+
+        // A second run has nothing left to do.
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert_eq!(0, res.num_migrated);
+    }
+
+    #[test]
+    fn test_podcast_feed_content_migrate_train_dictionary() {
+        let mut bootstrap = TestBootstrap::new();
+
+        // Zstandard won't train a dictionary from just a couple of samples, so
+        // store some more content that looks like a feed.
+        for i in 0..NUM_SAMPLES {
+            let sha256_hash = format!("{:064}", i);
+            let feed = sample_feed(i);
+            blob_store::store(
+                &bootstrap.log,
+                &*bootstrap.conn,
+                sha256_hash.as_str(),
+                feed.as_bytes(),
+            ).unwrap();
+            diesel::insert_into(schema::podcast_feed_content::table)
+                .values(&insertable::PodcastFeedContent {
+                    podcast_id: bootstrap.podcast.id,
+                    retrieved_at: Utc::now(),
+                    sha256_hash,
+                    page_number: 1,
+                })
+                .execute(&*bootstrap.conn)
+                .unwrap();
+        }
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.train_dictionary = true;
+            mediator.run(&log).unwrap()
+        };
+        assert_eq!(0, res.num_migrated);
+
+        // Every blob was compressed without a dictionary, so they're all
+        // recompressed with the new one.
+        let dictionary = res.dictionary.unwrap();
+        assert_eq!(NUM_SAMPLES as i32 + 1, dictionary.num_samples);
+        assert_eq!(NUM_SAMPLES + 1, res.num_recompressed);
+
+        let blobs: Vec<model::ContentBlob> = schema::content_blob::table
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert!(
+            blobs
+                .iter()
+                .all(|blob| blob.dictionary_id == Some(dictionary.id))
+        );
+
+        // And content still comes back out the same way that it went in.
+        assert_eq!(
+            Some(sample_feed(0).into_bytes()),
+            blob_store::load(
+                &bootstrap.log,
+                &*bootstrap.conn,
+                format!("{:064}", 0).as_str()
+            ).unwrap()
+        );
+    }
+
+    //
+    // Private types/functions
+    //
+
+    const NUM_SAMPLES: i64 = 50;
+
+    fn sample_feed(i: i64) -> String {
+        let items: Vec<String> = (0..20)
+            .map(|j| {
+                format!(
+                    r#"
+    <item>
+      <guid>{0}-{1}</guid>
+      <media:content url="https://example.com/podcast-{0}/episode-{1}.mp3" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Episode {1} of Podcast {0}</title>
+    </item>"#,
+                    i, j
+                )
+            })
+            .collect();
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <link>https://example.com/podcast-{}</link>
+    <title>Podcast {}</title>{}
+  </channel>
+</rss>"#,
+            i,
+            i,
+            items.concat()
+        )
+    }
+
+    struct TestBootstrap {
+        _common: test_helpers::CommonTestBootstrap,
+        conn:    PooledConnection<ConnectionManager<PgConnection>>,
+        log:     Logger,
+        podcast: model::Podcast,
+    }
+
+    impl TestBootstrap {
+        fn new() -> TestBootstrap {
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+
+            TestBootstrap {
+                _common: test_helpers::CommonTestBootstrap::new(),
+                podcast: test_data::podcast::insert(&log, &conn),
+
+                // Only move these after filling the above
+                conn: conn,
+                log:  log,
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    conn:             &*self.conn,
+                    train_dictionary: false,
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
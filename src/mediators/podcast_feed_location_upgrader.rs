@@ -1,25 +1,82 @@
 use errors::*;
+use http_requester::{HttpRequester, HttpRequesterFactory};
+use mediators::podcast_updater;
+use model::insertable;
+use schema;
 use time_helpers;
 
+use chrono::Utc;
 use diesel;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Text};
+use hyper::header::ContentType;
+use hyper::{Method, Request, StatusCode, Uri};
 use slog::Logger;
-
+use std::collections::HashMap;
+use std::str::FromStr;
+use url::Url;
+
+/// Adds `https://` locations for podcasts that we only know at an `http://`
+/// one.
+///
+/// Hosts already known to support HTTPS (because we have another feed from
+/// them over it) are upgraded without further ado. Every other host is probed
+/// by requesting one of its feeds over HTTPS, and is only considered to
+/// support it if what comes back is the same feed.
 pub struct Mediator<'a> {
-    pub conn: &'a PgConnection,
+    pub conn:                   &'a PgConnection,
+    pub http_requester_factory: Box<HttpRequesterFactory>,
 }
 
 impl<'a> Mediator<'a> {
     pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
-        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| {
-            self.conn.transaction::<_, Error, _>(|| self.run_inner(log))
-        })
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
     }
 
+    // There's no transaction around this run. Each insert stands on its own,
+    // and we don't want one held open while we're waiting on remote hosts.
     fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let num_upgraded = Self::insert_https_feed_locations(log, self.conn)?;
+
+        let locations = self.select_unsecured_locations(log)?;
+        let mut http_requester = self.http_requester_factory.create();
+
+        // Whether each host supports HTTPS, keyed by host, so that each one is
+        // only probed once.
+        let mut hosts: HashMap<String, bool> = HashMap::new();
+
+        let mut num_probed = 0;
+        let mut num_probe_upgraded = 0;
+        for location in &locations {
+            let host = match Url::parse(location.feed_url.as_str())
+                .ok()
+                .and_then(|u| u.host_str().map(|h| h.to_owned()))
+            {
+                Some(host) => host,
+                None => continue,
+            };
+
+            let supported = match hosts.get(&host).cloned() {
+                Some(supported) => supported,
+                None => {
+                    num_probed += 1;
+                    let supported = Self::probe_location(log, &mut *http_requester, location);
+                    info!(log, "Probed host for HTTPS";
+                        "host" => host.as_str(), "supported" => supported);
+                    hosts.insert(host, supported);
+                    supported
+                }
+            };
+
+            if supported && Self::insert_https_feed_location(log, self.conn, location)? {
+                num_probe_upgraded += 1;
+            }
+        }
+
         Ok(RunResult {
-            num_upgraded: Self::insert_https_feed_locations(log, self.conn)?,
+            num_probed,
+            num_upgraded: num_upgraded + num_probe_upgraded,
         })
     }
 
@@ -27,6 +84,85 @@ impl<'a> Mediator<'a> {
     // Steps
     //
 
+    // Fetches a feed and fingerprints it. Any kind of failure (the request
+    // failing, an unsuccessful status, an `https://` request that was redirected
+    // away from HTTPS, or a body that isn't a feed) results in `None`.
+    fn fetch_fingerprint(
+        log: &Logger,
+        http_requester: &mut HttpRequester,
+        url: &str,
+    ) -> Option<podcast_updater::FeedFingerprint> {
+        time_helpers::log_timed(&log.new(o!("step" => "fetch_fingerprint")), |log| {
+            let uri = match Uri::from_str(url) {
+                Ok(uri) => uri,
+                Err(_) => return None,
+            };
+
+            let res = match http_requester.execute(log, Request::new(Method::Get, uri)) {
+                Ok(res) => res,
+                Err(e) => {
+                    info!(log, "Error fetching feed"; "url" => url, "error" => e.to_string());
+                    return None;
+                }
+            };
+
+            if res.status != StatusCode::Ok {
+                info!(log, "Unsuccessful status fetching feed";
+                    "url" => url, "status" => res.status.as_u16());
+                return None;
+            }
+
+            // Hosts with a broken TLS setup often redirect back to `http://`, where
+            // we'd get the same feed even though HTTPS doesn't work for it.
+            if is_https(url) && !is_https(res.last_url.as_str()) {
+                info!(log, "Redirected away from HTTPS fetching feed";
+                    "url" => url, "last_url" => res.last_url.as_str());
+                return None;
+            }
+
+            let content_type = res.headers.get::<ContentType>().map(|h| h.to_string());
+            match podcast_updater::fingerprint_feed(
+                log,
+                res.body.as_slice(),
+                content_type.as_ref().map(String::as_str),
+            ) {
+                Ok(fingerprint) => Some(fingerprint),
+                Err(e) => {
+                    info!(log, "Error parsing feed"; "url" => url, "error" => e.to_string());
+                    None
+                }
+            }
+        })
+    }
+
+    // Inserts an `https://` location to go along with the given `http://` one,
+    // returning whether one was inserted. Feed URLs are unique across all
+    // podcasts, so nothing is inserted if another podcast already has it.
+    fn insert_https_feed_location(
+        log: &Logger,
+        conn: &PgConnection,
+        location: &UnsecuredLocation,
+    ) -> Result<bool> {
+        let num_inserted =
+            time_helpers::log_timed(&log.new(o!("step" => "insert_https_feed_location")), |_log| {
+                diesel::insert_into(schema::podcast_feed_location::table)
+                    .values(&insertable::PodcastFeedLocation {
+                        first_retrieved_at: Utc::now(),
+                        feed_url:           https_url(location.feed_url.as_str()),
+                        last_retrieved_at:  Utc::now(),
+                        podcast_id:         location.podcast_id,
+                        etag:               None,
+                        last_modified:      None,
+                        superseded_at:      None,
+                    })
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .chain_err(|| "Error inserting podcast feed location")
+            })?;
+
+        Ok(num_inserted > 0)
+    }
+
     fn insert_https_feed_locations(log: &Logger, conn: &PgConnection) -> Result<i64> {
         let res = time_helpers::log_timed(
             &log.new(o!("step" => "insert_https_feed_locations")),
@@ -41,14 +177,97 @@ impl<'a> Mediator<'a> {
 
         Ok(res as i64)
     }
+
+    // Probes a host for HTTPS support by requesting one of its feeds over it.
+    // The host supports HTTPS if we get back the same content that we last
+    // stored for the podcast, or failing that (a lot of feeds change on every
+    // request because of build dates and the like), a feed with the same title
+    // and episode GUIDs as the one at the `http://` location.
+    fn probe_location(
+        log: &Logger,
+        http_requester: &mut HttpRequester,
+        location: &UnsecuredLocation,
+    ) -> bool {
+        time_helpers::log_timed(&log.new(o!("step" => "probe_location")), |log| {
+            let secured = match Self::fetch_fingerprint(
+                log,
+                http_requester,
+                https_url(location.feed_url.as_str()).as_str(),
+            ) {
+                Some(fingerprint) => fingerprint,
+                None => return false,
+            };
+
+            if location.sha256_hash.as_ref() == Some(&secured.sha256_hash) {
+                return true;
+            }
+
+            match Self::fetch_fingerprint(log, http_requester, location.feed_url.as_str()) {
+                Some(unsecured) => {
+                    secured.title == unsecured.title
+                        && secured.episode_guids == unsecured.episode_guids
+                }
+                None => false,
+            }
+        })
+    }
+
+    fn select_unsecured_locations(&mut self, log: &Logger) -> Result<Vec<UnsecuredLocation>> {
+        time_helpers::log_timed(
+            &log.new(o!("step" => "select_unsecured_locations")),
+            |_log| {
+                diesel::sql_query(include_str!(
+                    "../static/sql/podcast_feed_location_upgrader_select.sql"
+                )).load::<UnsecuredLocation>(self.conn)
+                    .chain_err(|| "Error selecting unsecured podcast feed locations")
+            },
+        )
+    }
 }
 
 pub struct RunResult {
+    /// The number of hosts that were probed for HTTPS support.
+    pub num_probed: i64,
+
     pub num_upgraded: i64,
 }
 
+//
+// Private types
+//
+
+// An `http://` location without an `https://` counterpart. Exists because
+// `sql_query` doesn't support querying into a tuple, only a struct.
+#[derive(Clone, Debug, QueryableByName)]
+struct UnsecuredLocation {
+    #[sql_type = "Text"]
+    feed_url: String,
+
+    #[sql_type = "BigInt"]
+    podcast_id: i64,
+
+    /// The hash of the podcast's most recently stored content.
+    #[sql_type = "Nullable<Text>"]
+    sha256_hash: Option<String>,
+}
+
+//
+// Private functions
+//
+
+fn https_url(url: &str) -> String {
+    format!("https://{}", url.trim_left_matches("http://"))
+}
+
+fn is_https(url: &str) -> bool {
+    Url::parse(url)
+        .map(|u| u.scheme() == "https")
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
+    use http_requester::Response;
     use mediators::podcast_feed_location_upgrader::*;
     use model;
     use model::insertable;
@@ -57,6 +276,8 @@ mod tests {
     use test_helpers;
 
     use chrono::Utc;
+    use hyper::Headers;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_upgrades_unsecured_location() {
@@ -164,7 +385,7 @@ mod tests {
 
         // Insert an unsecured podcast, but at a different host (even a subdomain is a
         // different host). This should be ignored by the mediator's run
-        // because we don't know whether or not it supports HTTPS.
+        // because probing the host doesn't get us the same feed over HTTPS.
         let podcast = test_data::podcast::insert_args(
             &bootstrap.log,
             &*bootstrap.conn,
@@ -176,6 +397,7 @@ mod tests {
         {
             let (mut mediator, log) = bootstrap.mediator();
             let res = mediator.run(&log).unwrap();
+            assert_eq!(1, res.num_probed);
             assert_eq!(0, res.num_upgraded);
         }
 
@@ -185,6 +407,152 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_upgrades_probed_location() {
+        let conn = test_helpers::connection();
+        let mut bootstrap = TestBootstrapWithConn::new(&*conn);
+
+        // The same feed that the podcast was inserted with, so its hash matches
+        // the stored content.
+        bootstrap.data = Arc::new(test_helpers::MINIMAL_FEED.to_vec());
+
+        let podcast = test_data::podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::podcast::Args {
+                feed_url: Some("http://example.com/feed.xml".to_owned()),
+            },
+        );
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            let res = mediator.run(&log).unwrap();
+            assert_eq!(1, res.num_probed);
+            assert_eq!(1, res.num_upgraded);
+        }
+
+        assert_eq!(
+            vec![
+                "http://example.com/feed.xml",
+                "https://example.com/feed.xml",
+            ],
+            select_feed_urls(&*conn, &podcast)
+        );
+        assert_eq!(
+            vec!["https://example.com/feed.xml"],
+            *bootstrap.requested_urls.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_upgrades_probed_location_same_episodes() {
+        let conn = test_helpers::connection();
+        let mut bootstrap = TestBootstrapWithConn::new(&*conn);
+
+        let podcast = test_data::podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::podcast::Args {
+                feed_url: Some("http://example.com/feed.xml".to_owned()),
+            },
+        );
+
+        // The feed has changed since it was stored, but it has the same title
+        // and episodes over both HTTP and HTTPS.
+        bootstrap.data = Arc::new(
+            String::from_utf8(test_helpers::MINIMAL_FEED.to_vec())
+                .unwrap()
+                .replace(
+                    "<title>Title</title>",
+                    "<title>Title</title><ttl>60</ttl>",
+                )
+                .into_bytes(),
+        );
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            let res = mediator.run(&log).unwrap();
+            assert_eq!(1, res.num_upgraded);
+        }
+
+        assert_eq!(
+            vec![
+                "https://example.com/feed.xml",
+                "http://example.com/feed.xml",
+            ],
+            *bootstrap.requested_urls.lock().unwrap()
+        );
+        assert_eq!(2, select_feed_urls(&*conn, &podcast).len());
+    }
+
+    #[test]
+    fn test_ignores_probe_redirected_to_http() {
+        let conn = test_helpers::connection();
+        let mut bootstrap = TestBootstrapWithConn::new(&*conn);
+
+        // The same feed that the podcast was inserted with, but it only comes back
+        // after the host redirects us to plain HTTP.
+        bootstrap.data = Arc::new(test_helpers::MINIMAL_FEED.to_vec());
+        bootstrap.redirect_to_http = true;
+
+        let podcast = test_data::podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::podcast::Args {
+                feed_url: Some("http://example.com/feed.xml".to_owned()),
+            },
+        );
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            let res = mediator.run(&log).unwrap();
+            assert_eq!(1, res.num_probed);
+            assert_eq!(0, res.num_upgraded);
+        }
+
+        assert_eq!(
+            vec!["http://example.com/feed.xml"],
+            select_feed_urls(&*conn, &podcast)
+        );
+    }
+
+    #[test]
+    fn test_probes_host_once() {
+        let conn = test_helpers::connection();
+        let mut bootstrap = TestBootstrapWithConn::new(&*conn);
+
+        let podcast1 = test_data::podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::podcast::Args {
+                feed_url: Some("http://example.com/feed-1.xml".to_owned()),
+            },
+        );
+        let podcast2 = test_data::podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::podcast::Args {
+                feed_url: Some("http://example.com/feed-2.xml".to_owned()),
+            },
+        );
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            let res = mediator.run(&log).unwrap();
+            assert_eq!(1, res.num_probed);
+            assert_eq!(0, res.num_upgraded);
+        }
+
+        // The first probe failed, so the second location's host wasn't tried
+        // again.
+        assert_eq!(
+            vec!["https://example.com/feed-1.xml"],
+            *bootstrap.requested_urls.lock().unwrap()
+        );
+        assert_eq!(1, select_feed_urls(&*conn, &podcast1).len());
+        assert_eq!(1, select_feed_urls(&*conn, &podcast2).len());
+    }
+
     #[test]
     fn test_ignores_secured_location() {
         let conn = test_helpers::connection();
@@ -214,6 +582,9 @@ mod tests {
                 feed_url:           "https://example.com/feed.xml".to_owned(),
                 last_retrieved_at:  Utc::now(),
                 podcast_id:         podcast.id,
+                etag:               None,
+                last_modified:      None,
+                superseded_at:      None,
             })
             .execute(&*conn)
             .unwrap();
@@ -237,23 +608,104 @@ mod tests {
     // Private types/functions
     //
 
+    // Creates requesters that respond to every request with the same data and
+    // keep track of the URLs that were requested.
+    struct HttpRequesterFactoryRecording {
+        data:             Arc<Vec<u8>>,
+        redirect_to_http: bool,
+        requested_urls:   Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpRequesterFactory for HttpRequesterFactoryRecording {
+        fn clone_box(&self) -> Box<HttpRequesterFactory> {
+            Box::new(Self {
+                data:             Arc::clone(&self.data),
+                redirect_to_http: self.redirect_to_http,
+                requested_urls:   Arc::clone(&self.requested_urls),
+            })
+        }
+
+        fn create(&self) -> Box<HttpRequester> {
+            Box::new(HttpRequesterRecording {
+                data:             Arc::clone(&self.data),
+                redirect_to_http: self.redirect_to_http,
+                requested_urls:   Arc::clone(&self.requested_urls),
+            })
+        }
+    }
+
+    struct HttpRequesterRecording {
+        data:           Arc<Vec<u8>>,
+        requested_urls: Arc<Mutex<Vec<String>>>,
+
+        // Acts as if every `https://` request was temporarily redirected to its
+        // `http://` equivalent.
+        redirect_to_http: bool,
+    }
+
+    impl HttpRequester for HttpRequesterRecording {
+        fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+            self.requested_urls
+                .lock()
+                .unwrap()
+                .push(req.uri().to_string());
+
+            let url = req.uri().to_string();
+            let last_url = if self.redirect_to_http {
+                url.replace("https://", "http://")
+            } else {
+                url.clone()
+            };
+
+            Ok(Response {
+                body:               self.data.to_vec(),
+                final_url:          url,
+                headers:            Headers::new(),
+                last_url:           last_url,
+                permanent_redirect: false,
+                status:             StatusCode::Ok,
+            })
+        }
+    }
+
     struct TestBootstrapWithConn<'a> {
-        _common: test_helpers::CommonTestBootstrap,
-        conn:    &'a PgConnection,
-        log:     Logger,
+        _common:        test_helpers::CommonTestBootstrap,
+        conn:           &'a PgConnection,
+        log:            Logger,
+        requested_urls: Arc<Mutex<Vec<String>>>,
+
+        // Data that's returned for every probe. By default it's not a feed, so
+        // probes fail.
+        data: Arc<Vec<u8>>,
+
+        // See `HttpRequesterRecording`.
+        redirect_to_http: bool,
     }
 
     impl<'a> TestBootstrapWithConn<'a> {
         fn new(conn: &'a PgConnection) -> TestBootstrapWithConn<'a> {
             TestBootstrapWithConn {
-                _common: test_helpers::CommonTestBootstrap::new(),
-                conn:    conn,
-                log:     test_helpers::log(),
+                _common:        test_helpers::CommonTestBootstrap::new(),
+                conn:           conn,
+                data:             Arc::new(b"Not a feed".to_vec()),
+                log:              test_helpers::log(),
+                redirect_to_http: false,
+                requested_urls:   Arc::new(Mutex::new(Vec::new())),
             }
         }
 
         fn mediator(&mut self) -> (Mediator, Logger) {
-            (Mediator { conn: self.conn }, self.log.clone())
+            (
+                Mediator {
+                    conn:                   self.conn,
+                    http_requester_factory: Box::new(HttpRequesterFactoryRecording {
+                        data:             Arc::clone(&self.data),
+                        redirect_to_http: self.redirect_to_http,
+                        requested_urls:   Arc::clone(&self.requested_urls),
+                    }),
+                },
+                self.log.clone(),
+            )
         }
     }
 
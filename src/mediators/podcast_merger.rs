@@ -0,0 +1,890 @@
+use errors::*;
+use model;
+use schema;
+use time_helpers;
+
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use slog::Logger;
+use std::collections::HashMap;
+
+/// Merges podcasts that are the same show stored more than once. Podcasts are
+/// looked up by their exact feed URL, so one that's reachable at a few of them
+/// (say over both HTTP and HTTPS, or through a tracking redirect) can end up
+/// being added once for each.
+///
+/// The oldest podcast of each set of duplicates survives. Everything pointing
+/// to the others is moved onto it, and the others are then removed. All merges
+/// happen in one transaction, so a run either merges everything that it found
+/// or nothing at all.
+pub struct Mediator<'a> {
+    pub conn: &'a PgConnection,
+
+    /// Find duplicates, but don't merge them.
+    pub dry_run: bool,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let pairs = self.select_duplicate_pairs(log)?;
+        let merges = plan_merges(&pairs);
+
+        if !self.dry_run {
+            self.conn.transaction::<_, Error, _>(|| {
+                for merge in &merges {
+                    let log = log.new(o!(
+                        "duplicate_id" => merge.duplicate_id,
+                        "survivor_id" => merge.survivor_id
+                    ));
+                    self.merge_podcast(&log, merge)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(RunResult { merges })
+    }
+
+    fn merge_podcast(&mut self, log: &Logger, merge: &Merge) -> Result<()> {
+        // Episodes that the survivor already has (by GUID) are mapped onto its
+        // copies so that accounts' progress on them carries over.
+        let episode_ids = self.select_duplicate_episodes(log, merge)?;
+
+        let account_podcast_ids = self.merge_account_podcasts(log, merge)?;
+        self.merge_account_podcast_episodes(log, &account_podcast_ids, &episode_ids)?;
+        self.delete_account_podcasts(log, &account_podcast_ids)?;
+
+        self.delete_podcast_extras(log, merge)?;
+        self.merge_episodes(log, merge, &episode_ids)?;
+        self.move_podcast_references(log, merge)?;
+        self.delete_podcast(log, merge)?;
+
+        info!(log, "Merged podcast";
+            "num_duplicate_episodes" => episode_ids.len());
+        Ok(())
+    }
+
+    //
+    // Steps
+    //
+
+    // Deletes account podcasts that were merged into one that the account
+    // already had for the survivor.
+    fn delete_account_podcasts(
+        &mut self,
+        log: &Logger,
+        account_podcast_ids: &HashMap<i64, i64>,
+    ) -> Result<()> {
+        let ids: Vec<i64> = account_podcast_ids.keys().cloned().collect();
+        time_helpers::log_timed(&log.new(o!("step" => "delete_account_podcasts")), |_log| {
+            diesel::delete(
+                schema::account_podcast::table.filter(schema::account_podcast::id.eq_any(ids)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting account podcasts")
+        })?;
+        Ok(())
+    }
+
+    fn delete_podcast(&mut self, log: &Logger, merge: &Merge) -> Result<()> {
+        time_helpers::log_timed(&log.new(o!("step" => "delete_podcast")), |_log| {
+            diesel::delete(
+                schema::podcast::table.filter(schema::podcast::id.eq(merge.duplicate_id)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting podcast")
+        })?;
+        Ok(())
+    }
+
+    // Deletes everything belonging to the duplicate that's either replaced
+    // every time the survivor's feed is retrieved (categories, funding,
+    // persons) or specific to the duplicate's feed (its stored content and last
+    // exception). None of it is worth moving.
+    fn delete_podcast_extras(&mut self, log: &Logger, merge: &Merge) -> Result<()> {
+        time_helpers::log_timed(&log.new(o!("step" => "delete_podcast_extras")), |_log| {
+            diesel::delete(
+                schema::podcast_category::table
+                    .filter(schema::podcast_category::podcast_id.eq(merge.duplicate_id)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting podcast categories")?;
+
+            diesel::delete(
+                schema::podcast_exception::table
+                    .filter(schema::podcast_exception::podcast_id.eq(merge.duplicate_id)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting podcast exception")?;
+
+            diesel::delete(
+                schema::podcast_feed_content::table
+                    .filter(schema::podcast_feed_content::podcast_id.eq(merge.duplicate_id)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting podcast feed contents")?;
+
+            diesel::delete(
+                schema::podcast_funding::table
+                    .filter(schema::podcast_funding::podcast_id.eq(merge.duplicate_id)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting podcast fundings")?;
+
+            diesel::delete(
+                schema::podcast_person::table
+                    .filter(schema::podcast_person::podcast_id.eq(merge.duplicate_id)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting podcast persons")?;
+
+            // The survivor keeps (or makes) its own subscription to any hub.
+            diesel::delete(schema::podcast_websub_subscription::table.filter(
+                schema::podcast_websub_subscription::podcast_id.eq(merge.duplicate_id),
+            )).execute(self.conn)
+                .chain_err(|| "Error deleting podcast WebSub subscription")
+        })?;
+        Ok(())
+    }
+
+    // Moves account podcast episodes onto the survivor's account podcasts and
+    // episodes. Where the account already has a row for the survivor's copy of
+    // an episode, whichever of the two was updated most recently wins.
+    fn merge_account_podcast_episodes(
+        &mut self,
+        log: &Logger,
+        account_podcast_ids: &HashMap<i64, i64>,
+        episode_ids: &HashMap<i64, i64>,
+    ) -> Result<()> {
+        time_helpers::log_timed(
+            &log.new(o!("step" => "merge_account_podcast_episodes")),
+            |_log| {
+                let merged: Vec<model::AccountPodcastEpisode> =
+                    schema::account_podcast_episode::table
+                        .filter(
+                            schema::account_podcast_episode::account_podcast_id
+                                .eq_any(account_podcast_ids.keys().cloned().collect::<Vec<_>>())
+                                .or(schema::account_podcast_episode::episode_id
+                                    .eq_any(episode_ids.keys().cloned().collect::<Vec<_>>())),
+                        )
+                        .load(self.conn)
+                        .chain_err(|| "Error selecting account podcast episodes")?;
+
+                let existing: HashMap<(i64, i64), model::AccountPodcastEpisode> =
+                    schema::account_podcast_episode::table
+                        .filter(
+                            schema::account_podcast_episode::account_podcast_id
+                                .eq_any(account_podcast_ids.values().cloned().collect::<Vec<_>>()),
+                        )
+                        .load::<model::AccountPodcastEpisode>(self.conn)
+                        .chain_err(|| "Error selecting account podcast episodes")?
+                        .into_iter()
+                        .map(|e| ((e.account_podcast_id, e.episode_id), e))
+                        .collect();
+
+                for episode in &merged {
+                    let account_podcast_id = *account_podcast_ids
+                        .get(&episode.account_podcast_id)
+                        .unwrap_or(&episode.account_podcast_id);
+                    let episode_id = *episode_ids
+                        .get(&episode.episode_id)
+                        .unwrap_or(&episode.episode_id);
+
+                    match existing.get(&(account_podcast_id, episode_id)) {
+                        Some(other) => {
+                            if episode.updated_at > other.updated_at {
+                                diesel::update(
+                                    schema::account_podcast_episode::table
+                                        .filter(schema::account_podcast_episode::id.eq(other.id)),
+                                ).set((
+                                    schema::account_podcast_episode::favorited
+                                        .eq(episode.favorited),
+                                    schema::account_podcast_episode::listened_seconds
+                                        .eq(episode.listened_seconds),
+                                    schema::account_podcast_episode::played.eq(episode.played),
+                                    schema::account_podcast_episode::updated_at
+                                        .eq(episode.updated_at),
+                                ))
+                                    .execute(self.conn)
+                                    .chain_err(|| "Error updating account podcast episode")?;
+                            }
+
+                            diesel::delete(
+                                schema::account_podcast_episode::table
+                                    .filter(schema::account_podcast_episode::id.eq(episode.id)),
+                            ).execute(self.conn)
+                                .chain_err(|| "Error deleting account podcast episode")?;
+                        }
+                        None => {
+                            diesel::update(
+                                schema::account_podcast_episode::table
+                                    .filter(schema::account_podcast_episode::id.eq(episode.id)),
+                            ).set((
+                                schema::account_podcast_episode::account_podcast_id
+                                    .eq(account_podcast_id),
+                                schema::account_podcast_episode::episode_id.eq(episode_id),
+                            ))
+                                .execute(self.conn)
+                                .chain_err(|| "Error updating account podcast episode")?;
+                        }
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    // Moves the duplicate's account podcasts onto the survivor. An account
+    // that has both keeps its account podcast for the survivor (subscribed if
+    // it was subscribed to either), and a mapping from the duplicate's account
+    // podcast to that one is returned.
+    fn merge_account_podcasts(&mut self, log: &Logger, merge: &Merge) -> Result<HashMap<i64, i64>> {
+        time_helpers::log_timed(&log.new(o!("step" => "merge_account_podcasts")), |_log| {
+            let account_podcasts: Vec<model::AccountPodcast> = schema::account_podcast::table
+                .filter(
+                    schema::account_podcast::podcast_id
+                        .eq_any(vec![merge.duplicate_id, merge.survivor_id]),
+                )
+                .load(self.conn)
+                .chain_err(|| "Error selecting account podcasts")?;
+
+            let survivor_account_podcasts: HashMap<i64, &model::AccountPodcast> =
+                account_podcasts
+                    .iter()
+                    .filter(|ap| ap.podcast_id == merge.survivor_id)
+                    .map(|ap| (ap.account_id, ap))
+                    .collect();
+
+            let mut account_podcast_ids = HashMap::new();
+            for account_podcast in account_podcasts
+                .iter()
+                .filter(|ap| ap.podcast_id == merge.duplicate_id)
+            {
+                match survivor_account_podcasts.get(&account_podcast.account_id) {
+                    Some(other) => {
+                        if account_podcast.is_subscribed() && !other.is_subscribed() {
+                            diesel::update(
+                                schema::account_podcast::table
+                                    .filter(schema::account_podcast::id.eq(other.id)),
+                            ).set((
+                                schema::account_podcast::subscribed_at
+                                    .eq(account_podcast.subscribed_at),
+                                schema::account_podcast::unsubscribed_at
+                                    .eq(account_podcast.unsubscribed_at),
+                            ))
+                                .execute(self.conn)
+                                .chain_err(|| "Error updating account podcast")?;
+                        }
+                        account_podcast_ids.insert(account_podcast.id, other.id);
+                    }
+                    None => {
+                        diesel::update(
+                            schema::account_podcast::table
+                                .filter(schema::account_podcast::id.eq(account_podcast.id)),
+                        ).set(schema::account_podcast::podcast_id.eq(merge.survivor_id))
+                            .execute(self.conn)
+                            .chain_err(|| "Error updating account podcast")?;
+                    }
+                }
+            }
+
+            Ok(account_podcast_ids)
+        })
+    }
+
+    // Removes the duplicate's copies of episodes that the survivor already has
+    // (along with everything hanging off of them) and moves the rest over.
+    fn merge_episodes(
+        &mut self,
+        log: &Logger,
+        merge: &Merge,
+        episode_ids: &HashMap<i64, i64>,
+    ) -> Result<()> {
+        let ids: Vec<i64> = episode_ids.keys().cloned().collect();
+        time_helpers::log_timed(&log.new(o!("step" => "merge_episodes")), |_log| {
+            diesel::delete(
+                schema::episode_chapter::table
+                    .filter(schema::episode_chapter::episode_id.eq_any(&ids)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting episode chapters")?;
+
+            diesel::delete(
+                schema::episode_chapters_url::table
+                    .filter(schema::episode_chapters_url::episode_id.eq_any(&ids)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting episode chapters URLs")?;
+
+            diesel::delete(
+                schema::episode_revision::table
+                    .filter(schema::episode_revision::episode_id.eq_any(&ids)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting episode revisions")?;
+
+            diesel::delete(
+                schema::episode_transcript::table
+                    .filter(schema::episode_transcript::episode_id.eq_any(&ids)),
+            ).execute(self.conn)
+                .chain_err(|| "Error deleting episode transcripts")?;
+
+            diesel::delete(schema::episode::table.filter(schema::episode::id.eq_any(&ids)))
+                .execute(self.conn)
+                .chain_err(|| "Error deleting episodes")?;
+
+            diesel::update(
+                schema::episode::table
+                    .filter(schema::episode::podcast_id.eq(merge.duplicate_id)),
+            ).set(schema::episode::podcast_id.eq(merge.survivor_id))
+                .execute(self.conn)
+                .chain_err(|| "Error updating episodes")
+        })?;
+        Ok(())
+    }
+
+    // Moves directory podcasts and feed locations onto the survivor. Feed URLs
+    // are unique across all podcasts, so the duplicate's locations never
+    // conflict with the survivor's. The crawler only retrieves a podcast at its
+    // most recently retrieved location, so having a few is harmless.
+    fn move_podcast_references(&mut self, log: &Logger, merge: &Merge) -> Result<()> {
+        time_helpers::log_timed(&log.new(o!("step" => "move_podcast_references")), |_log| {
+            diesel::update(
+                schema::directory_podcast::table
+                    .filter(schema::directory_podcast::podcast_id.eq(merge.duplicate_id)),
+            ).set(schema::directory_podcast::podcast_id.eq(merge.survivor_id))
+                .execute(self.conn)
+                .chain_err(|| "Error updating directory podcasts")?;
+
+            diesel::update(
+                schema::podcast_feed_location::table
+                    .filter(schema::podcast_feed_location::podcast_id.eq(merge.duplicate_id)),
+            ).set(schema::podcast_feed_location::podcast_id.eq(merge.survivor_id))
+                .execute(self.conn)
+                .chain_err(|| "Error updating podcast feed locations")
+        })?;
+        Ok(())
+    }
+
+    // Maps the IDs of the duplicate's episodes to the IDs of the survivor's
+    // episodes with the same GUIDs.
+    fn select_duplicate_episodes(
+        &mut self,
+        log: &Logger,
+        merge: &Merge,
+    ) -> Result<HashMap<i64, i64>> {
+        let episodes: Vec<(i64, i64, String)> =
+            time_helpers::log_timed(&log.new(o!("step" => "select_episodes")), |_log| {
+                schema::episode::table
+                    .filter(
+                        schema::episode::podcast_id
+                            .eq_any(vec![merge.duplicate_id, merge.survivor_id]),
+                    )
+                    .select((
+                        schema::episode::id,
+                        schema::episode::podcast_id,
+                        schema::episode::guid,
+                    ))
+                    .load(self.conn)
+                    .chain_err(|| "Error selecting episodes")
+            })?;
+
+        let survivor_ids: HashMap<&str, i64> = episodes
+            .iter()
+            .filter(|e| e.1 == merge.survivor_id)
+            .map(|e| (e.2.as_str(), e.0))
+            .collect();
+
+        Ok(episodes
+            .iter()
+            .filter(|e| e.1 == merge.duplicate_id)
+            .filter_map(|e| survivor_ids.get(e.2.as_str()).map(|id| (e.0, *id)))
+            .collect())
+    }
+
+    fn select_duplicate_pairs(&mut self, log: &Logger) -> Result<Vec<DuplicatePair>> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_duplicate_pairs")), |_log| {
+            diesel::sql_query(include_str!("../static/sql/podcast_merger_select.sql"))
+                .load::<DuplicatePair>(self.conn)
+                .chain_err(|| "Error selecting duplicate podcasts")
+        })
+    }
+}
+
+pub struct RunResult {
+    /// The merges that were made (or would have been made on a dry run), in
+    /// the order that they happened.
+    pub merges: Vec<Merge>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Merge {
+    pub duplicate_id: i64,
+    pub survivor_id:  i64,
+}
+
+//
+// Private types
+//
+
+// Exists because `sql_query` doesn't support querying into a tuple, only a
+// struct.
+#[derive(Clone, Debug, QueryableByName)]
+struct DuplicatePair {
+    #[sql_type = "BigInt"]
+    duplicate_id: i64,
+
+    #[sql_type = "BigInt"]
+    survivor_id: i64,
+}
+
+//
+// Private functions
+//
+
+// Follows a podcast's chain of merges to the podcast that it'll end up as.
+fn find_survivor(survivor_ids: &HashMap<i64, i64>, id: i64) -> i64 {
+    let mut id = id;
+    while let Some(survivor_id) = survivor_ids.get(&id) {
+        id = *survivor_id;
+    }
+    id
+}
+
+// Turns pairs of duplicates into merges. Duplicates can be chained (A is a
+// duplicate of B by GUID, and B of C by link URL), in which case everything in
+// the chain is merged into its oldest podcast.
+fn plan_merges(pairs: &[DuplicatePair]) -> Vec<Merge> {
+    let mut survivor_ids: HashMap<i64, i64> = HashMap::new();
+    for pair in pairs {
+        let a = find_survivor(&survivor_ids, pair.survivor_id);
+        let b = find_survivor(&survivor_ids, pair.duplicate_id);
+        if a == b {
+            continue;
+        }
+
+        // IDs are sequential, so the smaller one belongs to the older podcast.
+        if a < b {
+            survivor_ids.insert(b, a);
+        } else {
+            survivor_ids.insert(a, b);
+        }
+    }
+
+    let mut merges: Vec<Merge> = survivor_ids
+        .keys()
+        .map(|id| Merge {
+            duplicate_id: *id,
+            survivor_id:  find_survivor(&survivor_ids, *id),
+        })
+        .collect();
+    merges.sort_by_key(|m| m.duplicate_id);
+    merges
+}
+
+#[cfg(test)]
+mod tests {
+    use http_requester::HttpRequesterPassThrough;
+    use mediators::podcast_merger::*;
+    use mediators::podcast_updater;
+    use test_data;
+    use test_helpers;
+
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_podcast_merge_guid() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let survivor = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        let duplicate = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        diesel::update(schema::podcast::table.filter(
+            schema::podcast::id.eq_any(vec![survivor.id, duplicate.id]),
+        )).set(schema::podcast::guid.eq(Some("917393e3-1b1e-5cef-ace4-edaa54e1f810")))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        // An account that's subscribed to both, and has played the duplicate's
+        // copy of the episode that they share.
+        let account = test_data::account::insert(&bootstrap.log, &*bootstrap.conn);
+        for podcast in &[&survivor, &duplicate] {
+            let _account_podcast = test_data::account_podcast::insert_args(
+                &bootstrap.log,
+                &*bootstrap.conn,
+                test_data::account_podcast::Args {
+                    account: Some(&account),
+                    podcast: Some(*podcast),
+                },
+            );
+        }
+        let _account_podcast_episode = test_data::account_podcast_episode::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::account_podcast_episode::Args {
+                account: Some(&account),
+                episode: Some(&test_data::episode::first(
+                    &bootstrap.log,
+                    &*bootstrap.conn,
+                    &duplicate,
+                )),
+            },
+        );
+
+        let dir_podcast = test_data::directory_podcast::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::directory_podcast::Args {
+                podcast: Some(&duplicate),
+            },
+        );
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert_eq!(
+            vec![
+                Merge {
+                    duplicate_id: duplicate.id,
+                    survivor_id:  survivor.id,
+                },
+            ],
+            res.merges
+        );
+
+        assert_eq!(
+            Ok(0),
+            schema::podcast::table
+                .filter(schema::podcast::id.eq(duplicate.id))
+                .count()
+                .first(&*bootstrap.conn)
+        );
+
+        let episodes: Vec<model::Episode> = schema::episode::table
+            .filter(schema::episode::podcast_id.eq_any(vec![survivor.id, duplicate.id]))
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(1, episodes.len());
+        assert_eq!(survivor.id, episodes[0].podcast_id);
+
+        let account_podcasts: Vec<model::AccountPodcast> = schema::account_podcast::table
+            .filter(schema::account_podcast::account_id.eq(account.id))
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(1, account_podcasts.len());
+        assert_eq!(survivor.id, account_podcasts[0].podcast_id);
+        assert!(account_podcasts[0].is_subscribed());
+
+        let account_podcast_episodes: Vec<model::AccountPodcastEpisode> =
+            schema::account_podcast_episode::table
+                .filter(
+                    schema::account_podcast_episode::account_podcast_id.eq(account_podcasts[0].id),
+                )
+                .load(&*bootstrap.conn)
+                .unwrap();
+        assert_eq!(1, account_podcast_episodes.len());
+        assert_eq!(episodes[0].id, account_podcast_episodes[0].episode_id);
+        assert!(account_podcast_episodes[0].played);
+
+        assert_eq!(
+            Ok(2),
+            schema::podcast_feed_location::table
+                .filter(schema::podcast_feed_location::podcast_id.eq(survivor.id))
+                .count()
+                .first(&*bootstrap.conn)
+        );
+
+        assert_eq!(
+            Ok(Some(survivor.id)),
+            schema::directory_podcast::table
+                .filter(schema::directory_podcast::id.eq(dir_podcast.id))
+                .select(schema::directory_podcast::podcast_id)
+                .first(&*bootstrap.conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_merge_link_url() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let survivor = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        let duplicate = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        let other = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        diesel::update(schema::podcast::table.filter(
+            schema::podcast::id.eq_any(vec![survivor.id, duplicate.id, other.id]),
+        )).set(schema::podcast::link_url.eq(Some("https://example.com/")))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        // A podcast that shares a link URL but none of its episodes is a
+        // different show from the same network.
+        diesel::update(schema::episode::table.filter(schema::episode::podcast_id.eq(other.id)))
+            .set(schema::episode::guid.eq("other-1"))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert_eq!(
+            vec![
+                Merge {
+                    duplicate_id: duplicate.id,
+                    survivor_id:  survivor.id,
+                },
+            ],
+            res.merges
+        );
+
+        assert_eq!(
+            Ok(2),
+            schema::podcast::table
+                .filter(schema::podcast::id.eq_any(vec![survivor.id, duplicate.id, other.id]))
+                .count()
+                .first(&*bootstrap.conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_merge_episode_overlap() {
+        let mut bootstrap = TestBootstrap::new();
+
+        // Same episodes, but no link URL or GUID to go on.
+        let feed = feed_with_episodes(&["a", "b", "c", "d", "e"]);
+        let survivor = insert_podcast(&bootstrap, "https://example.com/feed.xml", &feed);
+        let duplicate = insert_podcast(&bootstrap, "https://cdn.example.com/feed.xml", &feed);
+
+        // Too few episodes for the overlap to mean anything.
+        let small_feed = feed_with_episodes(&["a", "b"]);
+        let small1 = insert_podcast(&bootstrap, "https://example.com/small-1.xml", &small_feed);
+        let small2 = insert_podcast(&bootstrap, "https://example.com/small-2.xml", &small_feed);
+
+        // Not enough episodes in common.
+        let other = insert_podcast(
+            &bootstrap,
+            "https://example.com/other.xml",
+            &feed_with_episodes(&["a", "b", "x", "y", "z"]),
+        );
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert_eq!(
+            vec![
+                Merge {
+                    duplicate_id: duplicate.id,
+                    survivor_id:  survivor.id,
+                },
+            ],
+            res.merges
+        );
+
+        assert_eq!(
+            Ok(4),
+            schema::podcast::table
+                .filter(schema::podcast::id.eq_any(vec![
+                    survivor.id,
+                    duplicate.id,
+                    small1.id,
+                    small2.id,
+                    other.id,
+                ]))
+                .count()
+                .first(&*bootstrap.conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_merge_feed_url() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let survivor = insert_podcast(
+            &bootstrap,
+            "http://example.com/feed",
+            test_helpers::MINIMAL_FEED,
+        );
+        let duplicate = insert_podcast(
+            &bootstrap,
+            "https://example.com/feed/",
+            test_helpers::MINIMAL_FEED,
+        );
+        let other = insert_podcast(
+            &bootstrap,
+            "https://example.com/feed/other",
+            test_helpers::MINIMAL_FEED,
+        );
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert_eq!(
+            vec![
+                Merge {
+                    duplicate_id: duplicate.id,
+                    survivor_id:  survivor.id,
+                },
+            ],
+            res.merges
+        );
+
+        let feed_urls: Vec<String> = schema::podcast_feed_location::table
+            .filter(schema::podcast_feed_location::podcast_id.eq(survivor.id))
+            .select(schema::podcast_feed_location::feed_url)
+            .order(schema::podcast_feed_location::feed_url)
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(
+            vec!["http://example.com/feed", "https://example.com/feed/"],
+            feed_urls
+        );
+        assert_eq!(
+            Ok(1),
+            schema::podcast::table
+                .filter(schema::podcast::id.eq(other.id))
+                .count()
+                .first(&*bootstrap.conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_merge_dry_run() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let survivor = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        let duplicate = test_data::podcast::insert(&bootstrap.log, &*bootstrap.conn);
+        diesel::update(schema::podcast::table.filter(
+            schema::podcast::id.eq_any(vec![survivor.id, duplicate.id]),
+        )).set(schema::podcast::guid.eq(Some("917393e3-1b1e-5cef-ace4-edaa54e1f810")))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.dry_run = true;
+            mediator.run(&log).unwrap()
+        };
+        assert_eq!(1, res.merges.len());
+
+        assert_eq!(
+            Ok(2),
+            schema::podcast::table
+                .filter(schema::podcast::id.eq_any(vec![survivor.id, duplicate.id]))
+                .count()
+                .first(&*bootstrap.conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_merge_plan_merges() {
+        let pairs = vec![
+            DuplicatePair {
+                duplicate_id: 2,
+                survivor_id:  1,
+            },
+            DuplicatePair {
+                duplicate_id: 3,
+                survivor_id:  2,
+            },
+            DuplicatePair {
+                duplicate_id: 3,
+                survivor_id:  1,
+            },
+            DuplicatePair {
+                duplicate_id: 5,
+                survivor_id:  4,
+            },
+        ];
+        assert_eq!(
+            vec![
+                Merge {
+                    duplicate_id: 2,
+                    survivor_id:  1,
+                },
+                Merge {
+                    duplicate_id: 3,
+                    survivor_id:  1,
+                },
+                Merge {
+                    duplicate_id: 5,
+                    survivor_id:  4,
+                },
+            ],
+            plan_merges(&pairs)
+        );
+    }
+
+    //
+    // Private types/functions
+    //
+
+    // Builds a feed that has an episode for each of the given GUIDs.
+    fn feed_with_episodes(guids: &[&str]) -> Vec<u8> {
+        let items: String = guids
+            .iter()
+            .map(|guid| {
+                format!(
+                    r#"<item>
+      <guid>{}</guid>
+      <media:content url="https://example.com/{}.mp3" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Episode {}</title>
+    </item>"#,
+                    guid, guid, guid
+                )
+            })
+            .collect();
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    {}
+  </channel>
+</rss>"#,
+            items
+        ).into_bytes()
+    }
+
+    fn insert_podcast(bootstrap: &TestBootstrap, feed_url: &str, data: &[u8]) -> model::Podcast {
+        podcast_updater::Mediator {
+            conn:             &*bootstrap.conn,
+            disable_shortcut: false,
+            enqueue_jobs:     true,
+            feed_url:         feed_url.to_owned(),
+            follow_pages:     false,
+            http_requester:   &mut HttpRequesterPassThrough {
+                data: Arc::new(data.to_vec()),
+            },
+        }.run(&bootstrap.log)
+            .unwrap()
+            .podcast
+    }
+
+    struct TestBootstrap {
+        _common: test_helpers::CommonTestBootstrap,
+        conn:    PooledConnection<ConnectionManager<PgConnection>>,
+        log:     Logger,
+    }
+
+    impl TestBootstrap {
+        fn new() -> TestBootstrap {
+            TestBootstrap {
+                _common: test_helpers::CommonTestBootstrap::new(),
+                conn:    test_helpers::connection(),
+                log:     test_helpers::log(),
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    conn:    &*self.conn,
+                    dry_run: false,
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use blob_store;
 use error_helpers;
 use errors::*;
 use http_requester::HttpRequesterPassThrough;
@@ -10,7 +11,7 @@ use chan::{Receiver, Sender};
 use diesel;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
-use diesel::sql_types::{BigInt, Bytea, Text};
+use diesel::sql_types::{BigInt, Bytea, Nullable, Text};
 use flate2::read::GzDecoder;
 use r2d2::Pool;
 use r2d2_diesel::ConnectionManager;
@@ -121,16 +122,13 @@ impl Mediator {
                 // ...)`). This might be solvable somehow, but examples in tests and
                 // documentation are quite poor, so I gave up and fell back to
                 // this.
+                //
+                // Content that's been moved to the blob store only has its hash selected
+                // here. It's loaded by a worker so that the control thread doesn't
+                // have to decompress anything.
                 diesel::sql_query(
                     "
-                SELECT id,
-                    (
-                       SELECT content_gzip
-                       FROM podcast_feed_content
-                       WHERE podcast_feed_content.podcast_id = podcast.id
-                       ORDER BY retrieved_at DESC
-                       LIMIT 1
-                    ),
+                SELECT id, content.content_gzip, content.sha256_hash,
                     (
                        SELECT feed_url
                        FROM podcast_feed_location
@@ -139,6 +137,14 @@ impl Mediator {
                        LIMIT 1
                     )
                 FROM podcast
+                    INNER JOIN LATERAL (
+                        SELECT content_gzip, sha256_hash
+                        FROM podcast_feed_content
+                        WHERE podcast_feed_content.podcast_id = podcast.id
+                            AND podcast_feed_content.page_number = 1
+                        ORDER BY retrieved_at DESC
+                        LIMIT 1
+                    ) content ON TRUE
                 WHERE id > $1
                 ORDER BY id
                 LIMIT $2",
@@ -172,11 +178,15 @@ struct PodcastTuple {
     #[sql_type = "BigInt"]
     id: i64,
 
-    #[sql_type = "Bytea"]
-    content_gzip: Vec<u8>,
+    // Only set for content that hasn't been moved to the blob store yet.
+    #[sql_type = "Nullable<Bytea>"]
+    content_gzip: Option<Vec<u8>>,
 
     #[sql_type = "Text"]
     feed_url: String,
+
+    #[sql_type = "Text"]
+    sha256_hash: String,
 }
 
 // Private functions
@@ -216,9 +226,21 @@ fn work(
 }
 
 fn work_inner(log: &Logger, conn: &PgConnection, podcast_tuple: &PodcastTuple) -> Result<()> {
-    let mut decoder = GzDecoder::new(podcast_tuple.content_gzip.as_slice());
-    let mut content: Vec<u8> = Vec::new();
-    decoder.read_to_end(&mut content)?;
+    let content: Vec<u8> = match podcast_tuple.content_gzip {
+        Some(ref content_gzip) => {
+            let mut decoder = GzDecoder::new(content_gzip.as_slice());
+            let mut content: Vec<u8> = Vec::new();
+            decoder.read_to_end(&mut content)?;
+            content
+        }
+        None => blob_store::load(log, conn, podcast_tuple.sha256_hash.as_str())?
+            .ok_or_else(|| {
+                Error::from(format!(
+                    "No content blob for podcast {} with hash {}",
+                    podcast_tuple.id, podcast_tuple.sha256_hash
+                ))
+            })?,
+    };
 
     let feed_url = podcast_tuple.feed_url.to_string();
 
@@ -229,7 +251,17 @@ fn work_inner(log: &Logger, conn: &PgConnection, podcast_tuple: &PodcastTuple) -
         // sure that we've disabled any shortcuts that might otherwise be enabled.
         disable_shortcut: true,
 
+        // Reingesting only brings the database up to date with how feeds are
+        // parsed now. Episodes and hubs were already taken care of when the
+        // content was first processed, so no jobs are enqueued for them.
+        enqueue_jobs: false,
+
         feed_url,
+
+        // Only a feed's first page is reingested. Its other pages are stored
+        // separately and were already processed when they were fetched.
+        follow_pages: false,
+
         http_requester: &mut HttpRequesterPassThrough {
             data: Arc::new(content),
         },
@@ -1,33 +1,78 @@
+use blob_store;
+use crawl_scheduler;
 use error_helpers;
 use errors::*;
+use date_parser;
 use html;
-use http_requester::HttpRequester;
+use http_requester::{BodyReader, HttpRequester, StreamingResponse};
+use jobs;
+use links;
 use mediators::common;
 use model;
 use model::insertable;
 use schema;
 use time_helpers;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use crypto::digest::Digest;
 use crypto::sha2::Sha256;
 use diesel;
 use diesel::pg::upsert::excluded;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
-use flate2::write::GzEncoder;
-use flate2::Compression;
+use encoding_rs::{CoderResult, Decoder, Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use hyper::header::{ContentType, ETag, LastModified};
 use hyper::{Method, Request, StatusCode, Uri};
 use quick_xml::events::attributes::Attribute;
-use quick_xml::events::{BytesText, Event};
+use quick_xml::events::{BytesStart, BytesText, Event};
 use quick_xml::reader::Reader;
-use regex::Regex;
+use rand;
+use rand::distributions::Alphanumeric;
+use rand::{EntropyRng, Rng};
+use regex::bytes;
+use serde_json;
 use slog::Logger;
-use std::collections::HashSet;
-use std::io::prelude::*;
-use std::io::BufRead;
+use std::cmp;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::iter;
+use std::ops::Range;
 use std::str;
 use std::str::FromStr;
+use url::Url;
+
+/// A random number of minutes added to a podcast's next crawl time.
+///
+/// Jitter is integrated to space out big clumps of podcasts that might
+/// otherwise all get worked at once and generally try to even out the
+/// crawler's workload as much as possible. This is particularly useful if the
+/// `reingest` command is ever used because without some jitter all podcast
+/// schedules would stay in lockstep.
+const CRAWL_JITTER_MINUTES: i64 = 10;
+
+/// The number of a podcast's most recent episodes that are considered when
+/// scheduling its next crawl.
+const CRAWL_SCHEDULE_NUM_EPISODES: i64 = 50;
+
+/// The maximum number of pages (including the first) that will be retrieved
+/// when following a paged feed's `next` links.
+const MAX_FEED_PAGES: i32 = 25;
+
+/// The size of the buffers that a feed is decoded through.
+const DECODE_BUFFER_SIZE: usize = 16 * 1024;
+
+/// How much of the start of a feed is examined to decide what encoding it's
+/// in.
+const DECODE_PREFIX_SIZE: usize = 64 * 1024;
+
+/// How long before a WebSub subscription's lease runs out that we'll ask its
+/// hub to renew it. Also how long we'll wait for a hub to verify a request
+/// before making another one.
+const WEBSUB_RENEWAL_HOURS: i64 = 24;
+
+/// The length of the secret shared with a WebSub hub.
+const WEBSUB_SECRET_LENGTH: usize = 40;
 
 pub struct Mediator<'a> {
     pub conn: &'a PgConnection,
@@ -37,7 +82,19 @@ pub struct Mediator<'a> {
     /// will skip this check and force all processing.
     pub disable_shortcut: bool,
 
-    pub feed_url:       String,
+    /// Enqueue jobs for follow-up work on what's found in the feed, like
+    /// probing new episodes' media and subscribing to a WebSub hub. Setting
+    /// this value to `false` only updates the database, which is what
+    /// reingesting previously processed content calls for.
+    pub enqueue_jobs: bool,
+
+    pub feed_url: String,
+
+    /// Follow a paged feed's `next` links (RFC 5005) to retrieve its older
+    /// episodes. This always happens when a podcast is first added, but
+    /// setting this value to `true` will also do it for an existing podcast.
+    pub follow_pages: bool,
+
     pub http_requester: &'a mut HttpRequester,
 }
 
@@ -52,7 +109,13 @@ impl<'a> Mediator<'a> {
                     // fails. Otherwise, the crawler will attempt to process it over and over again
                     // because the entire transaction rolled back. We also store an exception
                     // record to ease debugging.
-                    self.run_recovery(log, &e);
+                    //
+                    // A requester that's been throttled by the host is the exception. The feed
+                    // isn't at fault, so it's up to the caller to decide when to try again.
+                    match *e.kind() {
+                        ErrorKind::Throttled(_) => (),
+                        _ => self.run_recovery(log, &e),
+                    }
 
                     // Return the original error because it's going to be more useful.
                     Err(e)
@@ -72,15 +135,120 @@ impl<'a> Mediator<'a> {
         //
         // In the case where no URLs existed, the function just returns the same URL
         // back to us.
-        let latest_url = self.select_latest_url(log, self.feed_url.as_str())?;
+        let mut latest_url = self.select_latest_url(log, self.feed_url.as_str())?;
+
+        // If we've fetched this URL before, we may have validators (`ETag` and
+        // `Last-Modified`) from the last response that we can send back to let the
+        // host tell us that nothing has changed without sending the feed again.
+        let location = if self.disable_shortcut {
+            None
+        } else {
+            self.select_location(log, latest_url.as_str())?
+        };
+
+        let mut res = self.read_feed(log, latest_url.as_str(), location)?;
+
+        // People tend to give us the URL of a podcast's homepage rather than the URL
+        // of its feed. If what came back is a web page, look for a feed that it
+        // advertises and start over with that instead. We only go one level deep.
+        let mut discovered_url = None;
+        if let FeedContent::Html(url) = res.content {
+            let url = match url {
+                Some(url) => url,
+                None => bail!(user_errors::bad_request(
+                    "That URL points to a web page that doesn't link to a podcast feed."
+                )),
+            };
+            info!(log, "Discovered feed URL in web page"; "url" => url.as_str());
+
+            latest_url = self.select_latest_url(log, url.as_str())?;
+            let location = if self.disable_shortcut {
+                None
+            } else {
+                self.select_location(log, latest_url.as_str())?
+            };
+            res = self.read_feed(log, latest_url.as_str(), location)?;
+            discovered_url = Some(url);
+        }
+
+        let etag = res.etag;
+        let last_modified = res.last_modified;
 
         // the "final URL" is one that might include a permanent redirect
-        let (body, final_url) = self.fetch_feed(log, latest_url.as_str())?;
+        let final_url = res.final_url;
+        let permanent_redirect = res.permanent_redirect;
+
+        let feed = match res.content {
+            FeedContent::Html(_) => bail!(user_errors::bad_request(
+                "That URL points to a web page rather than a podcast feed."
+            )),
+
+            // The feed hasn't changed, so the only thing left to do is record that we
+            // checked.
+            FeedContent::NotModified(location) => {
+                info!(log, "Feed not modified since last retrieval -- short circuiting");
+                let (location, podcast) = self.update_not_modified(log, &location)?;
+
+                // We don't get to see the feed's hub links, but a subscription that we
+                // already have still needs to be renewed before its lease runs out.
+                let subscription = if self.enqueue_jobs {
+                    self.select_websub_subscription(log, &podcast)?
+                } else {
+                    None
+                };
+                if let Some(subscription) = subscription {
+                    self.upsert_websub_subscription(
+                        log,
+                        &podcast,
+                        Some(&subscription),
+                        subscription.hub_url.clone(),
+                        subscription.topic_url.clone(),
+                    )?;
+                }
 
-        let sha256_hash = content_hash(&body);
+                // Even an unchanged feed may have moved. Its validators move along
+                // with it. A move onto another podcast's URL is skipped (see below).
+                let moving = permanent_redirect && {
+                    let other_podcast_id =
+                        self.select_other_podcast_id(log, &podcast, final_url.as_str())?;
+                    if let Some(other_podcast_id) = other_podcast_id {
+                        info!(log, "Redirect target belongs to another podcast -- not moving";
+                            "url" => final_url.as_str(), "other_podcast_id" => other_podcast_id);
+                    }
+                    other_podcast_id.is_none()
+                };
+                let location = if moving {
+                    let location = self.upsert_podcast_feed_location(
+                        log,
+                        &podcast,
+                        final_url,
+                        location.etag,
+                        location.last_modified,
+                    )?;
+                    self.supersede_podcast_feed_locations(log, &location)?;
+                    location
+                } else {
+                    location
+                };
+
+                let podcast = self.update_next_crawl_at(log, podcast.id)?;
+                return Ok(RunResult {
+                    discovered_url,
+                    episodes: None,
+                    location,
+                    podcast,
+                });
+            }
+
+            FeedContent::Parsed(feed) => feed,
+        };
 
-        let body = String::from_utf8(body).chain_err(|| "Error decoding to UTF-8")?;
-        let (raw_podcast, raw_episodes) = Self::parse_feed(log, body.as_str())?;
+        let ParsedFeed {
+            compressor,
+            raw_episodes,
+            raw_podcast,
+            sha256_hash,
+        } = feed;
 
         // Convert raw podcast data into something that's database compatible.
         let ins_podcast = Self::convert_podcast(log, &raw_podcast)?;
@@ -98,11 +266,78 @@ impl<'a> Mediator<'a> {
         // field so that the crawler will know not to try and update it again
         // right away even if the mediator short circuits early because it
         // already existed.
-        let podcast = self.upsert_podcast(log, &ins_podcast, final_url.as_str())?;
+        let (podcast, is_new) =
+            self.upsert_podcast(log, &ins_podcast, final_url.as_str(), latest_url.as_str())?;
+
+        // Links to other pages are resolved relative to the one we just fetched.
+        let next_page_url = raw_podcast
+            .next_page_url
+            .as_ref()
+            .and_then(|url| resolve_url(final_url.as_str(), url));
+        let hub_url = raw_podcast
+            .hub_url
+            .as_ref()
+            .and_then(|url| resolve_url(final_url.as_str(), url));
+        let self_url = raw_podcast
+            .self_url
+            .as_ref()
+            .and_then(|url| resolve_url(final_url.as_str(), url));
 
         // The final URL of the feed may be different than what a directory gave us.
         // Whatever it is, make sure that it's associated with the podcast.
-        let location = self.upsert_podcast_feed_location(log, &podcast, final_url)?;
+        // Validators from this response are stored along with it for next time.
+        let location =
+            self.upsert_podcast_feed_location(log, &podcast, final_url, etag, last_modified)?;
+
+        // A permanent redirect or an `itunes:new-feed-url` means that the podcast
+        // has moved. Its new URL becomes its only active location and the others
+        // are retired so that the crawler stops requesting them. Temporary
+        // redirects don't count because the requester reports the original URL
+        // for those.
+        //
+        // A new URL from the feed hasn't actually been retrieved yet, but it's
+        // recorded as such so that it's what we'll use next time.
+        //
+        // A new URL that another podcast already has can't be moved to because
+        // feed URLs are unique. The feed stays where it is instead of failing
+        // on every update from now on.
+        let (location, moved) = match new_feed_url(&raw_podcast, location.feed_url.as_str()) {
+            Some(url) => {
+                info!(log, "Feed declared a new URL"; "url" => url.as_str());
+                match self.select_other_podcast_id(log, &podcast, url.as_str())? {
+                    Some(other_podcast_id) => {
+                        info!(log, "New URL belongs to another podcast -- not moving";
+                            "other_podcast_id" => other_podcast_id);
+                        (location, permanent_redirect)
+                    }
+                    None => (
+                        self.upsert_podcast_feed_location(log, &podcast, url, None, None)?,
+                        true,
+                    ),
+                }
+            }
+            None => (location, permanent_redirect),
+        };
+        if moved {
+            self.supersede_podcast_feed_locations(log, &location)?;
+        }
+
+        // A feed that advertises a WebSub hub can have its updates pushed to us
+        // instead of waiting for the crawler. The feed's `self` link is what the
+        // hub knows it by, although we fall back to its location if it doesn't
+        // have one. This happens before the shortcut below so that subscriptions
+        // keep being renewed for feeds that rarely change.
+        let hub_url = if self.enqueue_jobs { hub_url } else { None };
+        if let Some(hub_url) = hub_url {
+            let subscription = self.select_websub_subscription(log, &podcast)?;
+            self.upsert_websub_subscription(
+                log,
+                &podcast,
+                subscription.as_ref(),
+                hub_url,
+                self_url.unwrap_or_else(|| location.feed_url.clone()),
+            )?;
+        }
 
         // Check to see if we already have a content record that matches our calculated
         // hash. If so, that means that we've already successfully processed
@@ -113,25 +348,66 @@ impl<'a> Mediator<'a> {
                 log,
                 "Already processed identical content -- short circuiting"
             );
+            let podcast = self.update_next_crawl_at(log, podcast.id)?;
             return Ok(RunResult {
+                discovered_url,
                 episodes: None,
                 location,
                 podcast,
             });
         }
 
-        // Store the podcast's raw content. Note that this is a relatively expensive
-        // operation because a feed's body can be quite large.
-        self.upsert_podcast_feed_content(log, &podcast, body.as_str(), sha256_hash)?;
+        // Store the podcast's raw content. It was already compressed as it was read,
+        // so this is only a matter of writing it out.
+        self.upsert_podcast_feed_content(log, &podcast, compressor, sha256_hash.clone(), 1)?;
+
+        // Podcasting 2.0 extras (funding, persons) are fully replaced on every update.
+        self.replace_podcast_extras(log, &podcast, &raw_podcast)?;
 
-        let ins_episodes = Self::convert_episodes(log, raw_episodes, &podcast)?;
+        let ins_episodes = Self::convert_episodes(log, &raw_episodes, &podcast)?;
+
+        // Changes to existing episodes are recorded before the upsert overwrites
+        // their old values.
+        self.insert_episode_revisions(log, &podcast, &ins_episodes)?;
 
         let episodes = self.upsert_episodes(log, &ins_episodes)?;
+        if self.enqueue_jobs {
+            self.enqueue_episode_media_probers(log, &episodes)?;
+        }
+
+        // Same for episode extras (chapters, persons, transcripts), which need to happen after
+        // the upsert so that we know the IDs of the episodes they belong to.
+        self.replace_episode_extras(log, &raw_episodes, &episodes)?;
+
+        // A paged feed only contains a podcast's most recent episodes. Following its
+        // `next` links to get the rest of the back catalog is expensive, so we only
+        // do it when a podcast is new or when it's been explicitly requested.
+        let episodes = match next_page_url {
+            Some(url) if is_new || self.follow_pages => {
+                self.ingest_pages(log, &podcast, sha256_hash.as_str(), url, episodes)?
+            }
+            _ => episodes,
+        };
+
+        // Any episode that we know about but whose GUID isn't in the feed anymore has
+        // been removed by its publisher. This can only be determined from a feed
+        // that contains every episode, so paged feeds are skipped. We also don't
+        // trust a feed without any valid episodes because that's much more likely
+        // to be a problem on our end or the host's than a publisher pulling
+        // everything.
+        if !raw_podcast.paged && !episodes.is_empty() {
+            self.update_removed_episodes(log, &podcast, &raw_episodes)?;
+        }
 
         // Now that we've had a successful run, remove any existing exceptions.
         self.delete_exception(log, &podcast)?;
 
+        // Scheduled last so that it sees the new episodes and doesn't back off for
+        // the exceptions that were just removed.
+        let podcast = self.update_next_crawl_at(log, podcast.id)?;
+
         Ok(RunResult {
+            discovered_url,
             episodes: Some(episodes),
             location,
             podcast,
@@ -144,6 +420,10 @@ impl<'a> Mediator<'a> {
             if let Some(podcast_id) = res {
                 self.update_podcast_last_retrieved_at(log, podcast_id)?;
                 self.upsert_exception(log, podcast_id, e)?;
+
+                // A failing feed is backed off so that we're not hammering a host
+                // that's having trouble (or that's gone for good).
+                self.update_next_crawl_at(log, podcast_id)?;
             }
             Ok(())
         });
@@ -185,7 +465,7 @@ impl<'a> Mediator<'a> {
 
     fn convert_episodes(
         log: &Logger,
-        raws: Vec<raw::Episode>,
+        raws: &[raw::Episode],
         podcast: &model::Podcast,
     ) -> Result<Vec<insertable::Episode>> {
         time_helpers::log_timed(&log.new(o!("step" => "convert_episodes")), |log| {
@@ -194,7 +474,7 @@ impl<'a> Mediator<'a> {
             let mut episodes = Vec::with_capacity(num_candidates);
 
             for raw in raws {
-                match validate_episode(&raw, podcast)
+                match validate_episode(raw, podcast.id)
                     .chain_err(|| format!("Failed to convert: {:?}", raw))?
                 {
                     EpisodeOrInvalid::Valid(e) => {
@@ -246,45 +526,290 @@ impl<'a> Mediator<'a> {
         Ok(())
     }
 
-    fn fetch_feed(&mut self, log: &Logger, url: &str) -> Result<(Vec<u8>, String)> {
-        let (status, body, final_url) =
-            time_helpers::log_timed(&log.new(o!("step" => "fetch_feed")), |_log| {
-                self.http_requester.execute(
-                    log,
-                    Request::new(Method::Get, Uri::from_str(url).map_err(Error::from)?),
-                )
-            })?;
-        common::log_body_sample(log, status, &body);
-        if status != StatusCode::Ok {
-            let string = String::from_utf8_lossy(body.as_slice()).replace("\n", "");
-            info!(log, "Body of errored request";
-                "body" => string.as_str(), "status" => format!("{}", status));
-
-            if status == StatusCode::NotFound {
+    // Looks for a feed advertised by an HTML page and returns its absolute URL.
+    // The first advertised feed is assumed to be the primary one.
+    fn discover_feed_url(log: &Logger, page: &str, page_url: &str) -> Option<String> {
+        time_helpers::log_timed(&log.new(o!("step" => "discover_feed_url")), |log| {
+            let links = html::feed_links(page);
+            info!(log, "Found feed links"; "num_links" => links.len());
+            links
+                .iter()
+                .filter_map(|link| resolve_url(page_url, link))
+                .next()
+        })
+    }
+
+    // Enqueues a job to probe the media of any episode that's new or whose media
+    // URL has changed since it was last probed. An episode may be enqueued
+    // twice if its feed changes again before the first job is worked, but
+    // probing is cheap enough that it's not worth guarding against.
+    fn enqueue_episode_media_probers(
+        &mut self,
+        log: &Logger,
+        episodes: &[model::Episode],
+    ) -> Result<()> {
+        time_helpers::log_timed(
+            &log.new(o!("step" => "enqueue_episode_media_probers")),
+            |log| {
+                let mut num_enqueued = 0;
+                for episode in episodes.iter().filter(|e| !e.is_media_probed()) {
+                    jobs::episode_media_prober::enqueue(
+                        log,
+                        self.conn,
+                        &jobs::episode_media_prober::Args {
+                            episode_id: episode.id,
+                        },
+                    )?;
+                    num_enqueued += 1;
+                }
+                info!(log, "Enqueued episode media probers"; "num_enqueued" => num_enqueued);
+                Ok(())
+            },
+        )
+    }
+
+    // Requests a feed. The response's body is left to be read by the caller,
+    // except when the host responds with an error.
+    fn fetch_feed<'b>(
+        log: &Logger,
+        http_requester: &'b mut HttpRequester,
+        url: &str,
+        location: Option<&model::PodcastFeedLocation>,
+    ) -> Result<StreamingResponse<'b>> {
+        let mut req = Request::new(Method::Get, Uri::from_str(url).map_err(Error::from)?);
+        if let Some(location) = location {
+            if let Some(ref etag) = location.etag {
+                req.headers_mut().set_raw("If-None-Match", etag.clone());
+            }
+            if let Some(ref last_modified) = location.last_modified {
+                req.headers_mut().set_raw("If-Modified-Since", last_modified.clone());
+            }
+        }
+
+        let mut res = time_helpers::log_timed(&log.new(o!("step" => "fetch_feed")), move |_log| {
+            http_requester.execute_streaming(log, req)
+        })?;
+        info!(log, "Response"; "status" => res.status.to_string());
+
+        // Not modified is only acceptable if we actually sent validators.
+        if res.status == StatusCode::NotModified && location.is_some() {
+            return Ok(res);
+        }
+
+        if res.status != StatusCode::Ok {
+            // Error pages are small, so we read them in their entirety to help with
+            // debugging.
+            let body = res.body.read_all()?;
+            common::log_body_sample(log, res.status, &body);
+
+            if res.status == StatusCode::NotFound {
                 bail!(user_errors::bad_request(
                     "That podcast doesn't seem to exist on the host's servers (404)."
                 ));
             } else {
                 bail!(user_errors::bad_request(format!(
                     "Error fetching podcast feed. Host responded with status: {}",
-                    status
+                    res.status
                 )));
             }
         }
-        Ok((body, final_url))
+        Ok(res)
+    }
+
+    fn fetch_page(&mut self, log: &Logger, url: &str) -> Result<FeedPage> {
+        let res = self.read_feed(log, url, None)?;
+        match res.content {
+            FeedContent::Parsed(feed) => Ok(FeedPage {
+                feed,
+                final_url: res.final_url,
+            }),
+            _ => Err("Feed page isn't a feed".into()),
+        }
+    }
+
+    // Follows a paged feed's `next` links starting from `next_url`, storing the
+    // content and episodes of each page along the way. `sha256_hash` is the hash
+    // of the feed's first page. Returns `episodes` with the episodes of every page
+    // merged in.
+    //
+    // A page that can't be fetched or parsed ends paging, but doesn't fail the
+    // update because the podcast's first page was still good. Anything it wrote
+    // is rolled back to a savepoint.
+    fn ingest_pages(
+        &mut self,
+        log: &Logger,
+        podcast: &model::Podcast,
+        sha256_hash: &str,
+        next_url: String,
+        mut episodes: Vec<model::Episode>,
+    ) -> Result<Vec<model::Episode>> {
+        let mut episode_ids: HashSet<i64> = episodes.iter().map(|e| e.id).collect();
+        let mut next_url = Some(next_url);
+        let mut page_number = 1;
+
+        // Feeds are known to link back to pages that we've already seen, and some
+        // hosts ignore paging parameters and return the same page no matter what,
+        // so keep track of both URLs and content to avoid going around in circles.
+        let mut seen_hashes = HashSet::new();
+        let mut seen_urls = HashSet::new();
+        seen_hashes.insert(sha256_hash.to_owned());
+
+        while let Some(url) = next_url.take() {
+            if page_number >= MAX_FEED_PAGES {
+                info!(log, "Reached maximum number of feed pages -- stopping";
+                    "max_feed_pages" => MAX_FEED_PAGES);
+                break;
+            }
+
+            if !seen_urls.insert(url.clone()) {
+                info!(log, "Feed page already retrieved -- stopping"; "url" => url.as_str());
+                break;
+            }
+
+            page_number += 1;
+            let log = log.new(o!("page_number" => page_number));
+            info!(log, "Retrieving feed page"; "url" => url.as_str());
+
+            // Reading a page runs SQL of its own, so it gets a savepoint. Otherwise an error
+            // from Postgres that we swallow here would leave the update's transaction aborted
+            // and fail every statement that came after it.
+            let page = match self.conn
+                .transaction::<_, Error, _>(|| self.fetch_page(&log, url.as_str()))
+            {
+                Ok(page) => page,
+                Err(e) => {
+                    error!(log, "Error retrieving feed page -- stopping";
+                        "error" => error_message(&e));
+                    break;
+                }
+            };
+
+            if !seen_hashes.insert(page.feed.sha256_hash.clone()) {
+                info!(log, "Feed page has content identical to another page -- stopping");
+                break;
+            }
+
+            next_url = page.feed
+                .raw_podcast
+                .next_page_url
+                .as_ref()
+                .and_then(|next_url| resolve_url(page.final_url.as_str(), next_url));
+
+            self.upsert_podcast_feed_content(
+                &log,
+                podcast,
+                page.feed.compressor,
+                page.feed.sha256_hash,
+                page_number,
+            )?;
+
+            let raw_episodes = page.feed.raw_episodes;
+            let ins_episodes = Self::convert_episodes(&log, &raw_episodes, podcast)?;
+            self.insert_episode_revisions(&log, podcast, &ins_episodes)?;
+            let page_episodes = self.upsert_episodes(&log, &ins_episodes)?;
+            if self.enqueue_jobs {
+                self.enqueue_episode_media_probers(&log, &page_episodes)?;
+            }
+            self.replace_episode_extras(&log, &raw_episodes, &page_episodes)?;
+
+            // Pages may overlap, in which case the same episode comes back more than
+            // once.
+            for episode in page_episodes {
+                if episode_ids.insert(episode.id) {
+                    episodes.push(episode);
+                }
+            }
+        }
+
+        Ok(episodes)
+    }
+
+    fn insert_episode_revisions(
+        &mut self,
+        log: &Logger,
+        podcast: &model::Podcast,
+        ins_episodes: &[insertable::Episode],
+    ) -> Result<()> {
+        let guids: Vec<&str> = ins_episodes.iter().map(|e| e.guid.as_str()).collect();
+
+        time_helpers::log_timed(
+            &log.new(o!("step" => "insert_episode_revisions")),
+            |log| {
+                let episodes: Vec<model::Episode> = schema::episode::table
+                    .filter(schema::episode::podcast_id.eq(podcast.id))
+                    .filter(schema::episode::guid.eq_any(guids))
+                    .load(self.conn)
+                    .chain_err(|| "Error loading existing episodes")?;
+                let episodes_by_guid: HashMap<&str, &model::Episode> =
+                    episodes.iter().map(|e| (e.guid.as_str(), e)).collect();
+
+                // New episodes don't have anything to compare against, so they don't get
+                // any revisions.
+                let ins_revisions: Vec<insertable::EpisodeRevision> = ins_episodes
+                    .iter()
+                    .filter_map(|ins| {
+                        episodes_by_guid
+                            .get(ins.guid.as_str())
+                            .map(|episode| episode_revisions(episode, ins))
+                    })
+                    .flat_map(|revisions| revisions)
+                    .collect();
+
+                if ins_revisions.is_empty() {
+                    return Ok(());
+                }
+
+                let num_inserted = diesel::insert_into(schema::episode_revision::table)
+                    .values(&ins_revisions)
+                    .execute(self.conn)
+                    .chain_err(|| "Error inserting episode revisions")?;
+                info!(log, "Inserted episode revisions"; "num_inserted" => num_inserted);
+                Ok(())
+            },
+        )
     }
 
-    fn parse_feed(log: &Logger, data: &str) -> Result<(raw::Podcast, Vec<raw::Episode>)> {
+    // Parses a feed from a reader. Events are handled as they're read so that
+    // the parser never builds up its own copy of a large feed.
+    fn parse_feed<R: BufRead>(
+        log: &Logger,
+        mut data: R,
+        content_type: Option<&str>,
+    ) -> Result<(raw::Podcast, Vec<raw::Episode>)> {
         time_helpers::log_timed(&log.new(o!("step" => "parse_feed")), |log| {
+            // Prefer the host's word on the format, but because so many hosts serve
+            // feeds with a generic content type, also sniff the content. An XML
+            // document can never start with a brace, so if we see one the feed is
+            // almost certainly a JSON Feed.
+            let is_json = content_type.map(|t| t.contains("json")).unwrap_or(false)
+                || starts_with_brace(&mut data)?;
+            if is_json {
+                return parse_json_feed(log, data);
+            }
+
             let mut buf = Vec::new();
             let mut skip_buf = Vec::new();
 
-            let mut reader = Reader::from_str(data);
+            let mut reader = Reader::from_reader(data);
             reader.trim_text(true).expand_empty_elements(true);
 
             loop {
                 match reader.read_event(&mut buf) {
                     Ok(Event::Start(ref e)) => match e.name() {
+                        b"feed" => {
+                            // Atom carries a feed's language as an attribute on its root
+                            // rather than in a child element like RSS does.
+                            let mut podcast = raw::Podcast::default();
+                            for attr in e.attributes().with_checks(false) {
+                                if let Ok(attr) = attr {
+                                    if attr.key == b"xml:lang" {
+                                        podcast.language =
+                                            Some(attribute_text(log, &mut reader, &attr)?);
+                                    }
+                                }
+                            }
+                            return parse_atom(log, &mut reader, podcast);
+                        }
                         b"rss" => {
                             return parse_rss(log, &mut reader);
                         }
@@ -297,7 +822,344 @@ impl<'a> Mediator<'a> {
                 buf.clear();
             }
 
-            Err("No <rss> tag found".into())
+            Err("No <rss> or <feed> tag found".into())
+        })
+    }
+
+    // Fetches a feed and reads its body. The body is decoded to UTF-8, hashed,
+    // compressed for storage, and parsed in a single pass as it comes off the
+    // wire, so a whole feed is never held in memory. The caller decides whether
+    // the compressed content is worth storing once it knows the hash.
+    //
+    // Validators from the response are returned so that they can be stored, and
+    // a `location` with validators is handed back if the feed hasn't changed
+    // since we last retrieved it.
+    fn read_feed(
+        &mut self,
+        log: &Logger,
+        url: &str,
+        location: Option<model::PodcastFeedLocation>,
+    ) -> Result<FeedResponse> {
+        let conn = self.conn;
+        let mut res = Self::fetch_feed(log, &mut *self.http_requester, url, location.as_ref())?;
+
+        let content_type = res.headers.get::<ContentType>().map(|h| h.to_string());
+        let etag = res.headers.get::<ETag>().map(|h| h.to_string());
+        let last_modified = res.headers.get::<LastModified>().map(|h| h.to_string());
+
+        let content = match location {
+            Some(location) if res.status == StatusCode::NotModified => {
+                FeedContent::NotModified(location)
+            }
+            _ => {
+                let content = Self::read_feed_content(
+                    log,
+                    conn,
+                    &mut res.body,
+                    content_type.as_ref().map(String::as_str),
+                    res.final_url.as_str(),
+                );
+
+                // Whatever failed, a body that was cut off for being too big is the
+                // more useful thing to report.
+                res.body.check_size()?;
+                content?
+            }
+        };
+
+        Ok(FeedResponse {
+            content,
+            etag,
+            final_url: res.final_url,
+            last_modified,
+            permanent_redirect: res.permanent_redirect,
+        })
+    }
+
+    // The second half of `read_feed`, which reads a response's body once we
+    // know that there is one.
+    fn read_feed_content(
+        log: &Logger,
+        conn: &PgConnection,
+        body: &mut BodyReader,
+        content_type: Option<&str>,
+        url: &str,
+    ) -> Result<FeedContent> {
+        let mut decoding_reader = DecodingReader::new(log, body, content_type)?;
+
+        // A web page is read in its entirety because all that we want from it is
+        // a link to a feed. Unlike a feed, it's not something that we'll store.
+        if is_html(content_type, decoding_reader.peek()) {
+            let mut page = String::new();
+            decoding_reader.read_to_string(&mut page)?;
+            return Ok(FeedContent::Html(Self::discover_feed_url(
+                log,
+                page.as_str(),
+                url,
+            )));
+        }
+
+        let mut compressor = blob_store::Compressor::new(log, conn)?;
+        let (raw_podcast, raw_episodes, sha256_hash) = {
+            let mut hashing_reader =
+                HashingReader::new(TeeReader::new(decoding_reader, &mut compressor));
+            let (raw_podcast, raw_episodes) =
+                Self::parse_feed(log, BufReader::new(&mut hashing_reader), content_type)?;
+            (raw_podcast, raw_episodes, hashing_reader.finish()?)
+        };
+
+        Ok(FeedContent::Parsed(ParsedFeed {
+            compressor,
+            raw_episodes,
+            raw_podcast,
+            sha256_hash,
+        }))
+    }
+
+    fn replace_episode_extras(
+        &mut self,
+        log: &Logger,
+        raws: &[raw::Episode],
+        episodes: &[model::Episode],
+    ) -> Result<()> {
+        let episodes_by_guid: HashMap<&str, &model::Episode> =
+            episodes.iter().map(|e| (e.guid.as_str(), e)).collect();
+
+        let mut chapter_ins: Vec<insertable::EpisodeChapter> = Vec::new();
+        let mut chapters_url_ins: Vec<insertable::EpisodeChaptersUrl> = Vec::new();
+        let mut person_ins: Vec<insertable::PodcastPerson> = Vec::new();
+        let mut transcript_ins: Vec<insertable::EpisodeTranscript> = Vec::new();
+
+        // Like in `convert_episodes`, the first item with any given GUID wins.
+        let mut seen: HashSet<i64> = HashSet::new();
+
+        for raw in raws {
+            let episode = match raw.guid
+                .as_ref()
+                .and_then(|g| episodes_by_guid.get(g.as_str()))
+            {
+                Some(e) => *e,
+                None => continue,
+            };
+            if !seen.insert(episode.id) {
+                continue;
+            }
+
+            chapter_ins.extend(
+                raw.chapters
+                    .iter()
+                    .filter_map(|c| validate_chapter(c, episode)),
+            );
+            chapters_url_ins.extend(
+                raw.chapters_urls
+                    .iter()
+                    .filter_map(|c| validate_chapters_url(c, episode)),
+            );
+            person_ins.extend(
+                raw.persons
+                    .iter()
+                    .filter_map(|p| validate_person(p, episode.podcast_id, Some(episode.id))),
+            );
+            transcript_ins.extend(
+                raw.transcripts
+                    .iter()
+                    .filter_map(|t| validate_transcript(t, episode)),
+            );
+        }
+
+        let ids: Vec<i64> = episodes.iter().map(|e| e.id).collect();
+
+        // Chapters files are only fetched when a link to one first appears, so
+        // figure out which links we already knew about before they're replaced.
+        let existing_chapters_urls: Vec<(i64, String)> = time_helpers::log_timed(
+            &log.new(o!("step" => "select_episode_chapters_urls")),
+            |_log| {
+                schema::episode_chapters_url::table
+                    .filter(schema::episode_chapters_url::episode_id.eq_any(&ids))
+                    .select((
+                        schema::episode_chapters_url::episode_id,
+                        schema::episode_chapters_url::url,
+                    ))
+                    .load(self.conn)
+                    .chain_err(|| "Error selecting episode chapters URLs")
+            },
+        )?;
+        let existing_chapters_urls: HashSet<(i64, String)> =
+            existing_chapters_urls.into_iter().collect();
+
+        time_helpers::log_timed(
+            &log.new(o!("step" => "replace_episode_extras")),
+            |_log| {
+                diesel::delete(
+                    schema::episode_chapter::table
+                        .filter(schema::episode_chapter::episode_id.eq_any(&ids))
+                        .filter(schema::episode_chapter::source.eq(model::CHAPTER_SOURCE_PSC)),
+                ).execute(self.conn)
+                    .chain_err(|| "Error deleting episode chapters")?;
+                diesel::delete(
+                    schema::episode_chapters_url::table
+                        .filter(schema::episode_chapters_url::episode_id.eq_any(&ids)),
+                ).execute(self.conn)
+                    .chain_err(|| "Error deleting episode chapters URLs")?;
+                diesel::delete(
+                    schema::episode_transcript::table
+                        .filter(schema::episode_transcript::episode_id.eq_any(&ids)),
+                ).execute(self.conn)
+                    .chain_err(|| "Error deleting episode transcripts")?;
+                diesel::delete(
+                    schema::podcast_person::table
+                        .filter(schema::podcast_person::episode_id.eq_any(&ids)),
+                ).execute(self.conn)
+                    .chain_err(|| "Error deleting episode persons")?;
+
+                if !chapter_ins.is_empty() {
+                    diesel::insert_into(schema::episode_chapter::table)
+                        .values(&chapter_ins)
+                        .execute(self.conn)
+                        .chain_err(|| "Error inserting episode chapters")?;
+                }
+                if !chapters_url_ins.is_empty() {
+                    diesel::insert_into(schema::episode_chapters_url::table)
+                        .values(&chapters_url_ins)
+                        .on_conflict_do_nothing()
+                        .execute(self.conn)
+                        .chain_err(|| "Error inserting episode chapters URLs")?;
+                }
+                if !person_ins.is_empty() {
+                    diesel::insert_into(schema::podcast_person::table)
+                        .values(&person_ins)
+                        .execute(self.conn)
+                        .chain_err(|| "Error inserting episode persons")?;
+                }
+                if !transcript_ins.is_empty() {
+                    diesel::insert_into(schema::episode_transcript::table)
+                        .values(&transcript_ins)
+                        .on_conflict_do_nothing()
+                        .execute(self.conn)
+                        .chain_err(|| "Error inserting episode transcripts")?;
+                }
+
+                Ok(())
+            },
+        )?;
+
+        for chapters_url in &chapters_url_ins {
+            if !is_json_chapters_url(chapters_url)
+                || existing_chapters_urls
+                    .contains(&(chapters_url.episode_id, chapters_url.url.clone()))
+            {
+                continue;
+            }
+
+            jobs::episode_chapters_fetcher::enqueue(
+                log,
+                self.conn,
+                &jobs::episode_chapters_fetcher::Args {
+                    episode_id: chapters_url.episode_id,
+                    url:        chapters_url.url.clone(),
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn replace_podcast_extras(
+        &mut self,
+        log: &Logger,
+        podcast: &model::Podcast,
+        raw_podcast: &raw::Podcast,
+    ) -> Result<()> {
+        let funding_ins: Vec<insertable::PodcastFunding> = raw_podcast
+            .fundings
+            .iter()
+            .filter_map(|f| validate_funding(f, podcast))
+            .collect();
+        let person_ins: Vec<insertable::PodcastPerson> = raw_podcast
+            .persons
+            .iter()
+            .filter_map(|p| validate_person(p, podcast.id, None))
+            .collect();
+
+        // Categories are matched by slug against the tree that's already in the
+        // database, so anything that isn't one of Apple's is dropped.
+        let category_slugs: HashSet<String> = raw_podcast
+            .categories
+            .iter()
+            .map(|name| normalize_category_slug(links::slug_category(name)))
+            .collect();
+
+        time_helpers::log_timed(
+            &log.new(o!("step" => "replace_podcast_extras")),
+            |_log| {
+                let category_ids: Vec<i64> = schema::category::table
+                    .filter(schema::category::slug.eq_any(category_slugs))
+                    .select(schema::category::id)
+                    .load(self.conn)
+                    .chain_err(|| "Error selecting categories")?;
+                let category_ins: Vec<insertable::PodcastCategory> = category_ids
+                    .into_iter()
+                    .map(|category_id| insertable::PodcastCategory {
+                        category_id,
+                        podcast_id: podcast.id,
+                    })
+                    .collect();
+
+                diesel::delete(
+                    schema::podcast_category::table
+                        .filter(schema::podcast_category::podcast_id.eq(podcast.id)),
+                ).execute(self.conn)
+                    .chain_err(|| "Error deleting podcast categories")?;
+                diesel::delete(
+                    schema::podcast_funding::table
+                        .filter(schema::podcast_funding::podcast_id.eq(podcast.id)),
+                ).execute(self.conn)
+                    .chain_err(|| "Error deleting podcast funding")?;
+                diesel::delete(
+                    schema::podcast_person::table
+                        .filter(schema::podcast_person::podcast_id.eq(podcast.id))
+                        .filter(schema::podcast_person::episode_id.is_null()),
+                ).execute(self.conn)
+                    .chain_err(|| "Error deleting podcast persons")?;
+
+                if !category_ins.is_empty() {
+                    diesel::insert_into(schema::podcast_category::table)
+                        .values(&category_ins)
+                        .execute(self.conn)
+                        .chain_err(|| "Error inserting podcast categories")?;
+                }
+                if !funding_ins.is_empty() {
+                    diesel::insert_into(schema::podcast_funding::table)
+                        .values(&funding_ins)
+                        .on_conflict_do_nothing()
+                        .execute(self.conn)
+                        .chain_err(|| "Error inserting podcast funding")?;
+                }
+                if !person_ins.is_empty() {
+                    diesel::insert_into(schema::podcast_person::table)
+                        .values(&person_ins)
+                        .execute(self.conn)
+                        .chain_err(|| "Error inserting podcast persons")?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    fn select_location(
+        &self,
+        log: &Logger,
+        url: &str,
+    ) -> Result<Option<model::PodcastFeedLocation>> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_location")), |_log| {
+            schema::podcast_feed_location::table
+                .filter(schema::podcast_feed_location::feed_url.eq(url))
+                .order(schema::podcast_feed_location::last_retrieved_at.desc())
+                .limit(1)
+                .first(self.conn)
+                .optional()
+                .chain_err(|| "Error selecting podcast feed location")
         })
     }
 
@@ -316,6 +1178,7 @@ impl<'a> Mediator<'a> {
                 time_helpers::log_timed(&log.new(o!("step" => "select_latest_url")), |_log| {
                     schema::podcast_feed_location::table
                         .filter(schema::podcast_feed_location::podcast_id.eq(podcast_id))
+                        .filter(schema::podcast_feed_location::superseded_at.is_null())
                         .select(schema::podcast_feed_location::feed_url)
                         .order(schema::podcast_feed_location::last_retrieved_at.desc())
                         .limit(1)
@@ -332,6 +1195,60 @@ impl<'a> Mediator<'a> {
         Ok(url.to_owned())
     }
 
+    // Looks up the podcast that already has a location with the given URL if
+    // it's one other than the podcast given. Feed URLs are unique across
+    // podcasts, so a podcast can't be moved to a URL that another one has.
+    fn select_other_podcast_id(
+        &self,
+        log: &Logger,
+        podcast: &model::Podcast,
+        url: &str,
+    ) -> Result<Option<i64>> {
+        Ok(match query_podcast(log, self.conn, url)? {
+            Some(podcast_id) if podcast_id != podcast.id => Some(podcast_id),
+            _ => None,
+        })
+    }
+
+    fn select_websub_subscription(
+        &self,
+        log: &Logger,
+        podcast: &model::Podcast,
+    ) -> Result<Option<model::PodcastWebsubSubscription>> {
+        time_helpers::log_timed(
+            &log.new(o!("step" => "select_websub_subscription")),
+            |_log| {
+                schema::podcast_websub_subscription::table
+                    .filter(schema::podcast_websub_subscription::podcast_id.eq(podcast.id))
+                    .first(self.conn)
+                    .optional()
+                    .chain_err(|| "Error selecting podcast WebSub subscription")
+            },
+        )
+    }
+
+    fn supersede_podcast_feed_locations(
+        &mut self,
+        log: &Logger,
+        location: &model::PodcastFeedLocation,
+    ) -> Result<()> {
+        time_helpers::log_timed(
+            &log.new(o!("step" => "supersede_podcast_feed_locations")),
+            |log| {
+                let num_rows_updated: usize = diesel::update(
+                    schema::podcast_feed_location::table
+                        .filter(schema::podcast_feed_location::podcast_id.eq(location.podcast_id))
+                        .filter(schema::podcast_feed_location::id.ne(location.id))
+                        .filter(schema::podcast_feed_location::superseded_at.is_null()),
+                ).set(schema::podcast_feed_location::superseded_at.eq(Utc::now()))
+                    .execute(self.conn)
+                    .chain_err(|| "Error superseding podcast feed locations")?;
+                info!(log, "Superseded old locations"; "num_rows_updated" => num_rows_updated);
+                Ok(())
+            },
+        )
+    }
+
     fn upsert_episodes(
         &mut self,
         log: &Logger,
@@ -351,6 +1268,17 @@ impl<'a> Mediator<'a> {
                     schema::episode::podcast_id.eq(excluded(schema::episode::podcast_id)),
                     schema::episode::published_at.eq(excluded(schema::episode::published_at)),
                     schema::episode::title.eq(excluded(schema::episode::title)),
+                    schema::episode::block.eq(excluded(schema::episode::block)),
+                    schema::episode::duration_seconds
+                        .eq(excluded(schema::episode::duration_seconds)),
+                    schema::episode::episode_number.eq(excluded(schema::episode::episode_number)),
+                    schema::episode::episode_type.eq(excluded(schema::episode::episode_type)),
+                    schema::episode::image_url.eq(excluded(schema::episode::image_url)),
+                    schema::episode::season.eq(excluded(schema::episode::season)),
+                    // an episode that's come back to the feed is no longer removed
+                    schema::episode::removed_at.eq(excluded(schema::episode::removed_at)),
+                    schema::episode::published_at_fallback
+                        .eq(excluded(schema::episode::published_at_fallback)),
                 ))
                 .get_results(self.conn)
                 .chain_err(|| "Error upserting podcast episodes")?)
@@ -374,6 +1302,8 @@ impl<'a> Mediator<'a> {
                         .eq(excluded(schema::podcast_exception::errors)),
                     schema::podcast_exception::occurred_at
                         .eq(excluded(schema::podcast_exception::occurred_at)),
+                    schema::podcast_exception::num_occurrences
+                        .eq(schema::podcast_exception::num_occurrences + 1),
                 ))
                 .execute(self.conn)
                 .chain_err(|| "Error upserting podcast exception")
@@ -386,8 +1316,14 @@ impl<'a> Mediator<'a> {
         log: &Logger,
         ins_podcast: &insertable::Podcast,
         final_url: &str,
-    ) -> Result<model::Podcast> {
-        let podcast_id: Option<i64> = query_podcast(log, self.conn, final_url)?;
+        requested_url: &str,
+    ) -> Result<(model::Podcast, bool)> {
+        // After a permanent redirect the final URL may not be associated with the
+        // podcast yet, so fall back to the URL that we requested.
+        let podcast_id: Option<i64> = match query_podcast(log, self.conn, final_url)? {
+            Some(podcast_id) => Some(podcast_id),
+            None => query_podcast(log, self.conn, requested_url)?,
+        };
 
         if let Some(podcast_id) = podcast_id {
             info!(log, "Found existing podcast ID {}", podcast_id);
@@ -396,7 +1332,7 @@ impl<'a> Mediator<'a> {
                     .set(ins_podcast)
                     .get_result(self.conn)
                     .chain_err(|| "Error updating podcast")
-            })
+            }).map(|podcast| (podcast, false))
         } else {
             info!(log, "No existing podcast found; inserting new");
             time_helpers::log_timed(&log.new(o!("step" => "insert_podcast")), |_log| {
@@ -404,10 +1340,104 @@ impl<'a> Mediator<'a> {
                     .values(ins_podcast)
                     .get_result(self.conn)
                     .chain_err(|| "Error inserting podcast")
-            })
+            }).map(|podcast| (podcast, true))
         }
     }
 
+    fn update_next_crawl_at(&mut self, log: &Logger, podcast_id: i64) -> Result<model::Podcast> {
+        time_helpers::log_timed(&log.new(o!("step" => "update_next_crawl_at")), |log| {
+            // Dates that needed a fallback to be parsed may have lost their time or
+            // timezone, which would throw off the podcast's apparent habits.
+            let published_at: Vec<DateTime<Utc>> = schema::episode::table
+                .filter(schema::episode::podcast_id.eq(podcast_id))
+                .filter(schema::episode::published_at_fallback.is_null())
+                .order(schema::episode::published_at.desc())
+                .limit(CRAWL_SCHEDULE_NUM_EPISODES)
+                .select(schema::episode::published_at)
+                .load(self.conn)
+                .chain_err(|| "Error selecting episode publication times")?;
+
+            let num_exceptions: Option<i32> = schema::podcast_exception::table
+                .filter(schema::podcast_exception::podcast_id.eq(podcast_id))
+                .select(schema::podcast_exception::num_occurrences)
+                .first(self.conn)
+                .optional()
+                .chain_err(|| "Error selecting podcast exception")?;
+
+            let subscription: Option<model::PodcastWebsubSubscription> =
+                schema::podcast_websub_subscription::table
+                    .filter(schema::podcast_websub_subscription::podcast_id.eq(podcast_id))
+                    .first(self.conn)
+                    .optional()
+                    .chain_err(|| "Error selecting podcast WebSub subscription")?;
+
+            let history = crawl_scheduler::History {
+                num_exceptions: num_exceptions.unwrap_or(0),
+                published_at:   published_at.as_slice(),
+                pushed:         subscription.map_or(false, |s| s.is_active()),
+            };
+            let jitter = Duration::minutes(rand::thread_rng().gen_range(0, CRAWL_JITTER_MINUTES));
+            let next_crawl_at = crawl_scheduler::next_crawl_at(Utc::now(), &history) + jitter;
+            info!(log, "Scheduled next crawl"; "next_crawl_at" => next_crawl_at.to_rfc3339());
+
+            diesel::update(schema::podcast::table.filter(schema::podcast::id.eq(podcast_id)))
+                .set(schema::podcast::next_crawl_at.eq(next_crawl_at))
+                .get_result(self.conn)
+                .chain_err(|| "Error updating podcast")
+        })
+    }
+
+    fn update_not_modified(
+        &mut self,
+        log: &Logger,
+        location: &model::PodcastFeedLocation,
+    ) -> Result<(model::PodcastFeedLocation, model::Podcast)> {
+        time_helpers::log_timed(&log.new(o!("step" => "update_not_modified")), |_log| {
+            let now = Utc::now();
+            let location: model::PodcastFeedLocation = diesel::update(
+                schema::podcast_feed_location::table
+                    .filter(schema::podcast_feed_location::id.eq(location.id)),
+            ).set(schema::podcast_feed_location::last_retrieved_at.eq(now))
+                .get_result(self.conn)
+                .chain_err(|| "Error updating podcast feed location")?;
+            let podcast: model::Podcast = diesel::update(
+                schema::podcast::table.filter(schema::podcast::id.eq(location.podcast_id)),
+            ).set(schema::podcast::last_retrieved_at.eq(now))
+                .get_result(self.conn)
+                .chain_err(|| "Error updating podcast")?;
+            Ok((location, podcast))
+        })
+    }
+
+    fn update_removed_episodes(
+        &mut self,
+        log: &Logger,
+        podcast: &model::Podcast,
+        raws: &[raw::Episode],
+    ) -> Result<()> {
+        // Note that this includes episodes that were invalid. They're not usable,
+        // but they haven't been removed either.
+        let guids: Vec<&str> = raws.iter()
+            .filter_map(|raw| raw.guid.as_ref().map(String::as_str))
+            .collect();
+
+        time_helpers::log_timed(
+            &log.new(o!("step" => "update_removed_episodes")),
+            |log| {
+                let num_rows_updated: usize = diesel::update(
+                    schema::episode::table
+                        .filter(schema::episode::podcast_id.eq(podcast.id))
+                        .filter(schema::episode::guid.ne_all(guids))
+                        .filter(schema::episode::removed_at.is_null()),
+                ).set(schema::episode::removed_at.eq(Utc::now()))
+                    .execute(self.conn)
+                    .chain_err(|| "Error marking removed episodes")?;
+                info!(log, "Marked removed episodes"; "num_rows_updated" => num_rows_updated);
+                Ok(())
+            },
+        )
+    }
+
     fn update_podcast_last_retrieved_at(&mut self, log: &Logger, podcast_id: i64) -> Result<()> {
         time_helpers::log_timed(
             &log.new(o!("step" => "update_podcast_last_retrieved_at")),
@@ -431,14 +1461,18 @@ impl<'a> Mediator<'a> {
         &mut self,
         log: &Logger,
         podcast: &model::Podcast,
-        body: &str,
+        compressor: blob_store::Compressor,
         sha256_hash: String,
+        page_number: i32,
     ) -> Result<()> {
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(body.as_bytes())?;
+        // The content itself is stored separately so that it's shared with any
+        // other snapshot that has the same hash.
+        time_helpers::log_timed(&log.new(o!("step" => "store_content_blob")), |log| {
+            blob_store::store_compressed(log, self.conn, sha256_hash.as_str(), compressor)
+        })?;
 
         let content_ins = insertable::PodcastFeedContent {
-            content_gzip: encoder.finish()?,
+            page_number,
             podcast_id: podcast.id,
             retrieved_at: Utc::now(),
             sha256_hash,
@@ -454,10 +1488,12 @@ impl<'a> Mediator<'a> {
                         schema::podcast_feed_content::sha256_hash,
                     ))
                     .do_update()
-                    .set(
+                    .set((
+                        schema::podcast_feed_content::page_number
+                            .eq(excluded(schema::podcast_feed_content::page_number)),
                         schema::podcast_feed_content::retrieved_at
                             .eq(excluded(schema::podcast_feed_content::retrieved_at)),
-                    )
+                    ))
                     .execute(self.conn)
                     .chain_err(|| "Error upserting podcast feed content")
             },
@@ -471,12 +1507,17 @@ impl<'a> Mediator<'a> {
         log: &Logger,
         podcast: &model::Podcast,
         final_url: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
     ) -> Result<model::PodcastFeedLocation> {
         let location_ins = insertable::PodcastFeedLocation {
             first_retrieved_at: Utc::now(),
             feed_url:           final_url,
             last_retrieved_at:  Utc::now(),
             podcast_id:         podcast.id,
+            etag,
+            last_modified,
+            superseded_at:      None,
         };
         time_helpers::log_timed(
             &log.new(o!("step" => "upsert_podcast_feed_location")),
@@ -488,18 +1529,97 @@ impl<'a> Mediator<'a> {
                         schema::podcast_feed_location::feed_url,
                     ))
                     .do_update()
-                    .set(
+                    .set((
+                        schema::podcast_feed_location::etag
+                            .eq(excluded(schema::podcast_feed_location::etag)),
+                        schema::podcast_feed_location::last_modified
+                            .eq(excluded(schema::podcast_feed_location::last_modified)),
                         schema::podcast_feed_location::last_retrieved_at
                             .eq(excluded(schema::podcast_feed_location::last_retrieved_at)),
-                    )
+                        schema::podcast_feed_location::superseded_at
+                            .eq(excluded(schema::podcast_feed_location::superseded_at)),
+                    ))
                     .get_result(self.conn)
                     .chain_err(|| "Error upserting podcast feed location")
             },
         )
     }
+
+    // Records that we're asking a podcast's hub for a subscription and enqueues a
+    // job to make the request. Nothing happens if an existing subscription is
+    // still good or a request for it was made recently.
+    fn upsert_websub_subscription(
+        &mut self,
+        log: &Logger,
+        podcast: &model::Podcast,
+        existing: Option<&model::PodcastWebsubSubscription>,
+        hub_url: String,
+        topic_url: String,
+    ) -> Result<()> {
+        time_helpers::log_timed(
+            &log.new(o!("step" => "upsert_websub_subscription")),
+            |log| {
+                if let Some(existing) = existing {
+                    if !needs_websub_request(existing, hub_url.as_str(), topic_url.as_str()) {
+                        return Ok(());
+                    }
+                }
+
+                // A subscription to a different hub or topic hasn't been verified
+                // yet, but a renewal of the same one stays verified until the
+                // hub says otherwise.
+                let (expires_at, verified_at) = match existing {
+                    Some(s) if s.hub_url == hub_url && s.topic_url == topic_url => {
+                        (s.expires_at, s.verified_at)
+                    }
+                    _ => (None, None),
+                };
+                let subscription: model::PodcastWebsubSubscription = diesel::insert_into(
+                    schema::podcast_websub_subscription::table,
+                ).values(&insertable::PodcastWebsubSubscription {
+                    hub_url:      hub_url.clone(),
+                    podcast_id:   podcast.id,
+                    requested_at: Utc::now(),
+                    secret:       existing
+                        .map(|s| s.secret.clone())
+                        .unwrap_or_else(generate_websub_secret),
+                    topic_url:    topic_url,
+                })
+                    .on_conflict(schema::podcast_websub_subscription::podcast_id)
+                    .do_update()
+                    .set((
+                        schema::podcast_websub_subscription::expires_at.eq(expires_at),
+                        schema::podcast_websub_subscription::hub_url
+                            .eq(excluded(schema::podcast_websub_subscription::hub_url)),
+                        schema::podcast_websub_subscription::requested_at
+                            .eq(excluded(schema::podcast_websub_subscription::requested_at)),
+                        schema::podcast_websub_subscription::topic_url
+                            .eq(excluded(schema::podcast_websub_subscription::topic_url)),
+                        schema::podcast_websub_subscription::verified_at.eq(verified_at),
+                    ))
+                    .get_result(self.conn)
+                    .chain_err(|| "Error upserting podcast WebSub subscription")?;
+
+                jobs::websub_subscriber::enqueue(
+                    log,
+                    self.conn,
+                    &jobs::websub_subscriber::Args {
+                        subscription_id: subscription.id,
+                    },
+                )?;
+                info!(log, "Enqueued WebSub subscriber"; "hub_url" => hub_url.as_str());
+                Ok(())
+            },
+        )
+    }
 }
 
 pub struct RunResult {
+    /// The URL of a feed that was discovered by following a `<link
+    /// rel="alternate">` in a web page. This is only set if the URL that the
+    /// mediator was given pointed to an HTML page rather than a feed.
+    pub discovered_url: Option<String>,
+
     /// Episodes that were inserted or updated by the mediator.
     ///
     /// This value is optional because if the mediator has detected that the
@@ -510,6 +1630,153 @@ pub struct RunResult {
     pub podcast:  model::Podcast,
 }
 
+/// A report on the problems found in a feed by `validate_feed`.
+#[derive(Debug, Serialize)]
+pub struct FeedReport {
+    /// Episodes that would be rejected or discarded. Episodes without any
+    /// problems aren't included.
+    pub episodes: Vec<EpisodeReport>,
+
+    pub num_episodes:       usize,
+    pub num_episodes_valid: usize,
+
+    /// Set if the feed couldn't be parsed at all (say because its XML was
+    /// truncated), in which case no further validation was possible.
+    pub parse_error: Option<String>,
+
+    /// Problems with the podcast itself. Any problem here means that the whole
+    /// feed would be rejected.
+    pub podcast_problems: Vec<String>,
+
+    pub podcast_title: Option<String>,
+
+    /// The number of valid episodes whose publishing dates could only be
+    /// parsed with a fallback, keyed by the fallback's name. These aren't
+    /// problems as such, but are a good indication of how broken a feed is.
+    pub published_at_fallbacks: BTreeMap<String, usize>,
+}
+
+impl FeedReport {
+    pub fn is_valid(&self) -> bool {
+        self.parse_error.is_none() && self.podcast_problems.is_empty() && self.episodes.is_empty()
+    }
+}
+
+/// Enough of a feed to tell whether two responses are the same feed. See
+/// `fingerprint_feed`.
+#[derive(Debug)]
+pub struct FeedFingerprint {
+    pub episode_guids: HashSet<String>,
+
+    /// A hash of the feed's content. Comparable to the hashes stored with
+    /// `podcast_feed_content`.
+    pub sha256_hash: String,
+
+    pub title: Option<String>,
+}
+
+/// A problem with a single episode in a `FeedReport`.
+#[derive(Debug, Serialize)]
+pub struct EpisodeReport {
+    pub guid: Option<String>,
+
+    /// The episode's position in the feed starting from 1. This is mostly
+    /// useful for finding episodes that are missing a GUID.
+    pub position: usize,
+
+    pub problem: String,
+    pub title:   Option<String>,
+}
+
+/// Parses and validates a feed in exactly the same way as the mediator would,
+/// but without touching the database. Instead of stopping at the first
+/// problem, everything that's wrong is collected into a report that can be
+/// used to explain to a podcast's owner why their feed (or some of its
+/// episodes) is being rejected.
+pub fn validate_feed(log: &Logger, data: &[u8], content_type: Option<&str>) -> FeedReport {
+    let mut report = FeedReport {
+        episodes:               Vec::new(),
+        num_episodes:           0,
+        num_episodes_valid:     0,
+        parse_error:            None,
+        podcast_problems:       Vec::new(),
+        podcast_title:          None,
+        published_at_fallbacks: BTreeMap::new(),
+    };
+
+    let parsed = DecodingReader::new(log, data, content_type).and_then(|decoding_reader| {
+        Mediator::parse_feed(log, BufReader::new(decoding_reader), content_type)
+    });
+    let (raw_podcast, raw_episodes) = match parsed {
+        Ok(t) => t,
+        Err(e) => {
+            report.parse_error = Some(error_message(&e));
+            return report;
+        }
+    };
+
+    report.podcast_title = raw_podcast.title.clone();
+    match validate_podcast(&raw_podcast) {
+        Ok(PodcastOrInvalid::Valid(_)) => (),
+        Ok(PodcastOrInvalid::Invalid { message: m }) => report.podcast_problems.push(m.to_owned()),
+        Err(e) => report.podcast_problems.push(error_message(&e)),
+    }
+
+    let mut guids: HashSet<String> = HashSet::new();
+    report.num_episodes = raw_episodes.len();
+
+    for (i, raw) in raw_episodes.iter().enumerate() {
+        // There's no podcast in the database to validate against, so its ID is
+        // just a placeholder.
+        let problem = match validate_episode(raw, 0) {
+            Ok(EpisodeOrInvalid::Valid(e)) => {
+                if guids.contains(&e.guid) {
+                    Some("Duplicate GUID (episode will be discarded)".to_owned())
+                } else {
+                    if let Some(fallback) = e.published_at_fallback {
+                        *report.published_at_fallbacks.entry(fallback).or_insert(0) += 1;
+                    }
+                    guids.insert(e.guid);
+                    None
+                }
+            }
+            Ok(EpisodeOrInvalid::Invalid { message: m, .. }) => Some(m.to_owned()),
+            Err(e) => Some(error_message(&e)),
+        };
+
+        match problem {
+            Some(problem) => report.episodes.push(EpisodeReport {
+                guid:     raw.guid.clone(),
+                position: i + 1,
+                problem:  problem,
+                title:    raw.title.clone(),
+            }),
+            None => report.num_episodes_valid += 1,
+        }
+    }
+
+    report
+}
+
+/// Parses a feed and fingerprints it without touching the database. The feed
+/// is decoded and hashed exactly as the mediator would do it.
+pub fn fingerprint_feed(
+    log: &Logger,
+    data: &[u8],
+    content_type: Option<&str>,
+) -> Result<FeedFingerprint> {
+    let mut hashing_reader = HashingReader::new(DecodingReader::new(log, data, content_type)?);
+    let (raw_podcast, raw_episodes) =
+        Mediator::parse_feed(log, BufReader::new(&mut hashing_reader), content_type)?;
+    let sha256_hash = hashing_reader.finish()?;
+
+    Ok(FeedFingerprint {
+        episode_guids: raw_episodes.iter().filter_map(|e| e.guid.clone()).collect(),
+        sha256_hash,
+        title: raw_podcast.title,
+    })
+}
+
 //
 // Private macros
 //
@@ -555,12 +1822,242 @@ macro_rules! require_podcast_field {
 // Private structs
 //
 
-/// Represents a regex find and replac rule that we use to coerce datetime
-/// formats that are not technically valid RFC 2822 into ones that are and
-/// which we can parse.
-struct DateTimeReplaceRule {
-    find:    Regex,
-    replace: &'static str,
+/// The interesting attributes of an Atom `<link>` tag.
+struct AtomLink {
+    href:       Option<String>,
+    media_type: Option<String>,
+    rel:        String,
+}
+
+/// Decodes a feed to UTF-8 as it's read. The encoding is decided by peeking at
+/// the start of the feed, and any other encoding named in its XML prolog is
+/// rewritten to say UTF-8. quick-xml decodes text with whatever encoding the
+/// prolog declares, and would otherwise decode our UTF-8 a second time.
+///
+/// Feeds are supposed to declare their encoding, but enough get it wrong that
+/// the order of precedence is:
+///
+/// 1. A byte order mark.
+/// 2. A `charset` in the response's `Content-Type`.
+/// 3. UTF-8 if the start of the feed contains non-ASCII characters that are
+///    valid UTF-8. An encoding declared in the XML prolog is only trusted if
+///    it doesn't, because a stale declaration left behind after a feed was
+///    converted to UTF-8 is a common mistake.
+/// 4. An `encoding` declared in the XML prolog.
+/// 5. UTF-8 (XML's default) if the start of the feed is valid UTF-8 at all.
+/// 6. Windows-1252, which is a superset of ISO-8859-1 and by far the most
+///    common legacy encoding in the wild.
+///
+/// Decoding is lossy, so reading only fails if the underlying reader does.
+struct DecodingReader<R: Read> {
+    decoder: Decoder,
+    eof:     bool,
+    in_buf:  Vec<u8>,
+    in_pos:  usize,
+    inner:   R,
+    out_buf: Vec<u8>,
+    out_pos: usize,
+}
+
+impl<R: Read> DecodingReader<R> {
+    fn new(log: &Logger, mut inner: R, content_type: Option<&str>) -> Result<DecodingReader<R>> {
+        let mut prefix = Vec::with_capacity(DECODE_PREFIX_SIZE);
+        (&mut inner)
+            .take(DECODE_PREFIX_SIZE as u64)
+            .read_to_end(&mut prefix)?;
+        let eof = prefix.len() < DECODE_PREFIX_SIZE;
+
+        // A multi-byte sequence may have been cut off at the end of the prefix,
+        // but that doesn't make it invalid unless that's the end of the feed.
+        let valid_utf8 = match str::from_utf8(&prefix) {
+            Ok(_) => true,
+            Err(e) => !eof && e.error_len().is_none(),
+        };
+
+        let (encoding, bom_len, source) = if let Some((encoding, bom_len)) =
+            Encoding::for_bom(&prefix)
+        {
+            (encoding, bom_len, "bom")
+        } else if let Some(encoding) = content_type.and_then(content_type_charset) {
+            (encoding, 0, "content_type")
+        } else if valid_utf8 && !prefix.is_ascii() {
+            (UTF_8, 0, "valid_utf8")
+        } else if let Some(encoding) = xml_declared_encoding(&prefix) {
+            (encoding, 0, "xml_prolog")
+        } else if valid_utf8 {
+            (UTF_8, 0, "default")
+        } else {
+            (WINDOWS_1252, 0, "fallback")
+        };
+        info!(log, "Decoding feed"; "encoding" => encoding.name(), "source" => source);
+
+        let mut reader = DecodingReader {
+            decoder: encoding.new_decoder_without_bom_handling(),
+            eof,
+            in_buf: Vec::new(),
+            in_pos: 0,
+            inner,
+            out_buf: Vec::new(),
+            out_pos: 0,
+        };
+
+        // The prefix is decoded up front so that the prolog can be rewritten
+        // before anything is read.
+        let mut decoded = Vec::with_capacity(prefix.len());
+        let mut src = &prefix[bom_len..];
+        loop {
+            let mut chunk = [0; DECODE_BUFFER_SIZE];
+            let (res, num_read, num_written, _) =
+                reader.decoder.decode_to_utf8(src, &mut chunk, eof);
+            decoded.extend_from_slice(&chunk[..num_written]);
+            src = &src[num_read..];
+            if res == CoderResult::InputEmpty {
+                break;
+            }
+        }
+
+        // The decoder only ever produces valid UTF-8.
+        let decoded = String::from_utf8(decoded).unwrap();
+        reader.out_buf = rewrite_xml_declared_encoding(decoded).into_bytes();
+        Ok(reader)
+    }
+
+    /// Returns decoded content that's been read ahead but not consumed yet.
+    /// Before anything is read this is the start of the feed.
+    fn peek(&self) -> &[u8] {
+        &self.out_buf[self.out_pos..]
+    }
+
+    // Decodes more of the feed into the output buffer. Returns `false` if
+    // there's nothing left to decode.
+    fn fill(&mut self) -> io::Result<bool> {
+        if self.in_pos == self.in_buf.len() {
+            if self.eof {
+                return Ok(false);
+            }
+
+            self.in_buf.resize(DECODE_BUFFER_SIZE, 0);
+            let num_read = self.inner.read(&mut self.in_buf)?;
+            self.in_buf.truncate(num_read);
+            self.in_pos = 0;
+            self.eof = num_read == 0;
+        }
+
+        // Output is always given room for a few characters so that the decoder
+        // can make progress.
+        self.out_buf.resize(DECODE_BUFFER_SIZE, 0);
+        let (_, num_read, num_written, _) =
+            self.decoder
+                .decode_to_utf8(&self.in_buf[self.in_pos..], &mut self.out_buf, self.eof);
+        self.in_pos += num_read;
+        self.out_buf.truncate(num_written);
+        self.out_pos = 0;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.out_pos == self.out_buf.len() {
+            if !self.fill()? {
+                return Ok(0);
+            }
+        }
+
+        let num_read = cmp::min(buf.len(), self.out_buf.len() - self.out_pos);
+        buf[..num_read].copy_from_slice(&self.out_buf[self.out_pos..self.out_pos + num_read]);
+        self.out_pos += num_read;
+        Ok(num_read)
+    }
+}
+
+/// The result of `Mediator::read_feed`.
+struct FeedResponse {
+    content:            FeedContent,
+    etag:               Option<String>,
+    final_url:          String,
+    last_modified:      Option<String>,
+    permanent_redirect: bool,
+}
+
+/// What a feed's URL responded with.
+enum FeedContent {
+    /// A web page, along with the URL of the first feed that it links to (if it
+    /// links to any).
+    Html(Option<String>),
+
+    /// The feed hasn't changed since it was last retrieved from this location.
+    NotModified(model::PodcastFeedLocation),
+
+    Parsed(ParsedFeed),
+}
+
+/// A single page of a paged feed, fetched and parsed.
+struct FeedPage {
+    feed:      ParsedFeed,
+    final_url: String,
+}
+
+/// Computes a SHA-256 hash of everything read through it so that content can
+/// be hashed in the same pass that parses it. The hash is of the feed after
+/// it's been decoded to UTF-8, which is also exactly what gets stored.
+struct HashingReader<R: Read> {
+    inner: R,
+    sha:   Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> HashingReader<R> {
+        HashingReader {
+            inner,
+            sha: Sha256::new(),
+        }
+    }
+
+    /// Reads whatever's left in the underlying reader (a parser doesn't
+    /// necessarily consume everything after the end of a document) and
+    /// returns the hash as a hex string.
+    fn finish(mut self) -> Result<String> {
+        io::copy(&mut self, &mut io::sink())?;
+        Ok(self.sha.result_str())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let num_read = self.inner.read(buf)?;
+        self.sha.input(&buf[..num_read]);
+        Ok(num_read)
+    }
+}
+
+/// A feed that's been read and parsed. Its content is only kept in compressed
+/// form, ready to be stored if it turns out to be new.
+struct ParsedFeed {
+    compressor:   blob_store::Compressor,
+    raw_episodes: Vec<raw::Episode>,
+    raw_podcast:  raw::Podcast,
+    sha256_hash:  String,
+}
+
+/// Copies everything read through it to a writer.
+struct TeeReader<R: Read, W: Write> {
+    inner:  R,
+    writer: W,
+}
+
+impl<R: Read, W: Write> TeeReader<R, W> {
+    fn new(inner: R, writer: W) -> TeeReader<R, W> {
+        TeeReader { inner, writer }
+    }
+}
+
+impl<R: Read, W: Write> Read for TeeReader<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let num_read = self.inner.read(buf)?;
+        self.writer.write_all(&buf[..num_read])?;
+        Ok(num_read)
+    }
 }
 
 /// Represents the result of an attempt to turn a raw episode (`raw::episode`)
@@ -594,25 +2091,155 @@ enum PodcastOrInvalid {
 /// set of functions attempts to coerce these data types into insertable rows
 /// and indicate that the data source is invalid if it's not possible.
 mod raw {
+    #[derive(Debug, Default)]
+    pub struct Chapter {
+        pub href:      Option<String>,
+        pub image_url: Option<String>,
+        pub start:     Option<String>,
+        pub title:     Option<String>,
+    }
+
+    #[derive(Debug, Default)]
+    pub struct ChaptersUrl {
+        pub media_type: Option<String>,
+        pub url:        Option<String>,
+    }
+
     #[derive(Debug, Default)]
     pub struct Episode {
-        pub description:  Option<String>,
-        pub explicit:     Option<bool>,
-        pub guid:         Option<String>,
-        pub link_url:     Option<String>,
-        pub media_type:   Option<String>,
-        pub media_url:    Option<String>,
-        pub published_at: Option<String>,
-        pub title:        Option<String>,
+        pub block:          Option<bool>,
+        pub chapters:       Vec<Chapter>,
+        pub chapters_urls:  Vec<ChaptersUrl>,
+        pub description:    Option<String>,
+        pub duration:       Option<String>,
+        pub episode_number: Option<String>,
+        pub episode_type:   Option<String>,
+        pub explicit:       Option<bool>,
+        pub guid:           Option<String>,
+        pub image_url:      Option<String>,
+        pub link_url:       Option<String>,
+        pub media_type:     Option<String>,
+        pub media_url:      Option<String>,
+        pub persons:        Vec<Person>,
+        pub published_at:   Option<String>,
+        pub season:         Option<String>,
+        pub title:          Option<String>,
+        pub transcripts:    Vec<Transcript>,
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Funding {
+        pub title: Option<String>,
+        pub url:   Option<String>,
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Person {
+        pub group_name: Option<String>,
+        pub href:       Option<String>,
+        pub image_url:  Option<String>,
+        pub name:       Option<String>,
+        pub role:       Option<String>,
     }
 
     #[derive(Debug, Default)]
     pub struct Podcast {
-        pub description: Option<String>,
-        pub image_url:   Option<String>,
-        pub language:    Option<String>,
-        pub link_url:    Option<String>,
-        pub title:       Option<String>,
+        pub author:        Option<String>,
+        pub block:         Option<bool>,
+        pub categories:    Vec<String>,
+        pub description:   Option<String>,
+        pub fundings:      Vec<Funding>,
+        pub guid:          Option<String>,
+        pub hub_url:       Option<String>,
+        pub image_url:     Option<String>,
+        pub itunes_image:  Option<String>,
+        pub language:      Option<String>,
+        pub link_url:      Option<String>,
+        pub locked:        Option<bool>,
+        pub new_feed_url:  Option<String>,
+        pub next_page_url: Option<String>,
+        pub owner_email:   Option<String>,
+        pub owner_name:    Option<String>,
+        pub paged:         bool,
+        pub persons:       Vec<Person>,
+        pub self_url:      Option<String>,
+        pub title:         Option<String>,
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Transcript {
+        pub language:   Option<String>,
+        pub media_type: Option<String>,
+        pub rel:        Option<String>,
+        pub url:        Option<String>,
+    }
+}
+
+/// Contains types for deserializing a JSON Feed (see jsonfeed.org). Only the
+/// fields that we're interested in are included, and nearly all of them are
+/// optional because even fields that the spec requires are commonly omitted.
+mod json_feed {
+    use serde_json;
+
+    #[derive(Debug, Deserialize)]
+    pub struct Attachment {
+        pub duration_in_seconds: Option<f64>,
+        pub mime_type:           Option<String>,
+        pub url:                 Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Author {
+        pub name: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Feed {
+        pub description:   Option<String>,
+        pub favicon:       Option<String>,
+        pub feed_url:      Option<String>,
+        pub home_page_url: Option<String>,
+        pub icon:          Option<String>,
+        pub language:      Option<String>,
+        pub next_url:      Option<String>,
+        pub title:         Option<String>,
+        pub version:       String,
+
+        #[serde(default)]
+        pub authors: Vec<Author>,
+
+        #[serde(default)]
+        pub hubs: Vec<Hub>,
+
+        #[serde(default)]
+        pub items: Vec<Item>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Hub {
+        #[serde(rename = "type")]
+        pub hub_type: Option<String>,
+
+        pub url: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Item {
+        pub content_html:   Option<String>,
+        pub content_text:   Option<String>,
+        pub date_modified:  Option<String>,
+        pub date_published: Option<String>,
+        pub image:          Option<String>,
+        pub summary:        Option<String>,
+        pub title:          Option<String>,
+        pub url:            Option<String>,
+
+        #[serde(default)]
+        pub attachments: Vec<Attachment>,
+
+        // The spec says that this is a string, but also that a number should be coerced to one,
+        // so take whatever we're given.
+        pub id: Option<serde_json::Value>,
     }
 }
 
@@ -634,10 +2261,22 @@ fn attribute_text<R: BufRead>(
         .to_owned())
 }
 
-fn content_hash(content: &[u8]) -> String {
-    let mut sha = Sha256::new();
-    sha.input(content);
-    sha.result_str()
+// Extracts the encoding named by the `charset` parameter of a `Content-Type`
+// header value, if there is one and it's one that we know about.
+fn content_type_charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|param| {
+            let mut parts = param.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) if name.trim().eq_ignore_ascii_case("charset") => {
+                    Encoding::for_label(value.trim().trim_matches('"').as_bytes())
+                }
+                _ => None,
+            }
+        })
+        .next()
 }
 
 // Extracts any text or content in cdata tags found within the reader's current
@@ -674,32 +2313,110 @@ fn element_text<R: BufRead>(log: &Logger, reader: &mut Reader<R>) -> Result<Stri
     }
 }
 
-fn parse_channel<R: BufRead>(
+// Compares an existing episode to the version of it that's about to be
+// upserted and produces a revision for each of the fields that we track that
+// has changed. Dates are compared and recorded as RFC 3339 strings.
+fn episode_revisions(
+    episode: &model::Episode,
+    ins_episode: &insertable::Episode,
+) -> Vec<insertable::EpisodeRevision> {
+    let mut revisions = Vec::new();
+
+    {
+        let mut compare = |field: &str, old_value: Option<&str>, new_value: Option<&str>| {
+            if old_value != new_value {
+                revisions.push(insertable::EpisodeRevision {
+                    episode_id: episode.id,
+                    field:      field.to_owned(),
+                    new_value:  new_value.map(str::to_owned),
+                    old_value:  old_value.map(str::to_owned),
+                });
+            }
+        };
+
+        compare(
+            "description",
+            episode.description.as_ref().map(String::as_str),
+            ins_episode.description.as_ref().map(String::as_str),
+        );
+        compare(
+            "media_url",
+            Some(episode.media_url.as_str()),
+            Some(ins_episode.media_url.as_str()),
+        );
+        compare(
+            "published_at",
+            Some(episode.published_at.to_rfc3339().as_str()),
+            Some(ins_episode.published_at.to_rfc3339().as_str()),
+        );
+        compare(
+            "title",
+            Some(episode.title.as_str()),
+            Some(ins_episode.title.as_str()),
+        );
+    }
+
+    revisions
+}
+
+// Flattens an error and its chain of causes into a single line.
+fn error_message(e: &Error) -> String {
+    e.iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join(": ")
+}
+
+// Generates a secret to share with a WebSub hub so that it can sign the content
+// that it pushes to us.
+fn generate_websub_secret() -> String {
+    let mut rng = EntropyRng::new();
+    iter::repeat(())
+        .map(|()| rng.sample(Alphanumeric))
+        .take(WEBSUB_SECRET_LENGTH)
+        .collect()
+}
+
+// Parses an Atom feed from within its root `<feed>` tag. This is the Atom
+// equivalent of `parse_channel` and maps its elements onto the same raw types so
+// that Atom feeds go through the same validation and upsert path as RSS.
+fn parse_atom<R: BufRead>(
     log: &Logger,
     reader: &mut Reader<R>,
+    mut podcast: raw::Podcast,
 ) -> Result<(raw::Podcast, Vec<raw::Episode>)> {
     let mut buf = Vec::new();
     let mut episodes: Vec<raw::Episode> = Vec::new();
-    let mut podcast = raw::Podcast::default();
     let mut skip_buf = Vec::new();
 
     loop {
         match reader.read_event(&mut buf) {
             Ok(Event::Start(ref e)) => match e.name() {
-                b"description" => podcast.description = Some(element_text(log, reader)?),
-                b"item" => episodes.push(parse_item(log, reader)?),
-                b"language" => podcast.language = Some(element_text(log, reader)?),
-                b"link" => podcast.link_url = Some(element_text(log, reader)?),
-                b"media:thumbnail" => {
-                    for attr in e.attributes().with_checks(false) {
-                        if let Ok(attr) = attr {
-                            if attr.key == b"url" {
-                                podcast.image_url = Some(attribute_text(log, reader, &attr)?);
-                            }
+                b"entry" => episodes.push(parse_entry(log, reader)?),
+                b"icon" => {
+                    // Prefer `<logo>` because it's meant to be a larger image, but fall back to
+                    // `<icon>` if it's all we have.
+                    let icon = element_text(log, reader)?;
+                    if podcast.image_url.is_none() {
+                        podcast.image_url = Some(icon);
+                    }
+                }
+                b"link" => {
+                    let link = parse_atom_link(log, reader, e)?;
+                    if link.rel == "alternate" {
+                        podcast.link_url = link.href;
+                    } else if is_paging_rel(link.rel.as_str()) {
+                        podcast.paged = true;
+                        if link.rel == "next" {
+                            podcast.next_page_url = link.href;
                         }
+                    } else {
+                        parse_websub_link(&mut podcast, link);
                     }
                     reader.read_to_end(e.name(), &mut skip_buf)?;
                 }
+                b"logo" => podcast.image_url = Some(element_text(log, reader)?),
+                b"subtitle" => podcast.description = Some(element_text(log, reader)?),
                 b"title" => {
                     podcast.title = Some(element_text(log, reader)?);
                     info!(log, "Parsed title"; "title" => podcast.title.clone());
@@ -707,7 +2424,7 @@ fn parse_channel<R: BufRead>(
                 name => reader.read_to_end(name, &mut skip_buf)?,
             },
             Ok(Event::End(_e)) => break,
-            Ok(Event::Eof) => return Err(Error::from("Unexpected EOF while parsing <channel> tag")),
+            Ok(Event::Eof) => return Err(Error::from("Unexpected EOF while parsing <feed> tag")),
             _ => {}
         }
         buf.clear();
@@ -716,69 +2433,574 @@ fn parse_channel<R: BufRead>(
     Ok((podcast, episodes))
 }
 
-#[cfg_attr(feature = "cargo-clippy", allow(trivial_regex))]
-fn parse_date_time(s: &str) -> Result<DateTime<Utc>> {
-    lazy_static! {
-        static ref RULES: Vec<DateTimeReplaceRule> = vec!(
-            // The "-0000" timezone is not considered valid by true pedants
-            DateTimeReplaceRule { find: Regex::new(r"-0000$").unwrap(), replace: "+0000", },
+// Extracts the attributes of an Atom `<link>` tag. Note that this doesn't
+// advance the reader past the tag, so callers are expected to do so.
+fn parse_atom_link<R: BufRead>(
+    log: &Logger,
+    reader: &mut Reader<R>,
+    e: &BytesStart,
+) -> Result<AtomLink> {
+    let mut link = AtomLink {
+        href:       None,
+        media_type: None,
+
+        // Per RFC 4287, a link without a `rel` attribute is an alternate link.
+        rel: "alternate".to_owned(),
+    };
 
-            // Like: "Mon, 27 Mar 2017 9:42:00 EST" (technically need two digits everywhere to be
-            // valid)
-            DateTimeReplaceRule { find: Regex::new(r"\b(?P<h>\d):").unwrap(), replace: "0$h:", },
-        );
+    for attr in e.attributes().with_checks(false) {
+        if let Ok(attr) = attr {
+            match attr.key {
+                b"href" => link.href = Some(attribute_text(log, reader, &attr)?),
+                b"rel" => link.rel = attribute_text(log, reader, &attr)?,
+                b"type" => link.media_type = Some(attribute_text(log, reader, &attr)?),
+                _ => (),
+            }
+        }
     }
 
-    // Try to parse a valid datetime first, then fall back and start moving into
-    // various known problem cases.
-    match DateTime::parse_from_rfc2822(s) {
-        Ok(d) => Ok(d.with_timezone(&Utc)),
-        _ => {
-            let mut s = s.to_owned();
-            for r in RULES.iter() {
-                s = r.find.replace(s.as_str(), r.replace).into_owned();
+    Ok(link)
+}
+
+// Parses an Atom text construct like `<content>` or `<summary>`. XHTML content
+// is markup nested in a `<div>` rather than text, so it's skipped over in its
+// entirety and `None` is returned so that the caller can fall back to another
+// element.
+fn parse_atom_text<R: BufRead>(
+    log: &Logger,
+    reader: &mut Reader<R>,
+    e: &BytesStart,
+) -> Result<Option<String>> {
+    let mut is_xhtml = false;
+    for attr in e.attributes().with_checks(false) {
+        if let Ok(attr) = attr {
+            if attr.key == b"type" {
+                is_xhtml = attribute_text(log, reader, &attr)? == "xhtml";
             }
-            Ok(DateTime::parse_from_rfc2822(s.as_str())
-                .chain_err(|| format!("Error parsing publishing date {:?} from feed item", s))?
-                .with_timezone(&Utc))
+        }
+    }
+
+    if is_xhtml {
+        let mut skip_buf = Vec::new();
+        reader.read_to_end(e.name(), &mut skip_buf)?;
+        return Ok(None);
+    }
+
+    Ok(Some(element_text(log, reader)?))
+}
+
+// Whether a response looks like a web page rather than a feed. Some hosts don't
+// send a content type at all, in which case we sniff the start of the body.
+fn is_html(content_type: Option<&str>, body_start: &[u8]) -> bool {
+    match content_type {
+        Some(content_type) => content_type.contains("html"),
+        None => {
+            let start = body_start
+                .iter()
+                .skip_while(|b| b.is_ascii_whitespace())
+                .take(14)
+                .cloned()
+                .collect::<Vec<u8>>()
+                .to_ascii_lowercase();
+            start.starts_with(b"<!doctype html") || start.starts_with(b"<html")
         }
     }
 }
 
-fn parse_rss<R: BufRead>(
+// Whether a chapters link points to a JSON chapters file, which is the only
+// kind that we know how to read.
+fn is_json_chapters_url(chapters_url: &insertable::EpisodeChaptersUrl) -> bool {
+    match chapters_url.media_type {
+        Some(ref media_type) => media_type.contains("json"),
+        None => chapters_url.url.ends_with(".json"),
+    }
+}
+
+// Whether a link relation indicates that a feed is split across multiple
+// pages or archives (RFC 5005), in which case any one document doesn't
+// contain all of a podcast's episodes.
+fn is_paging_rel(rel: &str) -> bool {
+    match rel {
+        "first" | "last" | "next" | "next-archive" | "prev" | "prev-archive" | "previous" => true,
+        _ => false,
+    }
+}
+
+// iTunes uses "Yes" for its boolean tags, although in practice it's not
+// unusual to see other casings or "true".
+fn itunes_yes(s: &str) -> bool {
+    s.eq_ignore_ascii_case("yes") || s.eq_ignore_ascii_case("true")
+}
+
+// Whether we should ask a podcast's hub for a subscription again given the
+// one that we already have.
+fn needs_websub_request(
+    subscription: &model::PodcastWebsubSubscription,
+    hub_url: &str,
+    topic_url: &str,
+) -> bool {
+    if subscription.hub_url != hub_url || subscription.topic_url != topic_url {
+        return true;
+    }
+
+    // Give the hub a chance to verify the last request before making another.
+    if subscription.requested_at > Utc::now() - Duration::hours(WEBSUB_RENEWAL_HOURS) {
+        return false;
+    }
+
+    match (subscription.verified_at, subscription.expires_at) {
+        (Some(_), Some(expires_at)) => {
+            expires_at < Utc::now() + Duration::hours(WEBSUB_RENEWAL_HOURS)
+        }
+        _ => true,
+    }
+}
+
+// Extracts a podcast's `itunes:new-feed-url` as long as it looks like a URL
+// that we can fetch and actually points somewhere other than where we got the
+// feed from.
+fn new_feed_url(raw_podcast: &raw::Podcast, current_url: &str) -> Option<String> {
+    let url = raw_podcast.new_feed_url.as_ref()?;
+    if url == current_url {
+        return None;
+    }
+
+    match Uri::from_str(url) {
+        Ok(ref uri) if uri.scheme() == Some("http") || uri.scheme() == Some("https") => {
+            Some(url.clone())
+        }
+        _ => None,
+    }
+}
+
+// Maps the slug of a category from Apple's older tree (which plenty of feeds
+// still use) onto its closest equivalent in the current one. Slugs that
+// aren't known to be old are returned unchanged.
+fn normalize_category_slug(slug: String) -> String {
+    let normalized = match slug.as_str() {
+        "amateur" | "college-and-high-school" | "professional" => "sports",
+        "educational-technology" | "higher-education" | "k-12" => "education",
+        "fitness-and-nutrition" => "fitness",
+        "gadgets" | "podcasting" | "software-how-to" => "technology",
+        "games-and-hobbies" => "leisure",
+        "government-and-organizations" | "local" | "national" | "regional" => "government",
+        "health" => "health-and-fitness",
+        "language-courses" => "language-learning",
+        "literature" => "books",
+        "management-and-marketing" | "shopping" => "business",
+        "news-and-politics" => "news",
+        "other" => "religion-and-spirituality",
+        "other-games" => "games",
+        "outdoor" => "wilderness",
+        "science-and-medicine" => "science",
+        "self-help" => "self-improvement",
+        "sports-and-recreation" => "sports",
+        "training" => "courses",
+        _ => return slug,
+    };
+    normalized.to_owned()
+}
+
+fn parse_channel<R: BufRead>(
     log: &Logger,
     reader: &mut Reader<R>,
 ) -> Result<(raw::Podcast, Vec<raw::Episode>)> {
     let mut buf = Vec::new();
+    let mut episodes: Vec<raw::Episode> = Vec::new();
+    let mut podcast = raw::Podcast::default();
     let mut skip_buf = Vec::new();
-    let mut res: Option<(raw::Podcast, Vec<raw::Episode>)> = None;
 
     loop {
         match reader.read_event(&mut buf) {
             Ok(Event::Start(ref e)) => match e.name() {
-                b"channel" => {
-                    if res.is_none() {
-                        res = Some(parse_channel(log, reader)?);
+                // RSS feeds borrow Atom's `<link>` for things like paging (RFC 5005).
+                b"atom:link" => {
+                    let link = parse_atom_link(log, reader, e)?;
+                    if is_paging_rel(link.rel.as_str()) {
+                        podcast.paged = true;
+                    }
+                    if link.rel == "next" {
+                        podcast.next_page_url = link.href;
                     } else {
-                        info!(
-                            log,
-                            "Found channel tag, but already parsed a channel -- skipping"
-                        );
+                        parse_websub_link(&mut podcast, link);
                     }
+                    reader.read_to_end(e.name(), &mut skip_buf)?;
                 }
-                name => reader.read_to_end(name, &mut skip_buf)?,
-            },
-            Ok(Event::End(_e)) => break,
-            Ok(Event::Eof) => return Err(Error::from("Unexpected EOF while parsing <rss> tag")),
-            _ => {}
-        }
-        buf.clear();
-    }
-
-    match res {
-        Some(t) => Ok(t),
-        None => Err("No channel tag found in feed".into()),
-    }
+                b"description" => podcast.description = Some(element_text(log, reader)?),
+                b"item" => episodes.push(parse_item(log, reader)?),
+                b"itunes:author" => podcast.author = Some(element_text(log, reader)?),
+                b"itunes:block" => podcast.block = Some(itunes_yes(&element_text(log, reader)?)),
+                b"itunes:category" => {
+                    let categories = parse_itunes_category(log, reader, e)?;
+                    podcast.categories.extend(categories);
+                }
+                b"itunes:image" => {
+                    for attr in e.attributes().with_checks(false) {
+                        if let Ok(attr) = attr {
+                            if attr.key == b"href" {
+                                podcast.itunes_image = Some(attribute_text(log, reader, &attr)?);
+                            }
+                        }
+                    }
+                    reader.read_to_end(e.name(), &mut skip_buf)?;
+                }
+                b"itunes:new-feed-url" => {
+                    podcast.new_feed_url = Some(element_text(log, reader)?)
+                }
+                b"itunes:owner" => {
+                    let (name, email) = parse_itunes_owner(log, reader)?;
+                    podcast.owner_email = email;
+                    podcast.owner_name = name;
+                }
+                b"language" => podcast.language = Some(element_text(log, reader)?),
+                b"link" => podcast.link_url = Some(element_text(log, reader)?),
+                b"media:thumbnail" => {
+                    for attr in e.attributes().with_checks(false) {
+                        if let Ok(attr) = attr {
+                            if attr.key == b"url" {
+                                podcast.image_url = Some(attribute_text(log, reader, &attr)?);
+                            }
+                        }
+                    }
+                    reader.read_to_end(e.name(), &mut skip_buf)?;
+                }
+                b"podcast:funding" => {
+                    let mut funding = raw::Funding::default();
+                    for attr in e.attributes().with_checks(false) {
+                        if let Ok(attr) = attr {
+                            if attr.key == b"url" {
+                                funding.url = Some(attribute_text(log, reader, &attr)?);
+                            }
+                        }
+                    }
+
+                    // The tag's text is an optional call to action like "Support the show!".
+                    funding.title = element_text(log, reader).ok();
+                    podcast.fundings.push(funding);
+                }
+                b"podcast:guid" => podcast.guid = Some(element_text(log, reader)?),
+                b"podcast:locked" => {
+                    podcast.locked = Some(itunes_yes(&element_text(log, reader)?))
+                }
+                b"podcast:person" => podcast.persons.push(parse_podcast_person(log, reader, e)?),
+                b"title" => {
+                    podcast.title = Some(element_text(log, reader)?);
+                    info!(log, "Parsed title"; "title" => podcast.title.clone());
+                }
+                name => reader.read_to_end(name, &mut skip_buf)?,
+            },
+            Ok(Event::End(_e)) => break,
+            Ok(Event::Eof) => return Err(Error::from("Unexpected EOF while parsing <channel> tag")),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((podcast, episodes))
+}
+
+// Normalizes an `itunes:duration` value to a number of seconds. Apple allows
+// `HH:MM:SS`, `MM:SS`, or a plain number of seconds, and we also tolerate
+// fractional seconds because they show up in the wild.
+//
+// Durations are informational only, so rather than failing we return `None`
+// for anything we can't make sense of.
+fn parse_duration(s: &str) -> Option<i64> {
+    let mut seconds: i64 = 0;
+    let parts: Vec<&str> = s.trim().split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+
+    for part in parts {
+        // Drop any fractional component like in "01:02:03.500".
+        let part = part.split('.').next().unwrap_or("");
+        seconds = seconds * 60 + part.parse::<i64>().ok()?;
+    }
+
+    Some(seconds)
+}
+
+// Parses a `<podcast:person>` tag, which may appear in either a channel or an
+// item. Like `element_text`, this consumes the tag up to its end.
+fn parse_podcast_person<R: BufRead>(
+    log: &Logger,
+    reader: &mut Reader<R>,
+    e: &BytesStart,
+) -> Result<raw::Person> {
+    let mut person = raw::Person::default();
+
+    for attr in e.attributes().with_checks(false) {
+        if let Ok(attr) = attr {
+            match attr.key {
+                b"group" => person.group_name = Some(attribute_text(log, reader, &attr)?),
+                b"href" => person.href = Some(attribute_text(log, reader, &attr)?),
+                b"img" => person.image_url = Some(attribute_text(log, reader, &attr)?),
+                b"role" => person.role = Some(attribute_text(log, reader, &attr)?),
+                _ => (),
+            }
+        }
+    }
+
+    person.name = element_text(log, reader).ok();
+    Ok(person)
+}
+
+// Parses a JSON Feed (see jsonfeed.org) into the same raw types that we
+// produce for RSS and Atom.
+fn parse_json_feed<R: Read>(log: &Logger, data: R) -> Result<(raw::Podcast, Vec<raw::Episode>)> {
+    let feed: json_feed::Feed =
+        serde_json::from_reader(data).chain_err(|| "Error parsing JSON Feed")?;
+
+    if !feed.version.starts_with("https://jsonfeed.org/version/") {
+        bail!("Unrecognized JSON Feed version: {}", feed.version);
+    }
+
+    let podcast = raw::Podcast {
+        author:        feed.authors.into_iter().filter_map(|a| a.name).next(),
+        block:         None,
+        categories:    Vec::new(),
+        description:   feed.description,
+        fundings:      Vec::new(),
+        guid:          None,
+        hub_url:       feed.hubs
+            .into_iter()
+            .find(|h| h.hub_type.as_ref().map_or(false, |t| t.eq_ignore_ascii_case("websub")))
+            .and_then(|h| h.url),
+        image_url:     feed.icon.or(feed.favicon),
+        itunes_image:  None,
+        language:      feed.language,
+        link_url:      feed.home_page_url,
+        locked:        None,
+        new_feed_url:  None,
+        next_page_url: feed.next_url.clone(),
+        owner_email:   None,
+        owner_name:    None,
+        paged:         feed.next_url.is_some(),
+        persons:       Vec::new(),
+        self_url:      feed.feed_url,
+        title:         feed.title,
+    };
+    info!(log, "Parsed title"; "title" => podcast.title.clone());
+
+    let episodes = feed.items
+        .into_iter()
+        .map(|item| {
+            // A podcast's item may have more than one attachment (say an MP3 and an M4A
+            // version of the same audio), in which case we take the first.
+            let attachment = item.attachments.into_iter().next();
+
+            raw::Episode {
+                block:          None,
+                chapters:       Vec::new(),
+                chapters_urls:  Vec::new(),
+                description:    item.content_html
+                    .or(item.content_text)
+                    .or(item.summary)
+                    .map(|s| html::sanitize(&s)),
+                duration:       attachment
+                    .as_ref()
+                    .and_then(|a| a.duration_in_seconds)
+                    .map(|d| d.to_string()),
+                episode_number: None,
+                episode_type:   None,
+                explicit:       None,
+                guid:           item.id.and_then(|id| match id {
+                    serde_json::Value::Number(n) => Some(n.to_string()),
+                    serde_json::Value::String(s) => Some(s),
+                    _ => None,
+                }),
+                image_url:      item.image,
+                link_url:       item.url,
+                media_type:     attachment.as_ref().and_then(|a| a.mime_type.clone()),
+                media_url:      attachment.and_then(|a| a.url),
+                persons:        Vec::new(),
+                published_at:   item.date_published.or(item.date_modified),
+                season:         None,
+                title:          item.title,
+                transcripts:    Vec::new(),
+            }
+        })
+        .collect();
+
+    Ok((podcast, episodes))
+}
+
+fn parse_rss<R: BufRead>(
+    log: &Logger,
+    reader: &mut Reader<R>,
+) -> Result<(raw::Podcast, Vec<raw::Episode>)> {
+    let mut buf = Vec::new();
+    let mut skip_buf = Vec::new();
+    let mut res: Option<(raw::Podcast, Vec<raw::Episode>)> = None;
+
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.name() {
+                b"channel" => {
+                    if res.is_none() {
+                        res = Some(parse_channel(log, reader)?);
+                    } else {
+                        info!(
+                            log,
+                            "Found channel tag, but already parsed a channel -- skipping"
+                        );
+                    }
+                }
+                name => reader.read_to_end(name, &mut skip_buf)?,
+            },
+            Ok(Event::End(_e)) => break,
+            Ok(Event::Eof) => return Err(Error::from("Unexpected EOF while parsing <rss> tag")),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    match res {
+        Some(t) => Ok(t),
+        None => Err("No channel tag found in feed".into()),
+    }
+}
+
+// Parses an `<itunes:category>` tag, returning the names of the category and
+// of any subcategories nested inside of it, like:
+//
+//     <itunes:category text="Society &amp; Culture">
+//       <itunes:category text="Documentary" />
+//     </itunes:category>
+//
+// Like `element_text`, this consumes the tag up to its end.
+fn parse_itunes_category<R: BufRead>(
+    log: &Logger,
+    reader: &mut Reader<R>,
+    e: &BytesStart,
+) -> Result<Vec<String>> {
+    let mut buf = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    let mut skip_buf = Vec::new();
+
+    for attr in e.attributes().with_checks(false) {
+        if let Ok(attr) = attr {
+            if attr.key == b"text" {
+                names.push(attribute_text(log, reader, &attr)?);
+            }
+        }
+    }
+
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                if e.name() == b"itunes:category" {
+                    for attr in e.attributes().with_checks(false) {
+                        if let Ok(attr) = attr {
+                            if attr.key == b"text" {
+                                names.push(attribute_text(log, reader, &attr)?);
+                            }
+                        }
+                    }
+                }
+                reader.read_to_end(e.name(), &mut skip_buf)?;
+            }
+            Ok(Event::End(_e)) => break,
+            Ok(Event::Eof) => {
+                return Err(Error::from(
+                    "Unexpected EOF while parsing <itunes:category> tag",
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(names.into_iter().filter(|n| !n.is_empty()).collect())
+}
+
+// Parses an `<itunes:owner>` tag, returning a tuple of the owner's name and
+// email.
+fn parse_itunes_owner<R: BufRead>(
+    log: &Logger,
+    reader: &mut Reader<R>,
+) -> Result<(Option<String>, Option<String>)> {
+    let mut buf = Vec::new();
+    let mut email: Option<String> = None;
+    let mut name: Option<String> = None;
+    let mut skip_buf = Vec::new();
+
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.name() {
+                b"itunes:email" => email = Some(element_text(log, reader)?),
+                b"itunes:name" => name = Some(element_text(log, reader)?),
+                name => reader.read_to_end(name, &mut skip_buf)?,
+            },
+            Ok(Event::End(_e)) => break,
+            Ok(Event::Eof) => {
+                return Err(Error::from(
+                    "Unexpected EOF while parsing <itunes:owner> tag",
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((name, email))
+}
+
+// Parses an Atom `<entry>` tag. This is the Atom equivalent of `parse_item`.
+fn parse_entry<R: BufRead>(log: &Logger, reader: &mut Reader<R>) -> Result<raw::Episode> {
+    let mut buf = Vec::new();
+    let mut episode = raw::Episode::default();
+    let mut skip_buf = Vec::new();
+
+    // Atom requires `<updated>`, but `<published>` is a closer match to what
+    // RSS' `<pubDate>` means, so prefer it if both are present.
+    let mut published_at: Option<String> = None;
+    let mut updated_at: Option<String> = None;
+
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.name() {
+                b"content" => {
+                    if let Some(content) = parse_atom_text(log, reader, e)? {
+                        episode.description = Some(html::sanitize(&content));
+                    }
+                }
+                b"id" => episode.guid = Some(element_text(log, reader)?),
+                b"link" => {
+                    let link = parse_atom_link(log, reader, e)?;
+                    match link.rel.as_str() {
+                        "alternate" => episode.link_url = link.href,
+                        "enclosure" => {
+                            episode.media_type = link.media_type;
+                            episode.media_url = link.href;
+                        }
+                        _ => (),
+                    }
+                    reader.read_to_end(e.name(), &mut skip_buf)?;
+                }
+                b"published" => published_at = Some(element_text(log, reader)?),
+                b"summary" => {
+                    // `<content>` is more complete, so only use a summary if we don't have it.
+                    if let Some(summary) = parse_atom_text(log, reader, e)? {
+                        if episode.description.is_none() {
+                            episode.description = Some(html::sanitize(&summary));
+                        }
+                    }
+                }
+                b"title" => episode.title = Some(element_text(log, reader)?),
+                b"updated" => updated_at = Some(element_text(log, reader)?),
+                name => reader.read_to_end(name, &mut skip_buf)?,
+            },
+            Ok(Event::End(_e)) => break,
+            Ok(Event::Eof) => return Err(Error::from("Unexpected EOF while parsing <entry> tag")),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    episode.published_at = published_at.or(updated_at);
+    Ok(episode)
 }
 
 fn parse_item<R: BufRead>(log: &Logger, reader: &mut Reader<R>) -> Result<raw::Episode> {
@@ -810,8 +3032,69 @@ fn parse_item<R: BufRead>(log: &Logger, reader: &mut Reader<R>) -> Result<raw::E
                     reader.read_to_end(e.name(), &mut skip_buf)?;
                 }
                 b"guid" => episode.guid = Some(element_text(log, reader)?),
+                b"itunes:block" => episode.block = Some(itunes_yes(&element_text(log, reader)?)),
+                b"itunes:duration" => episode.duration = Some(element_text(log, reader)?),
+                b"itunes:episode" => episode.episode_number = Some(element_text(log, reader)?),
+                b"itunes:episodeType" => episode.episode_type = Some(element_text(log, reader)?),
                 b"itunes:explicit" => episode.explicit = Some(element_text(log, reader)? == "yes"),
+                b"itunes:image" => {
+                    for attr in e.attributes().with_checks(false) {
+                        if let Ok(attr) = attr {
+                            if attr.key == b"href" {
+                                episode.image_url = Some(attribute_text(log, reader, &attr)?);
+                            }
+                        }
+                    }
+                    reader.read_to_end(e.name(), &mut skip_buf)?;
+                }
+                b"itunes:season" => episode.season = Some(element_text(log, reader)?),
                 b"link" => episode.link_url = Some(element_text(log, reader)?),
+                b"podcast:chapters" => {
+                    let mut chapters_url = raw::ChaptersUrl::default();
+                    for attr in e.attributes().with_checks(false) {
+                        if let Ok(attr) = attr {
+                            match attr.key {
+                                b"type" => {
+                                    chapters_url.media_type =
+                                        Some(attribute_text(log, reader, &attr)?);
+                                }
+                                b"url" => {
+                                    chapters_url.url = Some(attribute_text(log, reader, &attr)?);
+                                }
+                                _ => (),
+                            }
+                        }
+                    }
+                    episode.chapters_urls.push(chapters_url);
+                    reader.read_to_end(e.name(), &mut skip_buf)?;
+                }
+                b"podcast:person" => episode.persons.push(parse_podcast_person(log, reader, e)?),
+                b"podcast:transcript" => {
+                    let mut transcript = raw::Transcript::default();
+                    for attr in e.attributes().with_checks(false) {
+                        if let Ok(attr) = attr {
+                            match attr.key {
+                                b"language" => {
+                                    transcript.language = Some(attribute_text(log, reader, &attr)?);
+                                }
+                                b"rel" => {
+                                    transcript.rel = Some(attribute_text(log, reader, &attr)?);
+                                }
+                                b"type" => {
+                                    transcript.media_type =
+                                        Some(attribute_text(log, reader, &attr)?);
+                                }
+                                b"url" => {
+                                    transcript.url = Some(attribute_text(log, reader, &attr)?);
+                                }
+                                _ => (),
+                            }
+                        }
+                    }
+                    episode.transcripts.push(transcript);
+                    reader.read_to_end(e.name(), &mut skip_buf)?;
+                }
+                b"psc:chapters" => episode.chapters = parse_psc_chapters(log, reader)?,
                 b"pubDate" => episode.published_at = Some(element_text(log, reader)?),
                 b"title" => episode.title = Some(element_text(log, reader)?),
                 name => reader.read_to_end(name, &mut skip_buf)?,
@@ -826,39 +3109,120 @@ fn parse_item<R: BufRead>(log: &Logger, reader: &mut Reader<R>) -> Result<raw::E
     Ok(episode)
 }
 
-// The idea here is to produce a tolerant form of quick-xml's function that is
-// tolerant to as wide of a variety of possibly misencoded podcast feeds as
-// possible.
-pub fn safe_unescape_and_decode<'b, B: BufRead>(
-    log: &Logger,
-    bytes: &BytesText<'b>,
-    reader: &Reader<B>,
-) -> String {
-    // quick-xml's unescape might fail if it runs into an improperly encoded '&'
-    // with something like this:
-    //
-    //     Some(Error(Escape("Cannot find \';\' after \'&\'", 486..1124) ...
-    //
-    // The idea here is that we try to unescape: If we can, great, continue to
-    // decode. If we can't, then we just ignore the error (it goes to logs, but
-    // nothing else) and continue to decode.
-    //
-    // Eventually this would probably be better served by completely reimplementing
-    // quick-xml's unescaped so that we just don't balk when we see certain
-    // things that we know to be problems. Just do as good of a job as possible
-    // in the same style as a web browser with HTML.
-    match bytes.unescaped() {
-        Ok(bytes) => reader.decode(&*bytes).into_owned(),
-        Err(e) => {
-            error!(log, "Unescape failed"; "error" => e.description());
-            reader.decode(&*bytes).into_owned()
+// Parses a time in the "normal play time" format used by Podlove Simple
+// Chapters (e.g. "01:02:03.500", "02:03", or "3") into seconds.
+fn parse_npt(s: &str) -> Option<f64> {
+    let parts: Vec<&str> = s.trim().split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let mut seconds: f64 = 0.0;
+    for (i, part) in parts.iter().enumerate() {
+        // Only the last component may have a fractional part.
+        let value = if i == parts.len() - 1 {
+            part.parse::<f64>().ok()?
+        } else {
+            part.parse::<i64>().ok()? as f64
+        };
+        if value < 0.0 || !value.is_finite() {
+            return None;
         }
+        seconds = seconds * 60.0 + value;
     }
+
+    Some(seconds)
 }
 
-fn query_podcast(log: &Logger, conn: &PgConnection, url: &str) -> Result<Option<i64>> {
-    time_helpers::log_timed(&log.new(o!("step" => "query_podcast")), |_log| {
-        schema::podcast_feed_location::table
+// Parses the `<psc:chapter>` tags within a `<psc:chapters>` tag (Podlove
+// Simple Chapters). Like `element_text`, this consumes the tag up to its end.
+fn parse_psc_chapters<R: BufRead>(
+    log: &Logger,
+    reader: &mut Reader<R>,
+) -> Result<Vec<raw::Chapter>> {
+    let mut buf = Vec::new();
+    let mut chapters = Vec::new();
+    let mut skip_buf = Vec::new();
+
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                if e.name() == b"psc:chapter" {
+                    let mut chapter = raw::Chapter::default();
+                    for attr in e.attributes().with_checks(false) {
+                        if let Ok(attr) = attr {
+                            let value = Some(attribute_text(log, reader, &attr)?);
+                            match attr.key {
+                                b"href" => chapter.href = value,
+                                b"image" => chapter.image_url = value,
+                                b"start" => chapter.start = value,
+                                b"title" => chapter.title = value,
+                                _ => (),
+                            }
+                        }
+                    }
+                    chapters.push(chapter);
+                }
+                reader.read_to_end(e.name(), &mut skip_buf)?;
+            }
+            Ok(Event::End(_e)) => break,
+            Ok(Event::Eof) => {
+                return Err(Error::from("Unexpected EOF while parsing <psc:chapters> tag"))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(chapters)
+}
+
+// Picks out the links that a feed uses to advertise a WebSub hub. A feed may
+// list more than one hub, but subscribing to any of them is enough so we only
+// keep the first.
+fn parse_websub_link(podcast: &mut raw::Podcast, link: AtomLink) {
+    match link.rel.as_str() {
+        "hub" => if podcast.hub_url.is_none() {
+            podcast.hub_url = link.href;
+        },
+        "self" => podcast.self_url = link.href,
+        _ => (),
+    }
+}
+
+// The idea here is to produce a tolerant form of quick-xml's function that is
+// tolerant to as wide of a variety of possibly misencoded podcast feeds as
+// possible.
+pub fn safe_unescape_and_decode<'b, B: BufRead>(
+    log: &Logger,
+    bytes: &BytesText<'b>,
+    reader: &Reader<B>,
+) -> String {
+    // quick-xml's unescape might fail if it runs into an improperly encoded '&'
+    // with something like this:
+    //
+    //     Some(Error(Escape("Cannot find \';\' after \'&\'", 486..1124) ...
+    //
+    // The idea here is that we try to unescape: If we can, great, continue to
+    // decode. If we can't, then we just ignore the error (it goes to logs, but
+    // nothing else) and continue to decode.
+    //
+    // Eventually this would probably be better served by completely reimplementing
+    // quick-xml's unescaped so that we just don't balk when we see certain
+    // things that we know to be problems. Just do as good of a job as possible
+    // in the same style as a web browser with HTML.
+    match bytes.unescaped() {
+        Ok(bytes) => reader.decode(&*bytes).into_owned(),
+        Err(e) => {
+            error!(log, "Unescape failed"; "error" => e.description());
+            reader.decode(&*bytes).into_owned()
+        }
+    }
+}
+
+fn query_podcast(log: &Logger, conn: &PgConnection, url: &str) -> Result<Option<i64>> {
+    time_helpers::log_timed(&log.new(o!("step" => "query_podcast")), |_log| {
+        schema::podcast_feed_location::table
             .filter(schema::podcast_feed_location::feed_url.eq(url))
             .select(schema::podcast_feed_location::podcast_id)
             .first(conn)
@@ -867,12 +3231,157 @@ fn query_podcast(log: &Logger, conn: &PgConnection, url: &str) -> Result<Option<
     })
 }
 
-fn validate_episode(raw: &raw::Episode, podcast: &model::Podcast) -> Result<EpisodeOrInvalid> {
+// Resolves a link found in a feed (which may be relative) against the URL of
+// the feed that it was found in. Only links that we'd be able to fetch are
+// returned.
+fn resolve_url(base_url: &str, url: &str) -> Option<String> {
+    let url = Url::parse(base_url).and_then(|base| base.join(url)).ok()?;
+    match url.scheme() {
+        "http" | "https" => Some(url.into_string()),
+        _ => None,
+    }
+}
+
+// Changes the encoding declared in a document's XML prolog to UTF-8 for a
+// document that's been decoded to UTF-8. A prolog that already declares UTF-8
+// (or an encoding that we don't know about, which quick-xml will ignore) is left
+// alone.
+fn rewrite_xml_declared_encoding(mut data: String) -> String {
+    let range = match xml_declared_encoding_range(data.as_bytes()) {
+        Some(range) => range,
+        None => return data,
+    };
+
+    match Encoding::for_label(data[range.clone()].as_bytes()) {
+        Some(encoding) if encoding != UTF_8 => {
+            data.drain(range.clone());
+            data.insert_str(range.start, "UTF-8");
+        }
+        _ => (),
+    }
+    data
+}
+
+// Peeks at the start of a document to see whether its first significant
+// character is an opening brace, skipping any byte order mark and whitespace.
+// Nothing is consumed from the reader.
+fn starts_with_brace<R: BufRead>(data: &mut R) -> Result<bool> {
+    let buf = data.fill_buf()?;
+    let buf = if buf.starts_with(b"\xef\xbb\xbf") {
+        &buf[3..]
+    } else {
+        buf
+    };
+    Ok(buf.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{'))
+}
+
+// Extracts the encoding declared in a feed's XML prolog (like `<?xml
+// version="1.0" encoding="ISO-8859-1"?>`), if there is one and it's one that we
+// know about.
+fn xml_declared_encoding(data: &[u8]) -> Option<&'static Encoding> {
+    let label = &data[xml_declared_encoding_range(data)?];
+    match Encoding::for_label(label) {
+        // If we could read the declaration as ASCII the document clearly isn't
+        // UTF-16, whatever it says.
+        Some(encoding) if encoding == UTF_16BE || encoding == UTF_16LE => None,
+        encoding => encoding,
+    }
+}
+
+// Finds the byte range of the encoding label in a document's XML prolog, if it
+// has one.
+fn xml_declared_encoding_range(data: &[u8]) -> Option<Range<usize>> {
+    lazy_static! {
+        static ref XML_ENCODING: bytes::Regex = bytes::Regex::new(
+            r#"^\s*<\?xml[^>]*?\sencoding\s*=\s*["']([A-Za-z0-9._:\-]+)["']"#
+        ).unwrap();
+    }
+
+    // The prolog has to be at the very beginning of the document, so there's no
+    // need to look any further than this.
+    let prolog = &data[..data.len().min(1024)];
+
+    let label = XML_ENCODING.captures(prolog)?.get(1)?;
+    Some(label.start()..label.end())
+}
+
+// Podcasting 2.0 extras are considered optional decoration on top of a
+// podcast or episode, so unlike the validators for the primary types, these
+// just discard any that are missing required fields.
+
+fn validate_chapter(
+    raw: &raw::Chapter,
+    episode: &model::Episode,
+) -> Option<insertable::EpisodeChapter> {
+    Some(insertable::EpisodeChapter {
+        episode_id:    episode.id,
+        href:          raw.href.clone(),
+        image_url:     raw.image_url.clone(),
+        source:        model::CHAPTER_SOURCE_PSC.to_owned(),
+        start_seconds: parse_npt(raw.start.as_ref()?.as_str())?,
+        title:         raw.title.clone()?,
+    })
+}
+
+fn validate_chapters_url(
+    raw: &raw::ChaptersUrl,
+    episode: &model::Episode,
+) -> Option<insertable::EpisodeChaptersUrl> {
+    Some(insertable::EpisodeChaptersUrl {
+        episode_id: episode.id,
+        media_type: raw.media_type.clone(),
+        url:        raw.url.clone()?,
+    })
+}
+
+fn validate_funding(
+    raw: &raw::Funding,
+    podcast: &model::Podcast,
+) -> Option<insertable::PodcastFunding> {
+    Some(insertable::PodcastFunding {
+        podcast_id: podcast.id,
+        title:      raw.title.clone(),
+        url:        raw.url.clone()?,
+    })
+}
+
+fn validate_person(
+    raw: &raw::Person,
+    podcast_id: i64,
+    episode_id: Option<i64>,
+) -> Option<insertable::PodcastPerson> {
+    Some(insertable::PodcastPerson {
+        episode_id,
+        group_name: raw.group_name.clone(),
+        href: raw.href.clone(),
+        image_url: raw.image_url.clone(),
+        name: raw.name.clone()?,
+        podcast_id,
+        role: raw.role.clone(),
+    })
+}
+
+fn validate_transcript(
+    raw: &raw::Transcript,
+    episode: &model::Episode,
+) -> Option<insertable::EpisodeTranscript> {
+    Some(insertable::EpisodeTranscript {
+        episode_id: episode.id,
+        language:   raw.language.clone(),
+        media_type: raw.media_type.clone()?,
+        rel:        raw.rel.clone(),
+        url:        raw.url.clone()?,
+    })
+}
+
+fn validate_episode(raw: &raw::Episode, podcast_id: i64) -> Result<EpisodeOrInvalid> {
     require_episode_field!(raw.guid, "GUID");
     require_episode_field!(raw.media_url, "media URL", raw.guid.clone());
     require_episode_field!(raw.published_at, "publish date", raw.guid.clone());
     require_episode_field!(raw.title, "title", raw.guid.clone());
 
+    let published_at = date_parser::parse(raw.published_at.as_ref().unwrap())?;
+
     Ok(EpisodeOrInvalid::Valid(insertable::Episode {
         description:  raw.description.clone(),
         explicit:     raw.explicit,
@@ -880,9 +3389,23 @@ fn validate_episode(raw: &raw::Episode, podcast: &model::Podcast) -> Result<Epis
         link_url:     raw.link_url.clone(),
         media_url:    raw.media_url.clone().unwrap(),
         media_type:   raw.media_type.clone(),
-        podcast_id:   podcast.id,
-        published_at: parse_date_time(raw.published_at.clone().unwrap().as_str())?,
+        podcast_id:   podcast_id,
+        published_at: published_at.date_time,
         title:        raw.title.clone().unwrap(),
+
+        // iTunes fields are all optional and are discarded if malformed rather than invalidating
+        // the whole episode.
+        block:            raw.block,
+        duration_seconds: raw.duration.as_ref().and_then(|d| parse_duration(d)),
+        episode_number:   raw.episode_number.as_ref().and_then(|n| n.parse().ok()),
+        episode_type:     raw.episode_type.as_ref().map(|t| t.to_lowercase()),
+        image_url:        raw.image_url.clone(),
+        season:           raw.season.as_ref().and_then(|n| n.parse().ok()),
+
+        // The episode is in the feed, so by definition it hasn't been removed.
+        removed_at: None,
+
+        published_at_fallback: published_at.fallback.map(|f| f.name().to_owned()),
     }))
 }
 
@@ -896,6 +3419,15 @@ fn validate_podcast(raw: &raw::Podcast) -> Result<PodcastOrInvalid> {
         last_retrieved_at: Utc::now(),
         link_url:          raw.link_url.clone(),
         title:             raw.title.clone().unwrap(),
+
+        author:           raw.author.clone(),
+        block:            raw.block,
+        itunes_image_url: raw.itunes_image.clone(),
+        owner_email:      raw.owner_email.clone(),
+        owner_name:       raw.owner_name.clone(),
+
+        guid:   raw.guid.clone(),
+        locked: raw.locked,
     }))
 }
 
@@ -905,13 +3437,15 @@ fn validate_podcast(raw: &raw::Podcast) -> Result<PodcastOrInvalid> {
 
 #[cfg(test)]
 mod tests {
-    use http_requester::HttpRequesterPassThrough;
+    use http_requester::{HttpRequesterPassThrough, Response};
     use mediators::podcast_updater::*;
     use model;
     use schema;
+    use test_data;
     use test_helpers;
 
     use chrono::prelude::*;
+    use hyper::Headers;
     use r2d2::PooledConnection;
     use r2d2_diesel::ConnectionManager;
     use std::sync::Arc;
@@ -938,6 +3472,16 @@ mod tests {
             res.podcast.link_url
         );
         assert_eq!("Title", res.podcast.title);
+        assert_eq!(Some("Author".to_owned()), res.podcast.author);
+        assert_eq!(
+            Some("https://example.com/podcast-itunes-image-url.jpg".to_owned()),
+            res.podcast.itunes_image_url
+        );
+        assert_eq!(
+            Some("owner@example.com".to_owned()),
+            res.podcast.owner_email
+        );
+        assert_eq!(Some("Owner".to_owned()), res.podcast.owner_name);
 
         // Podcast feed location
         //
@@ -964,6 +3508,14 @@ mod tests {
             episode.published_at
         );
         assert_eq!("Item 1 Title", episode.title);
+        assert_eq!(Some(3723), episode.duration_seconds);
+        assert_eq!(Some(2), episode.episode_number);
+        assert_eq!(Some("full".to_owned()), episode.episode_type);
+        assert_eq!(
+            Some("https://example.com/item-1-image-url.jpg".to_owned()),
+            episode.image_url
+        );
+        assert_eq!(Some(1), episode.season);
 
         let episode = &episodes[1];
         assert_ne!(0, episode.id);
@@ -978,6 +3530,11 @@ mod tests {
             episode.published_at
         );
         assert_eq!("Item 2 Title", episode.title);
+        assert_eq!(Some(3723), episode.duration_seconds);
+        assert_eq!(Some(1), episode.episode_number);
+        assert_eq!(Some("trailer".to_owned()), episode.episode_type);
+        assert_eq!(None, episode.image_url);
+        assert_eq!(Some(1), episode.season);
     }
 
     #[test]
@@ -1003,89 +3560,693 @@ mod tests {
     }
 
     #[test]
-    fn test_podcast_update_idempotency_with_shortcut() {
-        let mut bootstrap = TestBootstrap::new(test_helpers::MINIMAL_FEED);
+    fn test_podcast_update_feed_atom() {
+        let mut bootstrap = TestBootstrap::new(
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en-US">
+  <id>urn:uuid:60a76c80-d399-11d9-b93C-0003939e0af6</id>
+  <link href="https://example.com/podcast"/>
+  <link rel="self" href="https://example.com/feed.xml"/>
+  <logo>https://example.com/podcast-image-url.jpg</logo>
+  <subtitle>Description</subtitle>
+  <title>Title</title>
+  <updated>2017-12-24T21:37:32Z</updated>
+  <entry>
+    <content type="html">&lt;p&gt;Item 1 description&lt;/p&gt;</content>
+    <id>1</id>
+    <link rel="alternate" href="https://example.com/item-1-page"/>
+    <link rel="enclosure" href="https://example.com/item-1" type="audio/mpeg"/>
+    <published>2017-12-24T21:37:32Z</published>
+    <title>Item 1 Title</title>
+    <updated>2017-12-25T21:37:32Z</updated>
+  </entry>
+  <entry>
+    <id>2</id>
+    <link rel="enclosure" href="https://example.com/item-2" type="audio/mpeg"/>
+    <summary>Item 2 description</summary>
+    <title>Item 2 Title</title>
+    <updated>2017-12-23T16:37:32-05:00</updated>
+  </entry>
+</feed>"#,
+        );
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
 
-        {
-            let (mut mediator, log) = bootstrap.mediator();
-            let _res = mediator.run(&log).unwrap();
-            let _res = mediator.run(&log).unwrap();
-        }
+        // Podcast
+        //
 
-        // Make sure that we ended up with one of everything
+        assert_eq!(Some("Description".to_owned()), res.podcast.description);
         assert_eq!(
-            Ok(1),
-            schema::episode::table.count().first(&*bootstrap.conn)
+            Some("https://example.com/podcast-image-url.jpg".to_owned()),
+            res.podcast.image_url
         );
+        assert_eq!(Some("en-US".to_owned()), res.podcast.language);
         assert_eq!(
-            Ok(1),
-            schema::podcast::table.count().first(&*bootstrap.conn)
+            Some("https://example.com/podcast".to_owned()),
+            res.podcast.link_url
+        );
+        assert_eq!("Title", res.podcast.title);
+
+        // Episode
+        //
+
+        let episodes = res.episodes.unwrap();
+        assert_eq!(2, episodes.len());
+
+        let episode = &episodes[0];
+        assert_eq!(
+            Some("<p>Item 1 description</p>".to_owned()),
+            episode.description
         );
+        assert_eq!("1", episode.guid);
         assert_eq!(
-            Ok(1),
-            schema::podcast_feed_content::table
-                .count()
-                .first(&*bootstrap.conn)
+            Some("https://example.com/item-1-page".to_owned()),
+            episode.link_url
         );
+        assert_eq!(Some("audio/mpeg".to_owned()), episode.media_type);
+        assert_eq!("https://example.com/item-1", episode.media_url);
         assert_eq!(
-            Ok(1),
-            schema::podcast_feed_location::table
-                .count()
-                .first(&*bootstrap.conn)
+            Utc.ymd(2017, 12, 24).and_hms(21, 37, 32),
+            episode.published_at
+        );
+        assert_eq!("Item 1 Title", episode.title);
+
+        // No `<published>`, so `<updated>` is used instead
+        let episode = &episodes[1];
+        assert_eq!(Some("Item 2 description".to_owned()), episode.description);
+        assert_eq!("2", episode.guid);
+        assert_eq!("https://example.com/item-2", episode.media_url);
+        assert_eq!(
+            Utc.ymd(2017, 12, 23).and_hms(21, 37, 32),
+            episode.published_at
         );
+        assert_eq!("Item 2 Title", episode.title);
     }
 
     #[test]
-    fn test_podcast_update_idempotency_without_shortcut() {
-        let mut bootstrap = TestBootstrap::new(test_helpers::MINIMAL_FEED);
+    fn test_podcast_update_feed_atom_xhtml_content() {
+        let mut bootstrap = TestBootstrap::new(
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Title</title>
+  <entry>
+    <content type="xhtml">
+      <div xmlns="http://www.w3.org/1999/xhtml"><p>Item 1 <em>content</em></p></div>
+    </content>
+    <id>1</id>
+    <link rel="enclosure" href="https://example.com/item-1" type="audio/mpeg"/>
+    <summary>Item 1 description</summary>
+    <title>Item 1 Title</title>
+    <updated>2017-12-24T21:37:32Z</updated>
+  </entry>
+  <entry>
+    <id>2</id>
+    <link rel="enclosure" href="https://example.com/item-2" type="audio/mpeg"/>
+    <summary type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml">Item 2</div></summary>
+    <title>Item 2 Title</title>
+    <updated>2017-12-23T21:37:32Z</updated>
+  </entry>
+</feed>"#,
+        );
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
 
-        {
-            let (mut mediator, log) = bootstrap.mediator();
+        let episodes = res.episodes.unwrap();
+        assert_eq!(2, episodes.len());
 
-            // Disable the shortcut that checks to see if content has already been
-            // processed so that we can verify idempotency even if the mediator
-            // is doing a complete end-to-end run.
-            mediator.disable_shortcut = true;
+        // XHTML content is skipped, so the summary is used instead
+        let episode = &episodes[0];
+        assert_eq!(Some("Item 1 description".to_owned()), episode.description);
+        assert_eq!("1", episode.guid);
+        assert_eq!("https://example.com/item-1", episode.media_url);
+        assert_eq!("Item 1 Title", episode.title);
 
-            let _res = mediator.run(&log).unwrap();
-            let _res = mediator.run(&log).unwrap();
-        }
+        let episode = &episodes[1];
+        assert_eq!(None, episode.description);
+        assert_eq!("2", episode.guid);
+        assert_eq!("https://example.com/item-2", episode.media_url);
+        assert_eq!("Item 2 Title", episode.title);
+    }
 
-        // Make sure that we ended up with one of everything
+    #[test]
+    fn test_podcast_update_feed_iso_8859_1() {
+        let mut bootstrap =
+            TestBootstrap::new(include_bytes!("../test_documents/feed_iso_8859_1.xml"));
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        assert_eq!("Café Crème", res.podcast.title);
         assert_eq!(
-            Ok(1),
-            schema::episode::table.count().first(&*bootstrap.conn)
+            Some("Un podcast à propos du café".to_owned()),
+            res.podcast.description
         );
+
+        let episode = &res.episodes.unwrap()[0];
+        assert_eq!("L'épisode numéro un", episode.title);
+    }
+
+    #[test]
+    fn test_podcast_update_feed_windows_1252() {
+        let mut bootstrap =
+            TestBootstrap::new(include_bytes!("../test_documents/feed_windows_1252.xml"));
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        assert_eq!("“Quoted” Title", res.podcast.title);
         assert_eq!(
-            Ok(1),
-            schema::podcast::table.count().first(&*bootstrap.conn)
+            Some("It costs €5 — or so".to_owned()),
+            res.podcast.description
         );
+
+        let episode = &res.episodes.unwrap()[0];
+        assert_eq!("Episode’s Title", episode.title);
+    }
+
+    #[test]
+    fn test_podcast_update_feed_json_ideal() {
+        let mut bootstrap =
+            TestBootstrap::new(include_bytes!("../test_documents/feed_json_ideal.json"));
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        // Podcast
+        //
+
+        assert_eq!(Some("Description".to_owned()), res.podcast.description);
         assert_eq!(
-            Ok(1),
-            schema::podcast_feed_content::table
-                .count()
-                .first(&*bootstrap.conn)
+            Some("https://example.com/podcast-image-url.jpg".to_owned()),
+            res.podcast.image_url
         );
+        assert_eq!(Some("en-US".to_owned()), res.podcast.language);
         assert_eq!(
-            Ok(1),
-            schema::podcast_feed_location::table
-                .count()
-                .first(&*bootstrap.conn)
+            Some("https://example.com/podcast".to_owned()),
+            res.podcast.link_url
         );
-    }
-
-    #[test]
-    fn test_podcast_update_prefer_latest_url() {
-        // Establish one connection with an open transaction for which data will live
-        // across this whole test.
-        let conn = test_helpers::connection();
+        assert_eq!("Title", res.podcast.title);
 
-        let mut bootstrap = TestBootstrapWithConn::new(test_helpers::MINIMAL_FEED, &*conn);
+        // Episode
+        //
 
-        let res = {
-            let (mut mediator, log) = bootstrap.mediator();
-            mediator.run(&log).unwrap()
-        };
+        let episodes = res.episodes.unwrap();
+        assert_eq!(2, episodes.len());
+
+        let episode = &episodes[0];
+        assert_eq!(
+            Some("<p>Item 1 description</p>".to_owned()),
+            episode.description
+        );
+        assert_eq!("1", episode.guid);
+        assert_eq!(
+            Some("https://example.com/item-1-page".to_owned()),
+            episode.link_url
+        );
+        assert_eq!(Some("audio/mpeg".to_owned()), episode.media_type);
+        assert_eq!("https://example.com/item-1", episode.media_url);
+        assert_eq!(
+            Utc.ymd(2017, 12, 24).and_hms(21, 37, 32),
+            episode.published_at
+        );
+        assert_eq!("Item 1 Title", episode.title);
+
+        // Numeric ID and multiple attachments (the first is used)
+        let episode = &episodes[1];
+        assert_eq!(Some("Item 2 description".to_owned()), episode.description);
+        assert_eq!("2", episode.guid);
+        assert_eq!("https://example.com/item-2", episode.media_url);
+        assert_eq!(
+            Utc.ymd(2017, 12, 23).and_hms(21, 37, 32),
+            episode.published_at
+        );
+        assert_eq!("Item 2 Title", episode.title);
+    }
+
+    #[test]
+    fn test_podcast_update_feed_json_minimal() {
+        let mut bootstrap =
+            TestBootstrap::new(include_bytes!("../test_documents/feed_json_minimal.json"));
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        assert!(res.podcast.description.is_none());
+        assert_eq!("Title", res.podcast.title);
+
+        // The second item has no attachment and is discarded by validation.
+        let episodes = res.episodes.unwrap();
+        assert_eq!(1, episodes.len());
+
+        let episode = &episodes[0];
+        assert_eq!("1", episode.guid);
+        assert_eq!("https://example.com/item-1", episode.media_url);
+    }
+
+    #[test]
+    fn test_podcast_update_feed_json_unknown_version() {
+        let mut bootstrap = TestBootstrap::new(br#"{"version": "1.0", "title": "Title"}"#);
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log);
+
+        assert_eq!(true, res.is_err());
+        let err = res.err().unwrap();
+        assert_eq!(
+            "Unrecognized JSON Feed version: 1.0",
+            err.iter().next().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_feed_podcast_namespace() {
+        let mut bootstrap = TestBootstrap::new(
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <podcast:funding url="https://example.com/donate">Support the show!</podcast:funding>
+    <podcast:guid>917393e3-1b1e-5cef-ace4-edaa54e1f810</podcast:guid>
+    <podcast:locked owner="owner@example.com">yes</podcast:locked>
+    <podcast:person role="host" img="https://example.com/host.jpg">Host</podcast:person>
+    <title>Title</title>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <podcast:chapters url="https://example.com/item-1-chapters.json" type="application/json+chapters"/>
+      <podcast:person role="guest" href="https://example.com/guest">Guest</podcast:person>
+      <podcast:transcript url="https://example.com/item-1.vtt" type="text/vtt" language="en" rel="captions"/>
+      <podcast:transcript url="https://example.com/item-1.html"/><!-- no type, so discarded -->
+      <psc:chapters version="1.2">
+        <psc:chapter start="0" title="Intro"/>
+        <psc:chapter start="00:01:05.500" title="Topic" href="https://example.com/topic"/>
+        <psc:chapter start="00:02:00"/><!-- no title, so discarded -->
+      </psc:chapters>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+  </channel>
+</rss>"#,
+        );
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.disable_shortcut = true;
+
+            // Run twice to make sure that extras are replaced rather than duplicated.
+            mediator.run(&log).unwrap();
+            mediator.run(&log).unwrap()
+        };
+
+        assert_eq!(
+            Some("917393e3-1b1e-5cef-ace4-edaa54e1f810".to_owned()),
+            res.podcast.guid
+        );
+        assert_eq!(Some(true), res.podcast.locked);
+
+        let fundings: Vec<model::PodcastFunding> = schema::podcast_funding::table
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(1, fundings.len());
+        assert_eq!(Some("Support the show!".to_owned()), fundings[0].title);
+        assert_eq!("https://example.com/donate", fundings[0].url);
+
+        let episode = &res.episodes.unwrap()[0];
+
+        let persons: Vec<model::PodcastPerson> = schema::podcast_person::table
+            .order(schema::podcast_person::name)
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(2, persons.len());
+        assert_eq!(Some(episode.id), persons[0].episode_id);
+        assert_eq!(
+            Some("https://example.com/guest".to_owned()),
+            persons[0].href
+        );
+        assert_eq!("Guest", persons[0].name);
+        assert_eq!(Some("guest".to_owned()), persons[0].role);
+        assert_eq!(None, persons[1].episode_id);
+        assert_eq!(
+            Some("https://example.com/host.jpg".to_owned()),
+            persons[1].image_url
+        );
+        assert_eq!("Host", persons[1].name);
+
+        let chapters_urls: Vec<model::EpisodeChaptersUrl> = schema::episode_chapters_url::table
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(1, chapters_urls.len());
+        assert_eq!(episode.id, chapters_urls[0].episode_id);
+        assert_eq!(
+            "https://example.com/item-1-chapters.json",
+            chapters_urls[0].url
+        );
+
+        let chapters: Vec<model::EpisodeChapter> = schema::episode_chapter::table
+            .order(schema::episode_chapter::start_seconds)
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(2, chapters.len());
+        assert_eq!(episode.id, chapters[0].episode_id);
+        assert_eq!(model::CHAPTER_SOURCE_PSC, chapters[0].source);
+        assert_eq!("Intro", chapters[0].title);
+        assert_eq!(65.5, chapters[1].start_seconds);
+        assert_eq!(
+            Some("https://example.com/topic".to_owned()),
+            chapters[1].href
+        );
+
+        // The chapters file is only fetched once even though the feed was updated
+        // twice.
+        let jobs: Vec<model::Job> = schema::job::table
+            .filter(schema::job::name.eq(jobs::episode_chapters_fetcher::NAME))
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(1, jobs.len());
+        assert_eq!(
+            json!({
+                "episode_id": episode.id,
+                "url": "https://example.com/item-1-chapters.json",
+            }),
+            jobs[0].args
+        );
+
+        let transcripts: Vec<model::EpisodeTranscript> = schema::episode_transcript::table
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(1, transcripts.len());
+        assert_eq!(Some("en".to_owned()), transcripts[0].language);
+        assert_eq!("text/vtt", transcripts[0].media_type);
+        assert_eq!(Some("captions".to_owned()), transcripts[0].rel);
+        assert_eq!("https://example.com/item-1.vtt", transcripts[0].url);
+    }
+
+    #[test]
+    fn test_podcast_update_enqueue_episode_media_probers() {
+        let mut bootstrap = TestBootstrap::new(test_helpers::MINIMAL_FEED);
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.disable_shortcut = true;
+            let _res = mediator.run(&log).unwrap();
+        }
+
+        let episode: model::Episode = schema::episode::table.first(&*bootstrap.conn).unwrap();
+        let count_jobs = |conn: &PgConnection| -> i64 {
+            schema::job::table
+                .filter(schema::job::name.eq(jobs::episode_media_prober::NAME))
+                .count()
+                .first(conn)
+                .unwrap()
+        };
+        assert_eq!(1, count_jobs(&*bootstrap.conn));
+
+        // Once media has been probed, it's not probed again unless its URL changes.
+        diesel::update(schema::episode::table.filter(schema::episode::id.eq(episode.id)))
+            .set(schema::episode::media_probed_url.eq(Some(episode.media_url.clone())))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.disable_shortcut = true;
+            let _res = mediator.run(&log).unwrap();
+        }
+        assert_eq!(1, count_jobs(&*bootstrap.conn));
+    }
+
+    #[test]
+    fn test_podcast_update_enqueue_jobs_disabled() {
+        let mut bootstrap = TestBootstrap::new(
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <atom:link rel="hub" href="https://hub.example.com/"/>
+    <title>Title</title>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+  </channel>
+</rss>"#,
+        );
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.enqueue_jobs = false;
+            let _res = mediator.run(&log).unwrap();
+        }
+
+        // The podcast and its episode are stored, but no follow-up work is queued.
+        assert_eq!(
+            Ok(1),
+            schema::episode::table.count().first(&*bootstrap.conn)
+        );
+        assert_eq!(Ok(0), schema::job::table.count().first(&*bootstrap.conn));
+        assert_eq!(
+            Ok(0),
+            schema::podcast_websub_subscription::table
+                .count()
+                .first(&*bootstrap.conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_feed_categories() {
+        let mut bootstrap = TestBootstrap::new(
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <itunes:category text="Society &amp; Culture">
+      <itunes:category text="Documentary"/>
+    </itunes:category>
+    <itunes:category text="Games &amp; Hobbies"/><!-- old name for "Leisure" -->
+    <itunes:category text="Not a Real Category"/>
+    <title>Title</title>
+  </channel>
+</rss>"#,
+        );
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.disable_shortcut = true;
+
+            // Run twice to make sure that categories are replaced rather than duplicated.
+            mediator.run(&log).unwrap();
+            mediator.run(&log).unwrap()
+        };
+
+        let categories: Vec<model::Category> = schema::category::table
+            .filter(
+                schema::category::id.eq_any(
+                    schema::podcast_category::table
+                        .filter(schema::podcast_category::podcast_id.eq(res.podcast.id))
+                        .select(schema::podcast_category::category_id),
+                ),
+            )
+            .order(schema::category::slug)
+            .load(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!(
+            vec!["documentary", "leisure", "society-and-culture"],
+            categories.iter().map(|c| c.slug.as_str()).collect::<Vec<&str>>()
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_websub_subscription() {
+        let mut bootstrap = TestBootstrap::new(
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <atom:link rel="hub" href="https://hub.example.com/"/>
+    <atom:link rel="hub" href="https://other-hub.example.com/"/>
+    <atom:link rel="self" href="https://example.com/canonical.xml"/>
+    <title>Title</title>
+  </channel>
+</rss>"#,
+        );
+
+        let count_jobs = |conn: &PgConnection| -> i64 {
+            schema::job::table
+                .filter(schema::job::name.eq(jobs::websub_subscriber::NAME))
+                .count()
+                .first(conn)
+                .unwrap()
+        };
+        let select_subscription = |conn: &PgConnection| -> model::PodcastWebsubSubscription {
+            schema::podcast_websub_subscription::table.first(conn).unwrap()
+        };
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap();
+        }
+
+        let subscription = select_subscription(&*bootstrap.conn);
+        assert_eq!("https://hub.example.com/", subscription.hub_url);
+        assert_eq!("https://example.com/canonical.xml", subscription.topic_url);
+        assert_eq!(1, count_jobs(&*bootstrap.conn));
+
+        // A subscription that was just requested isn't requested again.
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.disable_shortcut = true;
+            mediator.run(&log).unwrap();
+        }
+        assert_eq!(1, count_jobs(&*bootstrap.conn));
+
+        // But one that's about to expire is renewed, even if the feed hasn't changed.
+        diesel::update(schema::podcast_websub_subscription::table)
+            .set((
+                schema::podcast_websub_subscription::expires_at
+                    .eq(Utc::now() + Duration::hours(12)),
+                schema::podcast_websub_subscription::requested_at
+                    .eq(Utc::now() - Duration::days(10)),
+                schema::podcast_websub_subscription::verified_at
+                    .eq(Utc::now() - Duration::days(10)),
+            ))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap();
+        }
+        assert_eq!(2, count_jobs(&*bootstrap.conn));
+
+        let renewed = select_subscription(&*bootstrap.conn);
+        assert_eq!(subscription.secret, renewed.secret);
+        assert!(renewed.requested_at > subscription.requested_at);
+        assert!(renewed.verified_at.is_some());
+    }
+
+    #[test]
+    fn test_podcast_update_next_crawl_at() {
+        let mut bootstrap = TestBootstrap::new(test_helpers::MINIMAL_FEED);
+
+        // A single episode isn't enough to say anything about the podcast's habits,
+        // so it gets a default interval (plus jitter).
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert!(res.podcast.next_crawl_at > Utc::now() + Duration::minutes(50));
+        assert!(res.podcast.next_crawl_at < Utc::now() + Duration::minutes(80));
+
+        // Podcasts whose updates are pushed to us are crawled much less often.
+        let subscription = test_data::podcast_websub_subscription::insert_args(
+            &bootstrap.log,
+            &*bootstrap.conn,
+            test_data::podcast_websub_subscription::Args {
+                podcast: Some(&res.podcast),
+            },
+        );
+        diesel::update(schema::podcast_websub_subscription::table.filter(
+            schema::podcast_websub_subscription::id.eq(subscription.id),
+        )).set((
+            schema::podcast_websub_subscription::expires_at.eq(Utc::now() + Duration::days(10)),
+            schema::podcast_websub_subscription::verified_at.eq(Utc::now()),
+        ))
+            .execute(&*bootstrap.conn)
+            .unwrap();
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert!(res.podcast.next_crawl_at > Utc::now() + Duration::days(6));
+    }
+
+    #[test]
+    fn test_podcast_update_idempotency_with_shortcut() {
+        let mut bootstrap = TestBootstrap::new(test_helpers::MINIMAL_FEED);
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            let _res = mediator.run(&log).unwrap();
+            let _res = mediator.run(&log).unwrap();
+        }
+
+        // Make sure that we ended up with one of everything
+        assert_eq!(
+            Ok(1),
+            schema::episode::table.count().first(&*bootstrap.conn)
+        );
+        assert_eq!(
+            Ok(1),
+            schema::podcast::table.count().first(&*bootstrap.conn)
+        );
+        assert_eq!(
+            Ok(1),
+            schema::podcast_feed_content::table
+                .count()
+                .first(&*bootstrap.conn)
+        );
+        assert_eq!(
+            Ok(1),
+            schema::podcast_feed_location::table
+                .count()
+                .first(&*bootstrap.conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_idempotency_without_shortcut() {
+        let mut bootstrap = TestBootstrap::new(test_helpers::MINIMAL_FEED);
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+
+            // Disable the shortcut that checks to see if content has already been
+            // processed so that we can verify idempotency even if the mediator
+            // is doing a complete end-to-end run.
+            mediator.disable_shortcut = true;
+
+            let _res = mediator.run(&log).unwrap();
+            let _res = mediator.run(&log).unwrap();
+        }
+
+        // Make sure that we ended up with one of everything
+        assert_eq!(
+            Ok(1),
+            schema::content_blob::table.count().first(&*bootstrap.conn)
+        );
+        assert_eq!(
+            Ok(1),
+            schema::episode::table.count().first(&*bootstrap.conn)
+        );
+        assert_eq!(
+            Ok(1),
+            schema::podcast::table.count().first(&*bootstrap.conn)
+        );
+        assert_eq!(
+            Ok(1),
+            schema::podcast_feed_content::table
+                .count()
+                .first(&*bootstrap.conn)
+        );
+        assert_eq!(
+            Ok(1),
+            schema::podcast_feed_location::table
+                .count()
+                .first(&*bootstrap.conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_prefer_latest_url() {
+        // Establish one connection with an open transaction for which data will live
+        // across this whole test.
+        let conn = test_helpers::connection();
+
+        let mut bootstrap = TestBootstrapWithConn::new(test_helpers::MINIMAL_FEED, &*conn);
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
 
         // Running the updater above will have inserted one location record in the
         // database for our bootstra's default URL. Here we add a second one
@@ -1097,28 +4258,662 @@ mod tests {
             feed_url:           "https://example.com/new-feed.xml".to_owned(),
             last_retrieved_at:  Utc::now() + Duration::minutes(10),
             podcast_id:         res.podcast.id,
+            etag:               None,
+            last_modified:      None,
+            superseded_at:      None,
+        };
+        diesel::insert_into(schema::podcast_feed_location::table)
+            .values(&location_ins)
+            .execute(&*conn)
+            .unwrap();
+
+        // Now run it again.
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap();
+        };
+
+        // Make sure that our new URL wasn't superseded by the default one that comes
+        // from the bootstrap.
+        let latest_url: String = schema::podcast_feed_location::table
+            .filter(schema::podcast_feed_location::podcast_id.eq(res.podcast.id))
+            .select(schema::podcast_feed_location::feed_url)
+            .order(schema::podcast_feed_location::last_retrieved_at.desc())
+            .limit(1)
+            .first(&*conn)
+            .unwrap();
+        assert_eq!("https://example.com/new-feed.xml", latest_url.as_str());
+    }
+
+    #[test]
+    fn test_podcast_update_conditional_get() {
+        let _common = test_helpers::CommonTestBootstrap::new();
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+        let mut http_requester = HttpRequesterConditional {
+            data:             test_helpers::MINIMAL_FEED.to_vec(),
+            etag:             "\"abc123\"",
+            last_modified:    "Sun, 24 Dec 2017 21:37:32 GMT",
+            num_not_modified: 0,
+        };
+
+        {
+            let mut mediator = Mediator {
+                conn:             &*conn,
+                disable_shortcut: false,
+                enqueue_jobs:     true,
+                feed_url:         "https://example.com/feed.xml".to_owned(),
+                follow_pages:     false,
+                http_requester:   &mut http_requester,
+            };
+
+            // The first run has no validators to send, so it gets the full feed and
+            // stores the validators that came back with it.
+            let res = mediator.run(&log).unwrap();
+            assert!(res.episodes.is_some());
+            assert_eq!(Some("\"abc123\"".to_owned()), res.location.etag);
+            assert_eq!(
+                Some("Sun, 24 Dec 2017 21:37:32 GMT".to_owned()),
+                res.location.last_modified
+            );
+
+            // The second run sends them back and is told that nothing has changed.
+            let res2 = mediator.run(&log).unwrap();
+            assert!(res2.episodes.is_none());
+            assert_eq!(res.location.id, res2.location.id);
+            assert_eq!(res.podcast.id, res2.podcast.id);
+
+            // Disabling the shortcut also stops validators from being sent so that
+            // the feed gets fully reprocessed.
+            mediator.disable_shortcut = true;
+            let res3 = mediator.run(&log).unwrap();
+            assert!(res3.episodes.is_some());
+        }
+
+        assert_eq!(1, http_requester.num_not_modified);
+        assert_eq!(
+            Ok(1),
+            schema::podcast_feed_content::table.count().first(&*conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_new_feed_url() {
+        let mut bootstrap = TestBootstrap::new(
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <itunes:new-feed-url>https://example.com/new-feed.xml</itunes:new-feed-url>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+  </channel>
+</rss>"#,
+        );
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
         };
-        diesel::insert_into(schema::podcast_feed_location::table)
-            .values(&location_ins)
-            .execute(&*conn)
+        assert_eq!("https://example.com/new-feed.xml", res.location.feed_url);
+
+        // The location that we fetched from is retired in favor of the new one.
+        let locations: Vec<model::PodcastFeedLocation> = schema::podcast_feed_location::table
+            .filter(schema::podcast_feed_location::podcast_id.eq(res.podcast.id))
+            .order(schema::podcast_feed_location::feed_url)
+            .load(&*bootstrap.conn)
             .unwrap();
+        assert_eq!(2, locations.len());
+        assert_eq!("https://example.com/feed.xml", locations[0].feed_url);
+        assert!(locations[0].superseded_at.is_some());
+        assert_eq!("https://example.com/new-feed.xml", locations[1].feed_url);
+        assert!(locations[1].superseded_at.is_none());
+    }
 
-        // Now run it again.
+    #[test]
+    fn test_podcast_update_new_feed_url_other_podcast() {
+        let conn = test_helpers::connection();
+
+        // Another podcast already lives at the URL that the feed will declare.
+        let other = {
+            let mut bootstrap = TestBootstrapWithConn::new(test_helpers::MINIMAL_FEED, &*conn);
+            bootstrap.feed_url = "https://example.com/new-feed.xml";
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        let mut bootstrap = TestBootstrapWithConn::new(
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <itunes:new-feed-url>https://example.com/new-feed.xml</itunes:new-feed-url>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+  </channel>
+</rss>"#,
+            &*conn,
+        );
+
+        // The feed stays where it is, and does so across repeated updates.
+        for _ in 0..2 {
+            let res = {
+                let (mut mediator, log) = bootstrap.mediator();
+                mediator.run(&log).unwrap()
+            };
+            assert_ne!(other.podcast.id, res.podcast.id);
+            assert_eq!("https://example.com/feed.xml", res.location.feed_url);
+            assert!(res.location.superseded_at.is_none());
+        }
+
+        // Neither podcast's location has been retired.
+        assert_eq!(
+            Ok(0),
+            schema::podcast_feed_location::table
+                .filter(schema::podcast_feed_location::superseded_at.is_not_null())
+                .count()
+                .first(&*conn)
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_permanent_redirect() {
+        // Establish one connection with an open transaction for which data will live
+        // across this whole test.
+        let conn = test_helpers::connection();
+
+        let mut bootstrap = TestBootstrapWithConn::new(test_helpers::MINIMAL_FEED, &*conn);
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        let mut http_requester = HttpRequesterRedirect {
+            data:               test_helpers::MINIMAL_FEED.to_vec(),
+            final_url:          "https://example.com/moved.xml",
+            permanent_redirect: false,
+        };
+
+        // A temporary redirect leaves the podcast where it was.
+        {
+            let mut mediator = Mediator {
+                conn:             &*conn,
+                disable_shortcut: true,
+                enqueue_jobs:     true,
+                feed_url:         "https://example.com/feed.xml".to_owned(),
+                follow_pages:     false,
+                http_requester:   &mut http_requester,
+            };
+            let res2 = mediator.run(&bootstrap.log).unwrap();
+            assert_eq!(res.location.id, res2.location.id);
+            assert!(res2.location.superseded_at.is_none());
+        }
+
+        // A permanent one moves it.
+        http_requester.permanent_redirect = true;
+        {
+            let mut mediator = Mediator {
+                conn:             &*conn,
+                disable_shortcut: true,
+                enqueue_jobs:     true,
+                feed_url:         "https://example.com/feed.xml".to_owned(),
+                follow_pages:     false,
+                http_requester:   &mut http_requester,
+            };
+            let res2 = mediator.run(&bootstrap.log).unwrap();
+            assert_eq!(res.podcast.id, res2.podcast.id);
+            assert_eq!("https://example.com/moved.xml", res2.location.feed_url);
+        }
+
+        let old_location: model::PodcastFeedLocation = schema::podcast_feed_location::table
+            .filter(schema::podcast_feed_location::id.eq(res.location.id))
+            .first(&*conn)
+            .unwrap();
+        assert!(old_location.superseded_at.is_some());
+
+        // And the new URL is what gets used from now on, even when we're given the
+        // old one.
+        let latest_url = {
+            let (mediator, log) = bootstrap.mediator();
+            mediator
+                .select_latest_url(&log, "https://example.com/feed.xml")
+                .unwrap()
+        };
+        assert_eq!("https://example.com/moved.xml", latest_url.as_str());
+    }
+
+    #[test]
+    fn test_podcast_update_feed_discovery() {
+        let _common = test_helpers::CommonTestBootstrap::new();
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+
+        let mut pages: HashMap<&'static str, &'static [u8]> = HashMap::new();
+        pages.insert(
+            "https://example.com/",
+            br#"<!DOCTYPE html>
+<html>
+  <head>
+    <link rel="stylesheet" type="text/css" href="/main.css">
+    <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+  </head>
+</html>"#,
+        );
+        pages.insert("https://example.com/feed.xml", test_helpers::MINIMAL_FEED);
+        let mut http_requester = HttpRequesterPages {
+            pages,
+            requested_urls: Vec::new(),
+        };
+
+        let res = Mediator {
+            conn:             &*conn,
+            disable_shortcut: false,
+            enqueue_jobs:     true,
+            feed_url:         "https://example.com/".to_owned(),
+            follow_pages:     false,
+            http_requester:   &mut http_requester,
+        }.run(&log)
+            .unwrap();
+
+        assert_eq!(Some("https://example.com/feed.xml".to_owned()), res.discovered_url);
+        assert_eq!("https://example.com/feed.xml", res.location.feed_url);
+        assert_eq!(
+            vec!["https://example.com/", "https://example.com/feed.xml"],
+            http_requester.requested_urls
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_feed_discovery_no_feed() {
+        let _common = test_helpers::CommonTestBootstrap::new();
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+
+        let mut pages: HashMap<&'static str, &'static [u8]> = HashMap::new();
+        pages.insert(
+            "https://example.com/",
+            b"<html><head><title>A Podcast</title></head></html>",
+        );
+        let mut http_requester = HttpRequesterPages {
+            pages,
+            requested_urls: Vec::new(),
+        };
+
+        let res = Mediator {
+            conn:             &*conn,
+            disable_shortcut: false,
+            enqueue_jobs:     true,
+            feed_url:         "https://example.com/".to_owned(),
+            follow_pages:     false,
+            http_requester:   &mut http_requester,
+        }.run(&log);
+
+        assert!(res.is_err());
+        assert_eq!(
+            "Bad request: That URL points to a web page that doesn't link to a podcast feed.",
+            res.err().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_removed_episodes() {
+        // Establish one connection with an open transaction for which data will live
+        // across this whole test.
+        let conn = test_helpers::connection();
+
+        let two_items = br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+    <item>
+      <guid>2</guid>
+      <media:content url="https://example.com/item-2" type="audio/mpeg"/>
+      <pubDate>Mon, 25 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 2 Title</title>
+    </item>
+  </channel>
+</rss>"#;
+
+        let res = {
+            let mut bootstrap = TestBootstrapWithConn::new(two_items, &*conn);
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        // Item 2 is pulled from the feed.
         {
+            let mut bootstrap = TestBootstrapWithConn::new(test_helpers::MINIMAL_FEED, &*conn);
             let (mut mediator, log) = bootstrap.mediator();
             mediator.run(&log).unwrap();
+        }
+
+        let removed = |guid: &str| -> bool {
+            schema::episode::table
+                .filter(schema::episode::podcast_id.eq(res.podcast.id))
+                .filter(schema::episode::guid.eq(guid))
+                .select(schema::episode::removed_at)
+                .first::<Option<DateTime<Utc>>>(&*conn)
+                .unwrap()
+                .is_some()
         };
+        assert!(!removed("1"));
+        assert!(removed("2"));
 
-        // Make sure that our new URL wasn't superseded by the default one that comes
-        // from the bootstrap.
-        let latest_url: String = schema::podcast_feed_location::table
-            .filter(schema::podcast_feed_location::podcast_id.eq(res.podcast.id))
-            .select(schema::podcast_feed_location::feed_url)
-            .order(schema::podcast_feed_location::last_retrieved_at.desc())
-            .limit(1)
+        // And then it comes back. The shortcut is disabled because we've seen this
+        // exact content before.
+        {
+            let mut bootstrap = TestBootstrapWithConn::new(two_items, &*conn);
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.disable_shortcut = true;
+            mediator.run(&log).unwrap();
+        }
+        assert!(!removed("1"));
+        assert!(!removed("2"));
+
+        // A paged feed never removes episodes because it's not expected to contain
+        // all of them.
+        {
+            let mut bootstrap = TestBootstrapWithConn::new(
+                br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <atom:link rel="next" href="https://example.com/feed.xml?page=2"/>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+  </channel>
+</rss>"#,
+                &*conn,
+            );
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap();
+        }
+        assert!(!removed("2"));
+    }
+
+    #[test]
+    fn test_podcast_update_episode_revisions() {
+        // Establish one connection with an open transaction for which data will live
+        // across this whole test.
+        let conn = test_helpers::connection();
+
+        let res = {
+            let mut bootstrap = TestBootstrapWithConn::new(test_helpers::MINIMAL_FEED, &*conn);
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        let episode_id = res.episodes.unwrap()[0].id;
+
+        let load_revisions = || -> Vec<model::EpisodeRevision> {
+            schema::episode_revision::table
+                .filter(schema::episode_revision::episode_id.eq(episode_id))
+                .order(schema::episode_revision::field)
+                .load(&*conn)
+                .unwrap()
+        };
+
+        // A new episode doesn't have any revisions.
+        assert_eq!(0, load_revisions().len());
+
+        // The publisher swaps out the audio file and retitles the episode.
+        {
+            let mut bootstrap = TestBootstrapWithConn::new(
+                br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1-fixed" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title (Fixed)</title>
+    </item>
+  </channel>
+</rss>"#,
+                &*conn,
+            );
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap();
+        }
+
+        let revisions = load_revisions();
+        assert_eq!(2, revisions.len());
+
+        assert_eq!("media_url", revisions[0].field);
+        assert_eq!(Some("https://example.com/item-1".to_owned()), revisions[0].old_value);
+        assert_eq!(Some("https://example.com/item-1-fixed".to_owned()), revisions[0].new_value);
+
+        assert_eq!("title", revisions[1].field);
+        assert_eq!(Some("Item 1 Title".to_owned()), revisions[1].old_value);
+        assert_eq!(Some("Item 1 Title (Fixed)".to_owned()), revisions[1].new_value);
+    }
+
+    #[test]
+    fn test_podcast_update_paged_feed() {
+        let _common = test_helpers::CommonTestBootstrap::new();
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+
+        let mut http_requester = paged_requester();
+        let res = Mediator {
+            conn:             &*conn,
+            disable_shortcut: false,
+            enqueue_jobs:     true,
+            feed_url:         "https://example.com/feed.xml".to_owned(),
+            follow_pages:     false,
+            http_requester:   &mut http_requester,
+        }.run(&log)
+            .unwrap();
+
+        // The podcast is new, so all of its pages are retrieved. Page 3 links back to
+        // the feed, which is retrieved again before we notice that we've already
+        // seen its content.
+        assert_eq!(
+            vec![
+                "https://example.com/feed.xml",
+                "https://example.com/feed.xml?page=2",
+                "https://example.com/feed.xml?page=3",
+                "https://example.com/feed.xml",
+            ],
+            http_requester.requested_urls
+        );
+
+        let mut guids: Vec<String> = res.episodes
+            .unwrap()
+            .into_iter()
+            .map(|e| e.guid)
+            .collect();
+        guids.sort();
+        assert_eq!(vec!["1", "2", "3"], guids);
+
+        let page_numbers: Vec<i32> = schema::podcast_feed_content::table
+            .filter(schema::podcast_feed_content::podcast_id.eq(res.podcast.id))
+            .select(schema::podcast_feed_content::page_number)
+            .order(schema::podcast_feed_content::page_number)
+            .load(&*conn)
+            .unwrap();
+        assert_eq!(vec![1, 2, 3], page_numbers);
+    }
+
+    #[test]
+    fn test_podcast_update_paged_feed_existing() {
+        let _common = test_helpers::CommonTestBootstrap::new();
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+
+        {
+            let mut bootstrap = TestBootstrapWithConn::new(test_helpers::MINIMAL_FEED, &*conn);
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap();
+        }
+
+        // Pages aren't followed for a podcast that we already know about ...
+        let mut http_requester = paged_requester();
+        Mediator {
+            conn:             &*conn,
+            disable_shortcut: false,
+            enqueue_jobs:     true,
+            feed_url:         "https://example.com/feed.xml".to_owned(),
+            follow_pages:     false,
+            http_requester:   &mut http_requester,
+        }.run(&log)
+            .unwrap();
+        assert_eq!(1, http_requester.requested_urls.len());
+
+        // ... unless we ask for them.
+        let mut http_requester = paged_requester();
+        let res = Mediator {
+            conn:             &*conn,
+            disable_shortcut: true,
+            enqueue_jobs:     true,
+            feed_url:         "https://example.com/feed.xml".to_owned(),
+            follow_pages:     true,
+            http_requester:   &mut http_requester,
+        }.run(&log)
+            .unwrap();
+        assert_eq!(4, http_requester.requested_urls.len());
+        assert_eq!(3, res.episodes.unwrap().len());
+    }
+
+    #[test]
+    fn test_podcast_update_paged_feed_page_error() {
+        let _common = test_helpers::CommonTestBootstrap::new();
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+
+        // Page 2 is missing, but that shouldn't stop us from ingesting the first page.
+        let mut http_requester = paged_requester();
+        http_requester
+            .pages
+            .remove("https://example.com/feed.xml?page=2");
+
+        let res = Mediator {
+            conn:             &*conn,
+            disable_shortcut: false,
+            enqueue_jobs:     true,
+            feed_url:         "https://example.com/feed.xml".to_owned(),
+            follow_pages:     false,
+            http_requester:   &mut http_requester,
+        }.run(&log)
+            .unwrap();
+        assert_eq!(2, http_requester.requested_urls.len());
+        assert_eq!(1, res.episodes.unwrap().len());
+    }
+
+    #[test]
+    fn test_podcast_update_paged_feed_page_database_error() {
+        let _common = test_helpers::CommonTestBootstrap::new();
+        let conn = test_helpers::connection();
+        let log = test_helpers::log();
+
+        // A database error while retrieving page 2 is swallowed like any other page
+        // error, so it mustn't leave the update's transaction aborted.
+        let mut http_requester = HttpRequesterPagesDatabaseError {
+            conn:      &*conn,
+            error_url: "https://example.com/feed.xml?page=2",
+            inner:     paged_requester(),
+        };
+
+        let res = Mediator {
+            conn:             &*conn,
+            disable_shortcut: false,
+            enqueue_jobs:     true,
+            feed_url:         "https://example.com/feed.xml".to_owned(),
+            follow_pages:     false,
+            http_requester:   &mut http_requester,
+        }.run(&log)
+            .unwrap();
+        assert_eq!(1, res.episodes.unwrap().len());
+
+        let num_podcasts: i64 = schema::podcast::table
+            .filter(schema::podcast::id.eq(res.podcast.id))
+            .count()
             .first(&*conn)
             .unwrap();
-        assert_eq!("https://example.com/new-feed.xml", latest_url.as_str());
+        assert_eq!(1, num_podcasts);
+    }
+
+    #[test]
+    fn test_podcast_update_resolve_url() {
+        assert_eq!(
+            Some("https://example.com/feed.xml?page=2".to_owned()),
+            resolve_url("https://example.com/feed.xml", "?page=2")
+        );
+        assert_eq!(
+            Some("https://example.com/archive/2.xml".to_owned()),
+            resolve_url("https://example.com/feed.xml", "/archive/2.xml")
+        );
+        assert_eq!(
+            Some("https://example.org/feed.xml".to_owned()),
+            resolve_url("https://example.com/feed.xml", "https://example.org/feed.xml")
+        );
+        assert_eq!(
+            None,
+            resolve_url("https://example.com/feed.xml", "ftp://example.com/feed.xml")
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_published_at_fallback() {
+        let mut bootstrap = TestBootstrap::new(
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+    <item>
+      <guid>2</guid>
+      <media:content url="https://example.com/item-2" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32</pubDate>
+      <title>Item 2 Title</title>
+    </item>
+  </channel>
+</rss>"#,
+        );
+        let (mut mediator, log) = bootstrap.mediator();
+        let res = mediator.run(&log).unwrap();
+
+        let episodes = res.episodes.unwrap();
+        assert_eq!(2, episodes.len());
+        assert_eq!(None, episodes[0].published_at_fallback);
+        assert_eq!(
+            Some("no_timezone".to_owned()),
+            episodes[1].published_at_fallback
+        );
+        assert_eq!(
+            Utc.ymd(2017, 12, 24).and_hms(21, 37, 32),
+            episodes[1].published_at
+        );
     }
 
     #[test]
@@ -1186,7 +4981,10 @@ mod tests {
         let err = res.err().unwrap();
         let mut err_iter = err.iter();
 
-        assert_eq!("No <rss> tag found", err_iter.next().unwrap().to_string());
+        assert_eq!(
+            "No <rss> or <feed> tag found",
+            err_iter.next().unwrap().to_string()
+        );
         assert_eq!(true, err_iter.next().is_none());
     }
 
@@ -1217,6 +5015,92 @@ mod tests {
         let podcast_ex: model::PodcastException =
             schema::podcast_exception::table.first(&*conn).unwrap();
         assert_eq!(res.podcast.id, podcast_ex.podcast_id);
+        assert_eq!(1, podcast_ex.num_occurrences);
+
+        // Consecutive failures are counted and back off the podcast's next crawl.
+        {
+            let mut bootstrap = TestBootstrapWithConn::new(b"not a feed", &*conn);
+            let (mut mediator, log) = bootstrap.mediator();
+            let res = mediator.run(&log);
+            assert_eq!(true, res.is_err());
+        }
+
+        let podcast_ex: model::PodcastException =
+            schema::podcast_exception::table.first(&*conn).unwrap();
+        assert_eq!(2, podcast_ex.num_occurrences);
+
+        let podcast: model::Podcast = schema::podcast::table.first(&*conn).unwrap();
+        assert!(podcast.next_crawl_at > Utc::now() + Duration::hours(3));
+    }
+
+    #[test]
+    fn test_podcast_update_exception_body_too_large() {
+        let conn = test_helpers::connection();
+
+        let res = {
+            let mut bootstrap = TestBootstrapWithConn::new(test_helpers::MINIMAL_FEED, &*conn);
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        // Run again against a host serving a feed that's too big to read.
+        {
+            let log = test_helpers::log();
+            let mut http_requester = HttpRequesterTooLarge {};
+            let res = Mediator {
+                conn:             &*conn,
+                disable_shortcut: false,
+                enqueue_jobs:     true,
+                feed_url:         "https://example.com/feed.xml".to_owned(),
+                follow_pages:     false,
+                http_requester:   &mut http_requester,
+            }.run(&log);
+            assert_eq!(true, res.is_err());
+        }
+
+        let podcast_ex: model::PodcastException =
+            schema::podcast_exception::table.first(&*conn).unwrap();
+        assert_eq!(res.podcast.id, podcast_ex.podcast_id);
+        assert_eq!(
+            vec![
+                "Response body from https://example.com/feed.xml exceeded maximum size of \
+                 100 bytes"
+                    .to_owned(),
+            ],
+            podcast_ex.errors
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_exception_throttled() {
+        let conn = test_helpers::connection();
+
+        let res = {
+            let mut bootstrap = TestBootstrapWithConn::new(test_helpers::MINIMAL_FEED, &*conn);
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        // Run again against a host that's asking us to back off.
+        {
+            let log = test_helpers::log();
+            let mut http_requester = HttpRequesterThrottled {};
+            let res = Mediator {
+                conn:             &*conn,
+                disable_shortcut: false,
+                enqueue_jobs:     true,
+                feed_url:         "https://example.com/feed.xml".to_owned(),
+                follow_pages:     false,
+                http_requester:   &mut http_requester,
+            }.run(&log);
+            assert_eq!(true, res.is_err());
+        }
+
+        // Nothing is recorded against the podcast and it isn't backed off.
+        assert_eq!(Ok(0), schema::podcast_exception::table.count().first(&*conn));
+
+        let podcast: model::Podcast = schema::podcast::table.first(&*conn).unwrap();
+        assert_eq!(res.podcast.next_crawl_at, podcast.next_crawl_at);
     }
 
     #[test]
@@ -1260,41 +5144,214 @@ mod tests {
     }
 
     #[test]
-    fn test_podcast_update_parse_date_time() {
-        // Valid RFC 2822
+    fn test_podcast_update_decode_feed() {
+        let log = test_helpers::log();
+
+        // "é" in ISO-8859-1/Windows-1252
+        let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><title>caf\xe9</title>";
+
+        // Declared in the XML prolog, which is rewritten to match the output
         assert_eq!(
-            Utc.ymd(2017, 12, 24).and_hms(21, 37, 32),
-            parse_date_time("Sun, 24 Dec 2017 21:37:32 +0000").unwrap()
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><title>café</title>",
+            decode_feed(&log, latin1, None)
         );
 
-        // Also valid -- check use of named timezones
+        // Not declared at all, which falls back to Windows-1252
         assert_eq!(
-            FixedOffset::west(5 * 3600) // EST-0500
-                .ymd(2017, 12, 24)
-                .and_hms(21, 37, 32)
-                .with_timezone(&Utc),
-            parse_date_time("Sun, 24 Dec 2017 21:37:32 EST").unwrap()
+            "<title>café</title>",
+            decode_feed(&log, b"<title>caf\xe9</title>", None)
         );
 
-        // Never forget how uselessly pedantic Rust programmers are. A "-0000" is
-        // technically considered missing even though it's obvious to anyone on
-        // Earth what should be done with it. Our special implementation
-        // handles it, so test this case specifically.
+        // Declared in `Content-Type`
         assert_eq!(
-            Utc.ymd(2017, 12, 24).and_hms(21, 37, 32),
-            parse_date_time("Sun, 24 Dec 2017 21:37:32 -0000").unwrap()
+            "<title>café</title>",
+            decode_feed(
+                &log,
+                b"<title>caf\xe9</title>",
+                Some("application/rss+xml; charset=iso-8859-1")
+            )
+        );
+
+        // `Content-Type` takes precedence over the XML prolog
+        assert_eq!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><title>café</title>",
+            decode_feed(
+                &log,
+                b"<?xml version=\"1.0\" encoding=\"UTF-8\"?><title>caf\xe9</title>",
+                Some("text/xml; charset=\"windows-1252\"")
+            )
+        );
+
+        // Valid UTF-8 takes precedence over a stale XML prolog
+        assert_eq!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><title>café</title>",
+            decode_feed(
+                &log,
+                "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><title>café</title>".as_bytes(),
+                None
+            )
+        );
+
+        // A byte order mark takes precedence over everything, and is stripped
+        assert_eq!(
+            "<title>café</title>",
+            decode_feed(
+                &log,
+                b"\xef\xbb\xbf<title>caf\xc3\xa9</title>",
+                Some("text/xml; charset=iso-8859-1")
+            )
+        );
+    }
+
+    #[test]
+    fn test_podcast_update_decode_feed_large() {
+        let log = test_helpers::log();
+
+        // Much more than is examined to decide on an encoding, so most of the feed
+        // is decoded as it's read.
+        let mut latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><title>".to_vec();
+        let mut expected = "<?xml version=\"1.0\" encoding=\"UTF-8\"?><title>".to_owned();
+        for _ in 0..DECODE_PREFIX_SIZE {
+            latin1.extend_from_slice(b"caf\xe9 ");
+            expected.push_str("café ");
+        }
+        latin1.extend_from_slice(b"</title>");
+        expected.push_str("</title>");
+        assert_eq!(expected, decode_feed(&log, latin1.as_slice(), None));
+
+        // A multi-byte character that straddles the end of the examined prefix
+        // doesn't stop valid UTF-8 from being recognized.
+        let mut utf8 = "<title>".to_owned();
+        while utf8.len() < DECODE_PREFIX_SIZE - 1 {
+            utf8.push('a');
+        }
+        utf8.push_str("café</title>");
+        assert_eq!(utf8, decode_feed(&log, utf8.as_bytes(), None));
+    }
+
+    #[test]
+    fn test_podcast_update_element_text_transcoded() {
+        let log = test_helpers::log();
+
+        // quick-xml decodes text using the encoding in the prolog, so if that
+        // weren't rewritten this would come out as "cafÃ©".
+        let data = decode_feed(
+            &log,
+            b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><title>caf\xe9</title>",
+            None,
         );
 
-        // Notice the truncated "0:" -- seen on Communion After Dark
+        let mut buf = Vec::new();
+        let mut reader = Reader::from_reader(data.as_bytes());
+        loop {
+            match reader.read_event(&mut buf).unwrap() {
+                Event::Start(_) => break,
+                Event::Eof => panic!("No <title> tag found"),
+                _ => {}
+            }
+            buf.clear();
+        }
+        assert_eq!("café", element_text(&log, &mut reader).unwrap());
+    }
+
+    #[test]
+    fn test_podcast_update_hashing_reader() {
+        let log = test_helpers::log();
+
+        let mut sha = Sha256::new();
+        sha.input(test_helpers::MINIMAL_FEED);
+        let expected = sha.result_str();
+
+        // The parser stops at the end of the document, but trailing content should
+        // still be included in the hash.
+        let mut data = test_helpers::MINIMAL_FEED.to_vec();
+        data.extend_from_slice(b"\n<!-- trailing -->\n");
+        let mut sha = Sha256::new();
+        sha.input(data.as_slice());
+        let expected_trailing = sha.result_str();
+
+        let mut hashing_reader = HashingReader::new(test_helpers::MINIMAL_FEED);
+        Mediator::parse_feed(&log, BufReader::new(&mut hashing_reader), None).unwrap();
+        assert_eq!(expected, hashing_reader.finish().unwrap());
+
+        let mut hashing_reader = HashingReader::new(data.as_slice());
+        Mediator::parse_feed(&log, BufReader::new(&mut hashing_reader), None).unwrap();
+        assert_eq!(expected_trailing, hashing_reader.finish().unwrap());
+    }
+
+    #[test]
+    fn test_podcast_update_needs_websub_request() {
+        let subscription = model::PodcastWebsubSubscription {
+            id:           0,
+            expires_at:   Some(Utc::now() + Duration::days(5)),
+            hub_url:      "https://hub.example.com/".to_owned(),
+            podcast_id:   0,
+            requested_at: Utc::now() - Duration::days(5),
+            secret:       "secret".to_owned(),
+            topic_url:    "https://example.com/feed.xml".to_owned(),
+            verified_at:  Some(Utc::now() - Duration::days(5)),
+        };
+        let hub_url = subscription.hub_url.clone();
+        let topic_url = subscription.topic_url.clone();
+        let needs = |s: &model::PodcastWebsubSubscription| {
+            needs_websub_request(s, hub_url.as_str(), topic_url.as_str())
+        };
+
+        assert!(!needs(&subscription));
+        assert!(needs_websub_request(
+            &subscription,
+            "https://other-hub.example.com/",
+            topic_url.as_str()
+        ));
+
+        let mut expiring = subscription.clone();
+        expiring.expires_at = Some(Utc::now() + Duration::hours(1));
+        assert!(needs(&expiring));
+
+        // Unless it was only just requested.
+        expiring.requested_at = Utc::now();
+        assert!(!needs(&expiring));
+
+        let mut unverified = subscription.clone();
+        unverified.verified_at = None;
+        assert!(needs(&unverified));
+    }
+
+    #[test]
+    fn test_podcast_update_normalize_category_slug() {
+        assert_eq!("arts", normalize_category_slug("arts".to_owned()));
+        assert_eq!("leisure", normalize_category_slug("games-and-hobbies".to_owned()));
         assert_eq!(
-            FixedOffset::west(5 * 3600) // EST-0500
-                .ymd(2017, 12, 24)
-                .and_hms(0, 37, 32)
-                .with_timezone(&Utc),
-            parse_date_time("Sun, 24 Dec 2017 0:37:32 EST").unwrap()
+            "self-improvement",
+            normalize_category_slug("self-help".to_owned())
         );
     }
 
+    #[test]
+    fn test_podcast_update_parse_duration() {
+        assert_eq!(Some(3723), parse_duration("01:02:03"));
+        assert_eq!(Some(3723), parse_duration("1:02:03"));
+        assert_eq!(Some(123), parse_duration("02:03"));
+        assert_eq!(Some(3723), parse_duration("3723"));
+        assert_eq!(Some(3723), parse_duration(" 01:02:03.500 "));
+
+        assert_eq!(None, parse_duration(""));
+        assert_eq!(None, parse_duration("1:01:02:03"));
+        assert_eq!(None, parse_duration("about an hour"));
+    }
+
+    #[test]
+    fn test_podcast_update_parse_npt() {
+        assert_eq!(Some(3723.5), parse_npt("01:02:03.500"));
+        assert_eq!(Some(123.0), parse_npt("02:03"));
+        assert_eq!(Some(3.25), parse_npt(" 3.25 "));
+
+        assert_eq!(None, parse_npt(""));
+        assert_eq!(None, parse_npt("01.5:02"));
+        assert_eq!(None, parse_npt("1:01:02:03"));
+        assert_eq!(None, parse_npt("-5"));
+    }
+
     #[test]
     fn test_podcast_update_real_feed() {
         {
@@ -1454,16 +5511,26 @@ mod tests {
             last_retrieved_at: Utc::now(),
             link_url:          None,
             title:             "Title".to_owned(),
+            author:            None,
+            block:             None,
+            itunes_image_url:  None,
+            owner_email:       None,
+            owner_name:        None,
+            guid:              None,
+            locked:            None,
+            next_crawl_at:     Utc::now(),
         };
 
         {
             let raw = valid_raw_episode();
-            match validate_episode(&raw, &podcast).unwrap() {
+            match validate_episode(&raw, podcast.id).unwrap() {
                 EpisodeOrInvalid::Valid(p) => {
                     assert_eq!(raw.guid.unwrap(), p.guid);
                     assert_eq!(raw.media_url.unwrap(), p.media_url);
                     assert_eq!(
-                        parse_date_time(raw.published_at.unwrap().as_str()).unwrap(),
+                        date_parser::parse(raw.published_at.unwrap().as_str())
+                            .unwrap()
+                            .date_time,
                         p.published_at
                     );
                     assert_eq!(raw.title.unwrap(), p.title);
@@ -1478,7 +5545,7 @@ mod tests {
         {
             let mut raw = valid_raw_episode();
             raw.guid = None;
-            match validate_episode(&raw, &podcast).unwrap() {
+            match validate_episode(&raw, podcast.id).unwrap() {
                 EpisodeOrInvalid::Valid(_) => panic!("Unexpected valid episode"),
                 EpisodeOrInvalid::Invalid {
                     message: m,
@@ -1493,7 +5560,7 @@ mod tests {
         {
             let mut raw = valid_raw_episode();
             raw.media_url = None;
-            match validate_episode(&raw, &podcast).unwrap() {
+            match validate_episode(&raw, podcast.id).unwrap() {
                 EpisodeOrInvalid::Valid(_) => panic!("Unexpected valid episode"),
                 EpisodeOrInvalid::Invalid {
                     message: m,
@@ -1508,7 +5575,7 @@ mod tests {
         {
             let mut raw = valid_raw_episode();
             raw.published_at = None;
-            match validate_episode(&raw, &podcast).unwrap() {
+            match validate_episode(&raw, podcast.id).unwrap() {
                 EpisodeOrInvalid::Valid(_) => panic!("Unexpected valid episode"),
                 EpisodeOrInvalid::Invalid {
                     message: m,
@@ -1523,7 +5590,7 @@ mod tests {
         {
             let mut raw = valid_raw_episode();
             raw.title = None;
-            match validate_episode(&raw, &podcast).unwrap() {
+            match validate_episode(&raw, podcast.id).unwrap() {
                 EpisodeOrInvalid::Valid(_) => panic!("Unexpected valid episode"),
                 EpisodeOrInvalid::Invalid {
                     message: m,
@@ -1536,6 +5603,148 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_podcast_update_validate_feed() {
+        let log = test_helpers::log();
+
+        {
+            let report = validate_feed(&log, test_helpers::IDEAL_FEED, None);
+            assert!(report.is_valid());
+            assert_eq!(Some("Title".to_owned()), report.podcast_title);
+        }
+
+        {
+            let report = validate_feed(
+                &log,
+                br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+    <item>
+      <media:content url="https://example.com/item-2" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 2 Title</title>
+    </item>
+    <item>
+      <guid>3</guid>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 3 Title</title>
+    </item>
+    <item>
+      <guid>4</guid>
+      <media:content url="https://example.com/item-4" type="audio/mpeg"/>
+      <pubDate>not a date</pubDate>
+      <title>Item 4 Title</title>
+    </item>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Duplicate</title>
+    </item>
+  </channel>
+</rss>"#,
+                None,
+            );
+            assert!(!report.is_valid());
+            assert_eq!(
+                vec!["Missing title from podcast".to_owned()],
+                report.podcast_problems
+            );
+            assert_eq!(5, report.num_episodes);
+            assert_eq!(1, report.num_episodes_valid);
+
+            let episodes = &report.episodes;
+            assert_eq!(4, episodes.len());
+            assert_eq!(2, episodes[0].position);
+            assert_eq!("Missing GUID from episode", episodes[0].problem);
+            assert_eq!(3, episodes[1].position);
+            assert_eq!(Some("3".to_owned()), episodes[1].guid);
+            assert_eq!("Missing media URL from episode", episodes[1].problem);
+            assert_eq!(4, episodes[2].position);
+            assert!(
+                episodes[2]
+                    .problem
+                    .starts_with("Error parsing publishing date \"not a date\"")
+            );
+            assert_eq!(5, episodes[3].position);
+            assert_eq!(
+                "Duplicate GUID (episode will be discarded)",
+                episodes[3].problem
+            );
+            assert_eq!(Some("Item 1 Duplicate".to_owned()), episodes[3].title);
+        }
+
+        // Dates that needed a fallback aren't problems, but are counted.
+        {
+            let report = validate_feed(
+                &log,
+                r#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 CEST</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+    <item>
+      <guid>2</guid>
+      <media:content url="https://example.com/item-2" type="audio/mpeg"/>
+      <pubDate>2017-12-24</pubDate>
+      <title>Item 2 Title</title>
+    </item>
+    <item>
+      <guid>3</guid>
+      <media:content url="https://example.com/item-3" type="audio/mpeg"/>
+      <pubDate>dim., 24 déc. 2017 21:37:32 +0100</pubDate>
+      <title>Item 3 Title</title>
+    </item>
+    <item>
+      <guid>4</guid>
+      <media:content url="https://example.com/item-4" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 4 Title</title>
+    </item>
+  </channel>
+</rss>"#.as_bytes(),
+                None,
+            );
+            assert!(report.is_valid());
+            assert_eq!(4, report.num_episodes_valid);
+
+            let mut expected = BTreeMap::new();
+            expected.insert("date_only".to_owned(), 1);
+            expected.insert("rfc2822_repaired".to_owned(), 2);
+            assert_eq!(expected, report.published_at_fallbacks);
+        }
+
+        {
+            let report = validate_feed(
+                &log,
+                br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <language>en-US</language>"#,
+                None,
+            );
+            assert!(!report.is_valid());
+            assert_eq!(
+                Some("Unexpected EOF while parsing <channel> tag".to_owned()),
+                report.parse_error
+            );
+        }
+    }
+
     #[test]
     fn test_podcast_update_validate_podcast() {
         {
@@ -1592,7 +5801,9 @@ mod tests {
                 Mediator {
                     conn:             &*self.conn,
                     disable_shortcut: false,
+                    enqueue_jobs:     true,
                     feed_url:         self.feed_url.to_owned(),
+                    follow_pages:     false,
                     http_requester:   &mut self.http_requester,
                 },
                 self.log.clone(),
@@ -1600,6 +5811,145 @@ mod tests {
         }
     }
 
+    // An `HttpRequester` that acts like a host supporting conditional GET. It
+    // responds with the feed and a set of validators, or with a 304 if the
+    // request came in with an `If-None-Match` matching its `ETag`.
+    struct HttpRequesterConditional {
+        data:             Vec<u8>,
+        etag:             &'static str,
+        last_modified:    &'static str,
+        num_not_modified: i64,
+    }
+
+    impl HttpRequester for HttpRequesterConditional {
+        fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+            let not_modified = req.headers()
+                .get_raw("If-None-Match")
+                .and_then(|raw| raw.one())
+                .map(|value| value == self.etag.as_bytes())
+                .unwrap_or(false);
+
+            let mut headers = Headers::new();
+            headers.set_raw("ETag", self.etag);
+            headers.set_raw("Last-Modified", self.last_modified);
+
+            if not_modified {
+                self.num_not_modified += 1;
+                return Ok(Response {
+                    body:               Vec::new(),
+                    final_url:          req.uri().to_string(),
+                    headers:            headers,
+                    last_url:           req.uri().to_string(),
+                    permanent_redirect: false,
+                    status:             StatusCode::NotModified,
+                });
+            }
+
+            Ok(Response {
+                body:               self.data.clone(),
+                final_url:          req.uri().to_string(),
+                headers:            headers,
+                last_url:           req.uri().to_string(),
+                permanent_redirect: false,
+                status:             StatusCode::Ok,
+            })
+        }
+    }
+
+    // An `HttpRequester` that serves a different document for each URL, like the
+    // pages of a paged feed. URLs that it doesn't know about get a 404.
+    struct HttpRequesterPages {
+        pages:          HashMap<&'static str, &'static [u8]>,
+        requested_urls: Vec<String>,
+    }
+
+    impl HttpRequester for HttpRequesterPages {
+        fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+            let url = req.uri().to_string();
+            self.requested_urls.push(url.clone());
+
+            let (body, status) = match self.pages.get(url.as_str()) {
+                Some(page) => (page.to_vec(), StatusCode::Ok),
+                None => (Vec::new(), StatusCode::NotFound),
+            };
+
+            Ok(Response {
+                body:               body,
+                final_url:          url.clone(),
+                headers:            Headers::new(),
+                last_url:           url,
+                permanent_redirect: false,
+                status:             status,
+            })
+        }
+    }
+
+    // An `HttpRequester` that serves pages like `HttpRequesterPages`, except that
+    // for `error_url` it runs a statement that Postgres rejects before failing, like
+    // a database error in the middle of reading a page would.
+    struct HttpRequesterPagesDatabaseError<'a> {
+        conn:      &'a PgConnection,
+        error_url: &'static str,
+        inner:     HttpRequesterPages,
+    }
+
+    impl<'a> HttpRequester for HttpRequesterPagesDatabaseError<'a> {
+        fn execute(&mut self, log: &Logger, req: Request) -> Result<Response> {
+            if req.uri().to_string() == self.error_url {
+                diesel::sql_query("SELECT 1 / 0")
+                    .execute(self.conn)
+                    .chain_err(|| "Error reading feed page")?;
+            }
+            self.inner.execute(log, req)
+        }
+    }
+
+    // An `HttpRequester` that acts as if the request was redirected to a
+    // different URL. Like the live requester, the new URL is only reported if the
+    // redirect was permanent.
+    struct HttpRequesterRedirect {
+        data:               Vec<u8>,
+        final_url:          &'static str,
+        permanent_redirect: bool,
+    }
+
+    impl HttpRequester for HttpRequesterRedirect {
+        fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+            let final_url = if self.permanent_redirect {
+                self.final_url.to_owned()
+            } else {
+                req.uri().to_string()
+            };
+
+            Ok(Response {
+                body:               self.data.clone(),
+                final_url:          final_url,
+                headers:            Headers::new(),
+                last_url:           self.final_url.to_owned(),
+                permanent_redirect: self.permanent_redirect,
+                status:             StatusCode::Ok,
+            })
+        }
+    }
+
+    // Fails every request in the same way that `HttpRequesterLive` does for a
+    // body that's larger than its maximum size.
+    struct HttpRequesterTooLarge {}
+
+    impl HttpRequester for HttpRequesterTooLarge {
+        fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+            bail!(ErrorKind::BodyTooLarge(req.uri().to_string(), 100))
+        }
+    }
+
+    struct HttpRequesterThrottled {}
+
+    impl HttpRequester for HttpRequesterThrottled {
+        fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+            bail!(ErrorKind::Throttled(req.uri().to_string()))
+        }
+    }
+
     // The suite runs on test transactions that are connection-specific, so this
     // version of `TestBootStrap` is useful for sharing state across multiple
     // bootstraps.
@@ -1629,7 +5979,9 @@ mod tests {
                 Mediator {
                     conn:             self.conn,
                     disable_shortcut: false,
+                    enqueue_jobs:     true,
                     feed_url:         self.feed_url.to_owned(),
+                    follow_pages:     false,
                     http_requester:   &mut self.http_requester,
                 },
                 self.log.clone(),
@@ -1637,6 +5989,78 @@ mod tests {
         }
     }
 
+    // Decodes a feed in its entirety in the same way as the mediator would.
+    fn decode_feed(log: &Logger, data: &[u8], content_type: Option<&str>) -> String {
+        let mut decoded = String::new();
+        DecodingReader::new(log, data, content_type)
+            .unwrap()
+            .read_to_string(&mut decoded)
+            .unwrap();
+        decoded
+    }
+
+    // Builds a requester serving a feed split across three pages, the last of
+    // which links back to the first.
+    fn paged_requester() -> HttpRequesterPages {
+        let mut pages: HashMap<&'static str, &'static [u8]> = HashMap::new();
+        pages.insert(
+            "https://example.com/feed.xml",
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <atom:link rel="next" href="https://example.com/feed.xml?page=2"/>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+  </channel>
+</rss>"#,
+        );
+        pages.insert(
+            "https://example.com/feed.xml?page=2",
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <atom:link rel="next" href="?page=3"/>
+    <item>
+      <guid>2</guid>
+      <media:content url="https://example.com/item-2" type="audio/mpeg"/>
+      <pubDate>Sat, 23 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 2 Title</title>
+    </item>
+  </channel>
+</rss>"#,
+        );
+        pages.insert(
+            "https://example.com/feed.xml?page=3",
+            br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Title</title>
+    <atom:link rel="next" href="feed.xml"/>
+    <item>
+      <guid>3</guid>
+      <media:content url="https://example.com/item-3" type="audio/mpeg"/>
+      <pubDate>Fri, 22 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 3 Title</title>
+    </item>
+  </channel>
+</rss>"#,
+        );
+
+        HttpRequesterPages {
+            pages:          pages,
+            requested_urls: Vec::new(),
+        }
+    }
+
     fn valid_raw_episode() -> raw::Episode {
         let mut raw = raw::Episode::default();
         raw.guid = Some("unique-guid".to_owned());
@@ -0,0 +1,313 @@
+use errors::*;
+use http_requester::HttpRequesterPassThrough;
+use mediators::podcast_updater;
+use model;
+use schema;
+use time_helpers;
+
+use crypto::digest::Digest;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha1::Sha1;
+use crypto::sha2::{Sha256, Sha384, Sha512};
+use crypto::util::fixed_time_eq;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use slog::Logger;
+use std::sync::Arc;
+
+/// Accepts a feed pushed to us by a WebSub hub and runs it through the podcast
+/// updater as if we'd just retrieved it ourselves.
+///
+/// Content is only trusted if it's been signed with the secret that we shared
+/// with the hub. Anything else is ignored, but isn't an error because the hub
+/// isn't the one that should be told about it.
+pub struct Mediator<'a> {
+    pub conn: &'a PgConnection,
+    pub data: &'a [u8],
+
+    /// The contents of the request's `X-Hub-Signature` header.
+    pub signature: Option<&'a str>,
+
+    pub subscription_id: i64,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let subscription = match self.select_subscription(log)? {
+            Some(subscription) => subscription,
+            None => {
+                return Ok(RunResult {
+                    podcast:      None,
+                    subscription: None,
+                })
+            }
+        };
+
+        let valid = match self.signature {
+            Some(signature) => verify_signature(subscription.secret.as_str(), self.data, signature),
+            None => false,
+        };
+        if !valid {
+            info!(log, "Ignoring content with a missing or invalid signature";
+                "subscription_id" => subscription.id);
+            return Ok(RunResult {
+                podcast:      None,
+                subscription: Some(subscription),
+            });
+        }
+
+        // The content is fed in under the podcast's current location rather than the
+        // topic URL so that the updater treats it exactly like a normal retrieval.
+        let feed_url = self.select_feed_url(log, &subscription)?
+            .unwrap_or_else(|| subscription.topic_url.clone());
+
+        // The updater runs (and commits) in a transaction of its own.
+        let res = podcast_updater::Mediator {
+            conn: self.conn,
+            disable_shortcut: false,
+            enqueue_jobs: true,
+            feed_url,
+            follow_pages: false,
+            http_requester: &mut HttpRequesterPassThrough {
+                data: Arc::new(self.data.to_vec()),
+            },
+        }.run(log)?;
+
+        Ok(RunResult {
+            podcast:      Some(res.podcast),
+            subscription: Some(subscription),
+        })
+    }
+
+    //
+    // Steps
+    //
+
+    fn select_feed_url(
+        &mut self,
+        log: &Logger,
+        subscription: &model::PodcastWebsubSubscription,
+    ) -> Result<Option<String>> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_feed_url")), |_log| {
+            schema::podcast_feed_location::table
+                .filter(schema::podcast_feed_location::podcast_id.eq(subscription.podcast_id))
+                .filter(schema::podcast_feed_location::superseded_at.is_null())
+                .order(schema::podcast_feed_location::last_retrieved_at.desc())
+                .select(schema::podcast_feed_location::feed_url)
+                .first(self.conn)
+                .optional()
+                .chain_err(|| "Error selecting podcast feed location")
+        })
+    }
+
+    fn select_subscription(
+        &mut self,
+        log: &Logger,
+    ) -> Result<Option<model::PodcastWebsubSubscription>> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_subscription")), |_log| {
+            schema::podcast_websub_subscription::table
+                .filter(schema::podcast_websub_subscription::id.eq(self.subscription_id))
+                .first(self.conn)
+                .optional()
+                .chain_err(|| "Error selecting podcast WebSub subscription")
+        })
+    }
+}
+
+pub struct RunResult {
+    /// The updated podcast. `None` if the content wasn't accepted.
+    pub podcast: Option<model::Podcast>,
+
+    /// The subscription that the content was pushed for. `None` if we don't
+    /// know about it (say because its podcast was merged into another).
+    pub subscription: Option<model::PodcastWebsubSubscription>,
+}
+
+//
+// Private functions
+//
+
+// Produces a hex-encoded HMAC of the given data.
+fn hmac_hex<D: Digest>(digest: D, secret: &str, data: &[u8]) -> String {
+    let mut hmac = Hmac::new(digest, secret.as_bytes());
+    hmac.input(data);
+    hmac.result()
+        .code()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Checks an `X-Hub-Signature` header, which looks like `sha1=<hex HMAC>`. Hubs
+// may use any of the SHA family, so we support all of the ones that WebSub
+// allows.
+fn verify_signature(secret: &str, data: &[u8], signature: &str) -> bool {
+    let mut parts = signature.splitn(2, '=');
+    let (method, signature) = match (parts.next(), parts.next()) {
+        (Some(method), Some(signature)) => (method, signature.to_lowercase()),
+        _ => return false,
+    };
+
+    let expected = match method {
+        "sha1" => hmac_hex(Sha1::new(), secret, data),
+        "sha256" => hmac_hex(Sha256::new(), secret, data),
+        "sha384" => hmac_hex(Sha384::new(), secret, data),
+        "sha512" => hmac_hex(Sha512::new(), secret, data),
+        _ => return false,
+    };
+
+    // Compared in constant time so as not to leak how much of a forged signature
+    // was correct.
+    fixed_time_eq(expected.as_bytes(), signature.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use mediators::websub_content_receiver::*;
+    use test_data;
+    use test_helpers;
+
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+
+    #[test]
+    fn test_websub_content_receive() {
+        let mut bootstrap = TestBootstrap::new();
+        let signature = format!("sha1={}", hmac_hex(Sha1::new(), "secret", PUSHED_FEED));
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.signature = Some(signature.as_str());
+            mediator.run(&log).unwrap()
+        };
+
+        let podcast = res.podcast.unwrap();
+        assert_eq!(bootstrap.podcast.id, podcast.id);
+        assert_eq!("Pushed Title", podcast.title);
+    }
+
+    #[test]
+    fn test_websub_content_receive_invalid_signature() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.signature = Some("sha1=0000");
+            mediator.run(&log).unwrap()
+        };
+        assert!(res.podcast.is_none());
+        assert!(res.subscription.is_some());
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+        assert!(res.podcast.is_none());
+
+        let podcast: model::Podcast = schema::podcast::table
+            .filter(schema::podcast::id.eq(bootstrap.podcast.id))
+            .first(&*bootstrap.conn)
+            .unwrap();
+        assert_eq!("Title", podcast.title);
+    }
+
+    #[test]
+    fn test_websub_content_receive_unknown_subscription() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let (mut mediator, log) = bootstrap.mediator();
+        mediator.subscription_id = 0;
+        let res = mediator.run(&log).unwrap();
+        assert!(res.podcast.is_none());
+        assert!(res.subscription.is_none());
+    }
+
+    #[test]
+    fn test_websub_content_verify_signature() {
+        let data = b"content";
+        let sha1 = hmac_hex(Sha1::new(), "secret", data);
+        let sha256 = hmac_hex(Sha256::new(), "secret", data);
+
+        assert!(verify_signature("secret", data, format!("sha1={}", sha1).as_str()));
+        assert!(verify_signature(
+            "secret",
+            data,
+            format!("sha1={}", sha1.to_uppercase()).as_str()
+        ));
+        assert!(verify_signature("secret", data, format!("sha256={}", sha256).as_str()));
+
+        assert!(!verify_signature("other", data, format!("sha1={}", sha1).as_str()));
+        assert!(!verify_signature("secret", data, format!("sha256={}", sha1).as_str()));
+        assert!(!verify_signature("secret", data, format!("md5={}", sha1).as_str()));
+        assert!(!verify_signature("secret", data, sha1.as_str()));
+    }
+
+    //
+    // Private types/functions
+    //
+
+    const PUSHED_FEED: &[u8] = br#"
+<?xml version="1.0" encoding="UTF-8"?>
+<rss>
+  <channel>
+    <title>Pushed Title</title>
+    <item>
+      <guid>1</guid>
+      <media:content url="https://example.com/item-1" type="audio/mpeg"/>
+      <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
+      <title>Item 1 Title</title>
+    </item>
+  </channel>
+</rss>"#;
+
+    struct TestBootstrap {
+        _common:      test_helpers::CommonTestBootstrap,
+        conn:         PooledConnection<ConnectionManager<PgConnection>>,
+        log:          Logger,
+        podcast:      model::Podcast,
+        subscription: model::PodcastWebsubSubscription,
+    }
+
+    impl TestBootstrap {
+        fn new() -> TestBootstrap {
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+
+            let podcast = test_data::podcast::insert(&log, &conn);
+            let subscription = test_data::podcast_websub_subscription::insert_args(
+                &log,
+                &conn,
+                test_data::podcast_websub_subscription::Args {
+                    podcast: Some(&podcast),
+                },
+            );
+
+            TestBootstrap {
+                _common:      test_helpers::CommonTestBootstrap::new(),
+                podcast:      podcast,
+                subscription: subscription,
+
+                // Only move these after filling the above
+                conn: conn,
+                log:  log,
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    conn:            &*self.conn,
+                    data:            PUSHED_FEED,
+                    signature:       None,
+                    subscription_id: self.subscription.id,
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use errors::*;
+use mediators::websub_subscriber;
+use model;
+use schema;
+use time_helpers;
+
+use chrono::{DateTime, Duration, Utc};
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use slog::Logger;
+use std::cmp;
+
+/// The longest lease that we'll accept from a hub. A lease is only a
+/// suggestion of how long a hub will keep pushing to us, and anything longer
+/// is clamped down to this.
+pub const MAX_LEASE_SECONDS: i64 = 365 * 24 * 60 * 60;
+
+/// The shortest lease that we'll accept from a hub. Anything shorter
+/// (including a nonsensical negative one) is clamped up to this.
+pub const MIN_LEASE_SECONDS: i64 = 1;
+
+/// Handles a WebSub hub's request to verify that we asked for a subscription
+/// (or its notice that the subscription was denied).
+///
+/// We only ever subscribe, so a request to verify an unsubscribe is treated
+/// the same way as one for a subscription that we don't know about.
+pub struct Mediator<'a> {
+    pub conn: &'a PgConnection,
+
+    /// The length of the lease granted by the hub. Falls back to the one that
+    /// we asked for if the hub didn't send one, and is kept between
+    /// `MIN_LEASE_SECONDS` and `MAX_LEASE_SECONDS`.
+    pub lease_seconds: Option<i64>,
+
+    /// The `hub.mode` sent by the hub.
+    pub mode: String,
+
+    pub subscription_id: i64,
+
+    /// The `hub.topic` sent by the hub.
+    pub topic_url: String,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| {
+            self.conn.transaction::<_, Error, _>(|| self.run_inner(log))
+        })
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let subscription = match self.select_subscription(log)? {
+            Some(subscription) => subscription,
+            None => return Ok(RunResult { outcome: Outcome::Unknown }),
+        };
+
+        if subscription.topic_url != self.topic_url {
+            info!(log, "Topic doesn't match subscription";
+                "topic_url" => self.topic_url.as_str(),
+                "expected_topic_url" => subscription.topic_url.as_str());
+            return Ok(RunResult { outcome: Outcome::Unknown });
+        }
+
+        let outcome = match self.mode.as_str() {
+            "denied" => {
+                self.update_subscription(log, &subscription, None, None)?;
+                Outcome::Denied
+            }
+            "subscribe" => {
+                let lease_seconds = cmp::min(
+                    cmp::max(
+                        self.lease_seconds
+                            .unwrap_or(websub_subscriber::LEASE_SECONDS),
+                        MIN_LEASE_SECONDS,
+                    ),
+                    MAX_LEASE_SECONDS,
+                );
+                let now = Utc::now();
+                let expires_at = now.checked_add_signed(Duration::seconds(lease_seconds))
+                    .ok_or_else(|| Error::from("Error calculating WebSub lease expiry"))?;
+                self.update_subscription(log, &subscription, Some(expires_at), Some(now))?;
+                Outcome::Verified
+            }
+            _ => Outcome::Unknown,
+        };
+
+        Ok(RunResult { outcome })
+    }
+
+    //
+    // Steps
+    //
+
+    fn select_subscription(
+        &mut self,
+        log: &Logger,
+    ) -> Result<Option<model::PodcastWebsubSubscription>> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_subscription")), |_log| {
+            schema::podcast_websub_subscription::table
+                .filter(schema::podcast_websub_subscription::id.eq(self.subscription_id))
+                .first(self.conn)
+                .optional()
+                .chain_err(|| "Error selecting podcast WebSub subscription")
+        })
+    }
+
+    fn update_subscription(
+        &mut self,
+        log: &Logger,
+        subscription: &model::PodcastWebsubSubscription,
+        expires_at: Option<DateTime<Utc>>,
+        verified_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        time_helpers::log_timed(&log.new(o!("step" => "update_subscription")), |_log| {
+            diesel::update(
+                schema::podcast_websub_subscription::table
+                    .filter(schema::podcast_websub_subscription::id.eq(subscription.id)),
+            ).set((
+                schema::podcast_websub_subscription::expires_at.eq(expires_at),
+                schema::podcast_websub_subscription::verified_at.eq(verified_at),
+            ))
+                .execute(self.conn)
+                .chain_err(|| "Error updating podcast WebSub subscription")
+        })?;
+        Ok(())
+    }
+}
+
+pub struct RunResult {
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    /// The hub denied our request for a subscription.
+    Denied,
+
+    /// The request didn't match a subscription that we asked for, and so
+    /// shouldn't be confirmed.
+    Unknown,
+
+    /// The subscription was verified and the hub's challenge should be echoed
+    /// back to it.
+    Verified,
+}
+
+#[cfg(test)]
+mod tests {
+    use mediators::websub_intent_verifier::*;
+    use test_data;
+    use test_helpers;
+
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+
+    #[test]
+    fn test_websub_intent_verify_subscribe() {
+        let mut bootstrap = TestBootstrap::new();
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.lease_seconds = Some(3600);
+            let res = mediator.run(&log).unwrap();
+            assert_eq!(Outcome::Verified, res.outcome);
+        }
+
+        let subscription = bootstrap.select_subscription();
+        assert!(subscription.is_active());
+        assert!(subscription.expires_at.unwrap() < Utc::now() + Duration::seconds(3601));
+    }
+
+    #[test]
+    fn test_websub_intent_verify_subscribe_oversized_lease() {
+        let mut bootstrap = TestBootstrap::new();
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.lease_seconds = Some(i64::max_value());
+            let res = mediator.run(&log).unwrap();
+            assert_eq!(Outcome::Verified, res.outcome);
+        }
+
+        let subscription = bootstrap.select_subscription();
+        assert!(subscription.is_active());
+        assert!(
+            subscription.expires_at.unwrap()
+                < Utc::now() + Duration::seconds(MAX_LEASE_SECONDS + 1)
+        );
+    }
+
+    #[test]
+    fn test_websub_intent_verify_subscribe_negative_lease() {
+        let mut bootstrap = TestBootstrap::new();
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.lease_seconds = Some(-3600);
+            let res = mediator.run(&log).unwrap();
+            assert_eq!(Outcome::Verified, res.outcome);
+        }
+
+        // The lease is as short as we allow, but it doesn't start out expired.
+        let subscription = bootstrap.select_subscription();
+        let expires_at = subscription.expires_at.unwrap();
+        assert!(expires_at > Utc::now() - Duration::seconds(1));
+        assert!(expires_at < Utc::now() + Duration::seconds(MIN_LEASE_SECONDS + 1));
+    }
+
+    #[test]
+    fn test_websub_intent_verify_denied() {
+        let mut bootstrap = TestBootstrap::new();
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap();
+        }
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.mode = "denied".to_owned();
+            let res = mediator.run(&log).unwrap();
+            assert_eq!(Outcome::Denied, res.outcome);
+        }
+
+        assert!(!bootstrap.select_subscription().is_active());
+    }
+
+    #[test]
+    fn test_websub_intent_verify_topic_mismatch() {
+        let mut bootstrap = TestBootstrap::new();
+
+        {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.topic_url = "https://example.com/other.xml".to_owned();
+            let res = mediator.run(&log).unwrap();
+            assert_eq!(Outcome::Unknown, res.outcome);
+        }
+
+        assert!(bootstrap.select_subscription().verified_at.is_none());
+    }
+
+    #[test]
+    fn test_websub_intent_verify_unsubscribe() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let (mut mediator, log) = bootstrap.mediator();
+        mediator.mode = "unsubscribe".to_owned();
+        let res = mediator.run(&log).unwrap();
+        assert_eq!(Outcome::Unknown, res.outcome);
+    }
+
+    #[test]
+    fn test_websub_intent_verify_unknown_subscription() {
+        let mut bootstrap = TestBootstrap::new();
+
+        let (mut mediator, log) = bootstrap.mediator();
+        mediator.subscription_id = 0;
+        let res = mediator.run(&log).unwrap();
+        assert_eq!(Outcome::Unknown, res.outcome);
+    }
+
+    //
+    // Private types/functions
+    //
+
+    struct TestBootstrap {
+        _common:      test_helpers::CommonTestBootstrap,
+        conn:         PooledConnection<ConnectionManager<PgConnection>>,
+        log:          Logger,
+        subscription: model::PodcastWebsubSubscription,
+    }
+
+    impl TestBootstrap {
+        fn new() -> TestBootstrap {
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+
+            TestBootstrap {
+                _common:      test_helpers::CommonTestBootstrap::new(),
+                subscription: test_data::podcast_websub_subscription::insert(&log, &conn),
+
+                // Only move these after filling the above
+                conn: conn,
+                log:  log,
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    conn:            &*self.conn,
+                    lease_seconds:   None,
+                    mode:            "subscribe".to_owned(),
+                    subscription_id: self.subscription.id,
+                    topic_url:       self.subscription.topic_url.clone(),
+                },
+                self.log.clone(),
+            )
+        }
+
+        fn select_subscription(&self) -> model::PodcastWebsubSubscription {
+            schema::podcast_websub_subscription::table
+                .filter(schema::podcast_websub_subscription::id.eq(self.subscription.id))
+                .first(&*self.conn)
+                .unwrap()
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use errors::*;
+use http_requester::HttpRequester;
+use links;
+use model;
+use schema;
+use time_helpers;
+
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use hyper::header::{ContentLength, ContentType};
+use hyper::{Body, Method, Request, StatusCode, Uri};
+use serde_urlencoded;
+use slog::Logger;
+use std::str::FromStr;
+
+/// The lease that we ask hubs for. A hub is free to grant a different one,
+/// which it tells us about when it verifies the subscription.
+pub const LEASE_SECONDS: i64 = 10 * 24 * 60 * 60;
+
+/// Asks a WebSub hub to push a podcast's feed to us whenever it changes.
+///
+/// The hub doesn't consider the subscription active until it's verified our
+/// intent by calling back to the web server (see `web::endpoints::websub_get`),
+/// which usually happens asynchronously after this request has finished.
+pub struct Mediator<'a> {
+    pub conn:           &'a PgConnection,
+    pub http_requester: &'a mut HttpRequester,
+
+    /// The URL at which the web server can be reached from the outside world.
+    /// The hub's callbacks are made to a path under it.
+    pub public_url: String,
+
+    pub subscription_id: i64,
+}
+
+impl<'a> Mediator<'a> {
+    pub fn run(&mut self, log: &Logger) -> Result<RunResult> {
+        time_helpers::log_timed(&log.new(o!("step" => file!())), |log| self.run_inner(log))
+    }
+
+    fn run_inner(&mut self, log: &Logger) -> Result<RunResult> {
+        let subscription = self.select_subscription(log)?;
+        let callback_url = format!(
+            "{}{}",
+            self.public_url.trim_right_matches('/'),
+            links::link_websub_subscription(&subscription)
+        );
+        let status = self.request_subscription(log, &subscription, callback_url.as_str())?;
+        Ok(RunResult {
+            callback_url,
+            status,
+            subscription,
+        })
+    }
+
+    //
+    // Steps
+    //
+
+    fn request_subscription(
+        &mut self,
+        log: &Logger,
+        subscription: &model::PodcastWebsubSubscription,
+        callback_url: &str,
+    ) -> Result<StatusCode> {
+        time_helpers::log_timed(&log.new(o!("step" => "request_subscription")), |log| {
+            let body = serde_urlencoded::to_string(&[
+                ("hub.callback", callback_url),
+                ("hub.lease_seconds", LEASE_SECONDS.to_string().as_str()),
+                ("hub.mode", "subscribe"),
+                ("hub.secret", subscription.secret.as_str()),
+                ("hub.topic", subscription.topic_url.as_str()),
+            ]).map_err(|e| Error::from(format!("Error encoding subscription request: {}", e)))?;
+
+            let mut req = Request::new(
+                Method::Post,
+                Uri::from_str(subscription.hub_url.as_str()).map_err(Error::from)?,
+            );
+            req.headers_mut().set(ContentType::form_url_encoded());
+            req.headers_mut().set(ContentLength(body.len() as u64));
+            req.set_body(Body::from(body));
+
+            // Hubs are supposed to respond with a 202, but anything successful means
+            // that the request was accepted.
+            let res = self.http_requester.execute(log, req)?;
+            if !res.status.is_success() {
+                bail!(
+                    "Hub {} rejected subscription request with status {}",
+                    subscription.hub_url,
+                    res.status
+                );
+            }
+
+            info!(log, "Requested WebSub subscription";
+                "hub_url" => subscription.hub_url.as_str(), "status" => res.status.as_u16());
+            Ok(res.status)
+        })
+    }
+
+    fn select_subscription(&mut self, log: &Logger) -> Result<model::PodcastWebsubSubscription> {
+        time_helpers::log_timed(&log.new(o!("step" => "select_subscription")), |_log| {
+            schema::podcast_websub_subscription::table
+                .filter(schema::podcast_websub_subscription::id.eq(self.subscription_id))
+                .first(self.conn)
+                .chain_err(|| "Error selecting podcast WebSub subscription")
+        })
+    }
+}
+
+pub struct RunResult {
+    pub callback_url: String,
+    pub status:       StatusCode,
+    pub subscription: model::PodcastWebsubSubscription,
+}
+
+#[cfg(test)]
+mod tests {
+    use http_requester::Response;
+    use mediators::websub_subscriber::*;
+    use test_data;
+    use test_helpers;
+
+    use futures::{Future, Stream};
+    use hyper::Headers;
+    use r2d2::PooledConnection;
+    use r2d2_diesel::ConnectionManager;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_websub_subscribe() {
+        let mut bootstrap = TestBootstrap::new(StatusCode::Accepted);
+
+        let res = {
+            let (mut mediator, log) = bootstrap.mediator();
+            mediator.run(&log).unwrap()
+        };
+
+        assert_eq!(StatusCode::Accepted, res.status);
+        assert_eq!(
+            format!("https://podcore.example.com/websub/{}", bootstrap.subscription.id),
+            res.callback_url
+        );
+
+        assert_eq!(1, bootstrap.http_requester.requests.len());
+        let &(ref url, ref form) = &bootstrap.http_requester.requests[0];
+        assert_eq!("https://hub.example.com/", url);
+        assert_eq!(Some(&res.callback_url), form.get("hub.callback"));
+        assert_eq!(Some(&"subscribe".to_owned()), form.get("hub.mode"));
+        assert_eq!(Some(&bootstrap.subscription.secret), form.get("hub.secret"));
+        assert_eq!(
+            Some(&bootstrap.subscription.topic_url),
+            form.get("hub.topic")
+        );
+    }
+
+    #[test]
+    fn test_websub_subscribe_rejected() {
+        let mut bootstrap = TestBootstrap::new(StatusCode::BadRequest);
+
+        let (mut mediator, log) = bootstrap.mediator();
+        let e = mediator.run(&log).err().unwrap();
+        assert!(e.to_string().contains("rejected subscription request"));
+    }
+
+    //
+    // Private types/functions
+    //
+
+    // A stand-in for a hub that responds to every request with the given status
+    // and records what it was sent.
+    struct HttpRequesterHub {
+        requests: Vec<(String, HashMap<String, String>)>,
+        status:   StatusCode,
+    }
+
+    impl HttpRequester for HttpRequesterHub {
+        fn execute(&mut self, _log: &Logger, req: Request) -> Result<Response> {
+            let url = req.uri().to_string();
+            let body = req.body().concat2().wait().unwrap();
+            let form: HashMap<String, String> = serde_urlencoded::from_bytes(&body).unwrap();
+            self.requests.push((url.clone(), form));
+
+            Ok(Response {
+                body:               Vec::new(),
+                final_url:          url.clone(),
+                headers:            Headers::new(),
+                last_url:           url,
+                permanent_redirect: false,
+                status:             self.status,
+            })
+        }
+    }
+
+    struct TestBootstrap {
+        _common:        test_helpers::CommonTestBootstrap,
+        conn:           PooledConnection<ConnectionManager<PgConnection>>,
+        http_requester: HttpRequesterHub,
+        log:            Logger,
+        subscription:   model::PodcastWebsubSubscription,
+    }
+
+    impl TestBootstrap {
+        fn new(status: StatusCode) -> TestBootstrap {
+            let conn = test_helpers::connection();
+            let log = test_helpers::log();
+
+            TestBootstrap {
+                _common:        test_helpers::CommonTestBootstrap::new(),
+                http_requester: HttpRequesterHub {
+                    requests: Vec::new(),
+                    status:   status,
+                },
+                subscription:   test_data::podcast_websub_subscription::insert(&log, &conn),
+
+                // Only move these after filling the above
+                conn: conn,
+                log:  log,
+            }
+        }
+
+        fn mediator(&mut self) -> (Mediator, Logger) {
+            (
+                Mediator {
+                    conn:            &*self.conn,
+                    http_requester:  &mut self.http_requester,
+                    public_url:      "https://podcore.example.com/".to_owned(),
+                    subscription_id: self.subscription.id,
+                },
+                self.log.clone(),
+            )
+        }
+    }
+}
@@ -62,6 +62,32 @@ pub struct AccountPodcastEpisode {
     pub updated_at:         DateTime<Utc>,
 }
 
+#[derive(Clone, Debug, Queryable)]
+pub struct Category {
+    pub id:        i64,
+    pub name:      String,
+    pub parent_id: Option<i64>,
+    pub slug:      String,
+}
+
+#[derive(Queryable)]
+pub struct ContentBlob {
+    pub id:            i64,
+    pub content_zstd:  Vec<u8>,
+    pub created_at:    DateTime<Utc>,
+    pub dictionary_id: Option<i64>,
+    pub last_used_at:  DateTime<Utc>,
+    pub sha256_hash:   String,
+}
+
+#[derive(Queryable)]
+pub struct ContentDictionary {
+    pub id:          i64,
+    pub content:     Vec<u8>,
+    pub created_at:  DateTime<Utc>,
+    pub num_samples: i32,
+}
+
 #[derive(Queryable)]
 pub struct Directory {
     pub id:   i64,
@@ -160,6 +186,98 @@ pub struct Episode {
     pub podcast_id:   i64,
     pub published_at: DateTime<Utc>,
     pub title:        String,
+
+    // iTunes namespace
+    pub block:            Option<bool>,
+    pub duration_seconds: Option<i64>,
+    pub episode_number:   Option<i32>,
+    pub episode_type:     Option<String>,
+    pub image_url:        Option<String>,
+    pub season:           Option<i32>,
+
+    /// Set when the episode has disappeared from its podcast's feed.
+    pub removed_at: Option<DateTime<Utc>>,
+
+    /// Names the fallback that was needed to parse the episode's publishing
+    /// date if it wasn't valid.
+    pub published_at_fallback: Option<String>,
+
+    // Media probing (see `mediators::episode_media_prober`)
+    pub media_content_length: Option<i64>,
+    pub media_content_type:   Option<String>,
+    pub media_gone_at:        Option<DateTime<Utc>>,
+    pub media_probed_at:      Option<DateTime<Utc>>,
+    pub media_probed_url:     Option<String>,
+    pub media_status:         Option<i32>,
+}
+
+impl Episode {
+    /// Whether the episode's media has been probed since its media URL last
+    /// changed.
+    pub fn is_media_probed(&self) -> bool {
+        self.media_probed_url.as_ref() == Some(&self.media_url)
+    }
+}
+
+#[derive(Clone, Debug, Queryable)]
+pub struct EpisodeChapter {
+    pub id:            i64,
+    pub episode_id:    i64,
+    pub href:          Option<String>,
+    pub image_url:     Option<String>,
+    pub source:        String,
+    pub start_seconds: f64,
+    pub title:         String,
+}
+
+impl EpisodeChapter {
+    /// Narrows an episode's chapters down to the set that should be shown.
+    /// Chapters from a JSON chapters file are preferred because they're
+    /// usually more complete, with those from `psc:chapters` as a fallback.
+    pub fn preferred(chapters: Vec<EpisodeChapter>) -> Vec<EpisodeChapter> {
+        if chapters.iter().any(|c| c.source == CHAPTER_SOURCE_JSON) {
+            chapters
+                .into_iter()
+                .filter(|c| c.source == CHAPTER_SOURCE_JSON)
+                .collect()
+        } else {
+            chapters
+        }
+    }
+}
+
+/// Source of chapters parsed from a JSON chapters file.
+pub const CHAPTER_SOURCE_JSON: &str = "json";
+
+/// Source of chapters parsed from `psc:chapters` in a feed.
+pub const CHAPTER_SOURCE_PSC: &str = "psc";
+
+#[derive(Queryable)]
+pub struct EpisodeChaptersUrl {
+    pub id:         i64,
+    pub episode_id: i64,
+    pub media_type: Option<String>,
+    pub url:        String,
+}
+
+#[derive(Queryable)]
+pub struct EpisodeRevision {
+    pub id:         i64,
+    pub created_at: DateTime<Utc>,
+    pub episode_id: i64,
+    pub field:      String,
+    pub new_value:  Option<String>,
+    pub old_value:  Option<String>,
+}
+
+#[derive(Queryable)]
+pub struct EpisodeTranscript {
+    pub id:         i64,
+    pub episode_id: i64,
+    pub language:   Option<String>,
+    pub media_type: String,
+    pub rel:        Option<String>,
+    pub url:        String,
 }
 
 #[derive(Insertable, Queryable)]
@@ -192,15 +310,37 @@ pub struct Podcast {
     pub link_url:          Option<String>,
     pub title:             String,
     pub description:       Option<String>,
+
+    // iTunes namespace
+    pub author:           Option<String>,
+    pub block:            Option<bool>,
+    pub itunes_image_url: Option<String>,
+    pub owner_email:      Option<String>,
+    pub owner_name:       Option<String>,
+
+    // Podcasting 2.0 namespace
+    pub guid:   Option<String>,
+    pub locked: Option<bool>,
+
+    pub next_crawl_at: DateTime<Utc>,
 }
 
 #[allow(dead_code)]
 #[derive(Queryable)]
-pub struct PodcastException {
+pub struct PodcastCategory {
     pub id:          i64,
+    pub category_id: i64,
     pub podcast_id:  i64,
-    pub errors:      Vec<String>,
-    pub occurred_at: DateTime<Utc>,
+}
+
+#[allow(dead_code)]
+#[derive(Queryable)]
+pub struct PodcastException {
+    pub id:              i64,
+    pub podcast_id:      i64,
+    pub errors:          Vec<String>,
+    pub occurred_at:     DateTime<Utc>,
+    pub num_occurrences: i32,
 }
 
 #[allow(dead_code)]
@@ -211,6 +351,7 @@ pub struct PodcastFeedContent {
     pub retrieved_at: DateTime<Utc>,
     pub sha256_hash:  String,
     pub content_gzip: Option<Vec<u8>>,
+    pub page_number:  i32,
 }
 
 #[derive(Queryable)]
@@ -220,6 +361,49 @@ pub struct PodcastFeedLocation {
     pub feed_url:           String,
     pub last_retrieved_at:  DateTime<Utc>,
     pub podcast_id:         i64,
+    pub etag:               Option<String>,
+    pub last_modified:      Option<String>,
+    pub superseded_at:      Option<DateTime<Utc>>,
+}
+
+#[derive(Queryable)]
+pub struct PodcastFunding {
+    pub id:         i64,
+    pub podcast_id: i64,
+    pub title:      Option<String>,
+    pub url:        String,
+}
+
+#[derive(Queryable)]
+pub struct PodcastPerson {
+    pub id:         i64,
+    pub episode_id: Option<i64>,
+    pub group_name: Option<String>,
+    pub href:       Option<String>,
+    pub image_url:  Option<String>,
+    pub name:       String,
+    pub podcast_id: i64,
+    pub role:       Option<String>,
+}
+
+#[derive(Clone, Debug, Queryable)]
+pub struct PodcastWebsubSubscription {
+    pub id:           i64,
+    pub expires_at:   Option<DateTime<Utc>>,
+    pub hub_url:      String,
+    pub podcast_id:   i64,
+    pub requested_at: DateTime<Utc>,
+    pub secret:       String,
+    pub topic_url:    String,
+    pub verified_at:  Option<DateTime<Utc>>,
+}
+
+impl PodcastWebsubSubscription {
+    /// Whether the hub has verified the subscription and its lease hasn't run
+    /// out, meaning that we can expect updates to be pushed to us.
+    pub fn is_active(&self) -> bool {
+        self.verified_at.is_some() && self.expires_at.map_or(false, |t| t > Utc::now())
+    }
 }
 
 #[derive(Queryable)]
@@ -234,6 +418,31 @@ pub struct VerificationCode {
 mod tests {
     use model::*;
 
+    use chrono::Duration;
+
+    #[test]
+    fn test_episode_chapter_preferred() {
+        let chapter = |source: &str| EpisodeChapter {
+            id:            0,
+            episode_id:    0,
+            href:          None,
+            image_url:     None,
+            source:        source.to_owned(),
+            start_seconds: 0.0,
+            title:         "Chapter".to_owned(),
+        };
+
+        let chapters = EpisodeChapter::preferred(vec![chapter(CHAPTER_SOURCE_PSC)]);
+        assert_eq!(1, chapters.len());
+
+        let chapters = EpisodeChapter::preferred(vec![
+            chapter(CHAPTER_SOURCE_PSC),
+            chapter(CHAPTER_SOURCE_JSON),
+        ]);
+        assert_eq!(1, chapters.len());
+        assert_eq!(CHAPTER_SOURCE_JSON, chapters[0].source);
+    }
+
     #[test]
     fn test_podcast_is_subscribed() {
         let mut account_podcast = AccountPodcast::default();
@@ -245,13 +454,39 @@ mod tests {
         account_podcast.unsubscribed_at = Some(Utc::now());
         assert!(!account_podcast.is_subscribed());
     }
+
+    #[test]
+    fn test_podcast_websub_subscription_is_active() {
+        let mut subscription = PodcastWebsubSubscription {
+            id:           0,
+            expires_at:   None,
+            hub_url:      "https://example.com/hub".to_owned(),
+            podcast_id:   0,
+            requested_at: Utc::now(),
+            secret:       "secret".to_owned(),
+            topic_url:    "https://example.com/feed.xml".to_owned(),
+            verified_at:  None,
+        };
+        assert!(!subscription.is_active());
+
+        subscription.verified_at = Some(Utc::now());
+        subscription.expires_at = Some(Utc::now() + Duration::days(1));
+        assert!(subscription.is_active());
+
+        subscription.expires_at = Some(Utc::now() - Duration::days(1));
+        assert!(!subscription.is_active());
+    }
 }
 
 pub mod insertable {
-    use schema::{account, account_podcast, account_podcast_episode, directory, directory_podcast,
+    use schema::{account, account_podcast, account_podcast_episode, content_blob,
+                 content_dictionary, directory, directory_podcast,
                  directory_podcast_directory_search, directory_podcast_exception,
-                 directory_search, episode, job, job_exception, key, podcast, podcast_exception,
-                 podcast_feed_content, podcast_feed_location, verification_code};
+                 directory_search, episode, episode_chapter, episode_chapters_url,
+                 episode_revision, episode_transcript, job, job_exception, key, podcast,
+                 podcast_category, podcast_exception, podcast_feed_content,
+                 podcast_feed_location, podcast_funding, podcast_person,
+                 podcast_websub_subscription, verification_code};
 
     use chrono::{DateTime, Utc};
     use serde_json;
@@ -296,6 +531,21 @@ pub mod insertable {
         pub updated_at:         DateTime<Utc>,
     }
 
+    #[derive(Insertable)]
+    #[table_name = "content_blob"]
+    pub struct ContentBlob {
+        pub content_zstd:  Vec<u8>,
+        pub dictionary_id: Option<i64>,
+        pub sha256_hash:   String,
+    }
+
+    #[derive(Insertable)]
+    #[table_name = "content_dictionary"]
+    pub struct ContentDictionary {
+        pub content:     Vec<u8>,
+        pub num_samples: i32,
+    }
+
     #[derive(Insertable)]
     #[table_name = "directory"]
     pub struct Directory {
@@ -349,6 +599,55 @@ pub mod insertable {
         pub podcast_id:   i64,
         pub published_at: DateTime<Utc>,
         pub title:        String,
+
+        // iTunes namespace
+        pub block:            Option<bool>,
+        pub duration_seconds: Option<i64>,
+        pub episode_number:   Option<i32>,
+        pub episode_type:     Option<String>,
+        pub image_url:        Option<String>,
+        pub season:           Option<i32>,
+
+        pub removed_at:            Option<DateTime<Utc>>,
+        pub published_at_fallback: Option<String>,
+    }
+
+    #[derive(Insertable)]
+    #[table_name = "episode_chapter"]
+    pub struct EpisodeChapter {
+        pub episode_id:    i64,
+        pub href:          Option<String>,
+        pub image_url:     Option<String>,
+        pub source:        String,
+        pub start_seconds: f64,
+        pub title:         String,
+    }
+
+    #[derive(Insertable)]
+    #[table_name = "episode_chapters_url"]
+    pub struct EpisodeChaptersUrl {
+        pub episode_id: i64,
+        pub media_type: Option<String>,
+        pub url:        String,
+    }
+
+    #[derive(Insertable)]
+    #[table_name = "episode_revision"]
+    pub struct EpisodeRevision {
+        pub episode_id: i64,
+        pub field:      String,
+        pub new_value:  Option<String>,
+        pub old_value:  Option<String>,
+    }
+
+    #[derive(Insertable)]
+    #[table_name = "episode_transcript"]
+    pub struct EpisodeTranscript {
+        pub episode_id: i64,
+        pub language:   Option<String>,
+        pub media_type: String,
+        pub rel:        Option<String>,
+        pub url:        String,
     }
 
     #[derive(Insertable)]
@@ -385,6 +684,24 @@ pub mod insertable {
         pub link_url:          Option<String>,
         pub title:             String,
         pub description:       Option<String>,
+
+        // iTunes namespace
+        pub author:           Option<String>,
+        pub block:            Option<bool>,
+        pub itunes_image_url: Option<String>,
+        pub owner_email:      Option<String>,
+        pub owner_name:       Option<String>,
+
+        // Podcasting 2.0 namespace
+        pub guid:   Option<String>,
+        pub locked: Option<bool>,
+    }
+
+    #[derive(Insertable)]
+    #[table_name = "podcast_category"]
+    pub struct PodcastCategory {
+        pub category_id: i64,
+        pub podcast_id:  i64,
     }
 
     #[derive(Insertable)]
@@ -398,10 +715,10 @@ pub mod insertable {
     #[derive(Insertable)]
     #[table_name = "podcast_feed_content"]
     pub struct PodcastFeedContent {
-        pub content_gzip: Vec<u8>,
         pub podcast_id:   i64,
         pub retrieved_at: DateTime<Utc>,
         pub sha256_hash:  String,
+        pub page_number:  i32,
     }
 
     #[derive(Insertable)]
@@ -411,6 +728,39 @@ pub mod insertable {
         pub feed_url:           String,
         pub last_retrieved_at:  DateTime<Utc>,
         pub podcast_id:         i64,
+        pub etag:               Option<String>,
+        pub last_modified:      Option<String>,
+        pub superseded_at:      Option<DateTime<Utc>>,
+    }
+
+    #[derive(Insertable)]
+    #[table_name = "podcast_funding"]
+    pub struct PodcastFunding {
+        pub podcast_id: i64,
+        pub title:      Option<String>,
+        pub url:        String,
+    }
+
+    #[derive(Insertable)]
+    #[table_name = "podcast_person"]
+    pub struct PodcastPerson {
+        pub episode_id: Option<i64>,
+        pub group_name: Option<String>,
+        pub href:       Option<String>,
+        pub image_url:  Option<String>,
+        pub name:       String,
+        pub podcast_id: i64,
+        pub role:       Option<String>,
+    }
+
+    #[derive(Insertable)]
+    #[table_name = "podcast_websub_subscription"]
+    pub struct PodcastWebsubSubscription {
+        pub hub_url:      String,
+        pub podcast_id:   i64,
+        pub requested_at: DateTime<Utc>,
+        pub secret:       String,
+        pub topic_url:    String,
     }
 
     #[derive(Insertable)]
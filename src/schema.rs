@@ -34,6 +34,35 @@ table! {
     }
 }
 
+table! {
+    category (id) {
+        id -> Int8,
+        name -> Text,
+        parent_id -> Nullable<Int8>,
+        slug -> Text,
+    }
+}
+
+table! {
+    content_blob (id) {
+        id -> Int8,
+        content_zstd -> Bytea,
+        created_at -> Timestamptz,
+        dictionary_id -> Nullable<Int8>,
+        last_used_at -> Timestamptz,
+        sha256_hash -> Text,
+    }
+}
+
+table! {
+    content_dictionary (id) {
+        id -> Int8,
+        content -> Bytea,
+        created_at -> Timestamptz,
+        num_samples -> Int4,
+    }
+}
+
 table! {
     directory (id) {
         id -> Int8,
@@ -68,6 +97,7 @@ table! {
         directory_podcast_id -> Int8,
         errors -> Array<Text>,
         occurred_at -> Timestamptz,
+        num_occurrences -> Int4,
     }
 }
 
@@ -92,6 +122,63 @@ table! {
         podcast_id -> Int8,
         published_at -> Timestamptz,
         title -> Text,
+        block -> Nullable<Bool>,
+        duration_seconds -> Nullable<Int8>,
+        episode_number -> Nullable<Int4>,
+        episode_type -> Nullable<Text>,
+        image_url -> Nullable<Text>,
+        season -> Nullable<Int4>,
+        removed_at -> Nullable<Timestamptz>,
+        published_at_fallback -> Nullable<Text>,
+        media_content_length -> Nullable<Int8>,
+        media_content_type -> Nullable<Text>,
+        media_gone_at -> Nullable<Timestamptz>,
+        media_probed_at -> Nullable<Timestamptz>,
+        media_probed_url -> Nullable<Text>,
+        media_status -> Nullable<Int4>,
+    }
+}
+
+table! {
+    episode_chapter (id) {
+        id -> Int8,
+        episode_id -> Int8,
+        href -> Nullable<Text>,
+        image_url -> Nullable<Text>,
+        source -> Text,
+        start_seconds -> Float8,
+        title -> Text,
+    }
+}
+
+table! {
+    episode_chapters_url (id) {
+        id -> Int8,
+        episode_id -> Int8,
+        media_type -> Nullable<Text>,
+        url -> Text,
+    }
+}
+
+table! {
+    episode_revision (id) {
+        id -> Int8,
+        created_at -> Timestamptz,
+        episode_id -> Int8,
+        field -> Text,
+        new_value -> Nullable<Text>,
+        old_value -> Nullable<Text>,
+    }
+}
+
+table! {
+    episode_transcript (id) {
+        id -> Int8,
+        episode_id -> Int8,
+        language -> Nullable<Text>,
+        media_type -> Text,
+        rel -> Nullable<Text>,
+        url -> Text,
     }
 }
 
@@ -135,6 +222,22 @@ table! {
         link_url -> Nullable<Text>,
         title -> Text,
         description -> Nullable<Text>,
+        author -> Nullable<Text>,
+        block -> Nullable<Bool>,
+        itunes_image_url -> Nullable<Text>,
+        owner_email -> Nullable<Text>,
+        owner_name -> Nullable<Text>,
+        guid -> Nullable<Text>,
+        locked -> Nullable<Bool>,
+        next_crawl_at -> Timestamptz,
+    }
+}
+
+table! {
+    podcast_category (id) {
+        id -> Int8,
+        category_id -> Int8,
+        podcast_id -> Int8,
     }
 }
 
@@ -153,7 +256,8 @@ table! {
         podcast_id -> Int8,
         retrieved_at -> Timestamptz,
         sha256_hash -> Text,
-        content_gzip -> Bytea,
+        content_gzip -> Nullable<Bytea>,
+        page_number -> Int4,
     }
 }
 
@@ -164,6 +268,44 @@ table! {
         feed_url -> Text,
         last_retrieved_at -> Timestamptz,
         podcast_id -> Int8,
+        etag -> Nullable<Text>,
+        last_modified -> Nullable<Text>,
+        superseded_at -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    podcast_funding (id) {
+        id -> Int8,
+        podcast_id -> Int8,
+        title -> Nullable<Text>,
+        url -> Text,
+    }
+}
+
+table! {
+    podcast_person (id) {
+        id -> Int8,
+        episode_id -> Nullable<Int8>,
+        group_name -> Nullable<Text>,
+        href -> Nullable<Text>,
+        image_url -> Nullable<Text>,
+        name -> Text,
+        podcast_id -> Int8,
+        role -> Nullable<Text>,
+    }
+}
+
+table! {
+    podcast_websub_subscription (id) {
+        id -> Int8,
+        expires_at -> Nullable<Timestamptz>,
+        hub_url -> Text,
+        podcast_id -> Int8,
+        requested_at -> Timestamptz,
+        secret -> Text,
+        topic_url -> Text,
+        verified_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -180,6 +322,7 @@ joinable!(account_podcast -> account (account_id));
 joinable!(account_podcast -> podcast (podcast_id));
 joinable!(account_podcast_episode -> account_podcast (account_podcast_id));
 joinable!(account_podcast_episode -> episode (episode_id));
+joinable!(content_blob -> content_dictionary (dictionary_id));
 joinable!(directory_podcast -> directory (directory_id));
 joinable!(directory_podcast -> podcast (podcast_id));
 joinable!(directory_podcast_directory_search -> directory_podcast (directory_podcast_id));
@@ -187,29 +330,50 @@ joinable!(directory_podcast_directory_search -> directory_search (directory_sear
 joinable!(directory_podcast_exception -> directory_podcast (directory_podcast_id));
 joinable!(directory_search -> directory (directory_id));
 joinable!(episode -> podcast (podcast_id));
+joinable!(episode_chapter -> episode (episode_id));
+joinable!(episode_chapters_url -> episode (episode_id));
+joinable!(episode_revision -> episode (episode_id));
+joinable!(episode_transcript -> episode (episode_id));
 joinable!(job_exception -> job (job_id));
 joinable!(key -> account (account_id));
+joinable!(podcast_category -> category (category_id));
+joinable!(podcast_category -> podcast (podcast_id));
 joinable!(podcast_exception -> podcast (podcast_id));
 joinable!(podcast_feed_content -> podcast (podcast_id));
 joinable!(podcast_feed_location -> podcast (podcast_id));
+joinable!(podcast_funding -> podcast (podcast_id));
+joinable!(podcast_person -> episode (episode_id));
+joinable!(podcast_person -> podcast (podcast_id));
+joinable!(podcast_websub_subscription -> podcast (podcast_id));
 joinable!(verification_code -> account (account_id));
 
 allow_tables_to_appear_in_same_query!(
     account,
     account_podcast,
     account_podcast_episode,
+    category,
+    content_blob,
+    content_dictionary,
     directory,
     directory_podcast,
     directory_podcast_directory_search,
     directory_podcast_exception,
     directory_search,
     episode,
+    episode_chapter,
+    episode_chapters_url,
+    episode_revision,
+    episode_transcript,
     job,
     job_exception,
     key,
     podcast,
+    podcast_category,
     podcast_exception,
     podcast_feed_content,
     podcast_feed_location,
+    podcast_funding,
+    podcast_person,
+    podcast_websub_subscription,
     verification_code,
 );
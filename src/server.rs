@@ -204,6 +204,70 @@ pub fn ip_for_request<S: State>(req: &HttpRequest<S>) -> &str {
     req.connection_info().remote().unwrap_or("<no IP>")
 }
 
+/// Gets the contents of a named field from a `multipart/form-data` request
+/// body, which is what browsers send for forms that upload files. Returns
+/// `None` if the body didn't contain the field.
+///
+/// This is far from a complete implementation of multipart, but it's enough to
+/// handle the simple forms that we send, and saves us from having to pull in a
+/// streaming parser for the one endpoint that needs it.
+pub fn multipart_field<S: State>(
+    req: &HttpRequest<S>,
+    data: &[u8],
+    name: &str,
+) -> Result<Option<Vec<u8>>> {
+    let content_type = req.headers()
+        .get("Content-Type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !content_type.starts_with("multipart/form-data") {
+        bail!(user_errors::bad_request("Expected a multipart form"));
+    }
+
+    let boundary = content_type
+        .split(';')
+        .map(|s| s.trim())
+        .find(|s| s.starts_with("boundary="))
+        .map(|s| s["boundary=".len()..].trim_matches('"'))
+        .ok_or_else(|| user_errors::bad_request("Multipart form is missing a boundary"))?;
+
+    let delimiter = format!("--{}", boundary);
+    let disposition = format!("; name=\"{}\"", name);
+
+    let mut rest = data;
+    while let Some(start) = find_bytes(rest, delimiter.as_bytes()) {
+        rest = &rest[start + delimiter.len()..];
+        let part = match find_bytes(rest, delimiter.as_bytes()) {
+            Some(end) => &rest[..end],
+            None => break,
+        };
+
+        let headers_end = match find_bytes(part, b"\r\n\r\n") {
+            Some(headers_end) => headers_end,
+            None => continue,
+        };
+        let headers = String::from_utf8_lossy(&part[..headers_end]);
+        let matches = headers.lines().any(|line| {
+            line.to_lowercase().starts_with("content-disposition:")
+                && line.contains(disposition.as_str())
+        });
+        if !matches {
+            continue;
+        }
+
+        // The line break before the next delimiter belongs to the delimiter.
+        let body = &part[headers_end + 4..];
+        let body = if body.ends_with(b"\r\n") {
+            &body[..body.len() - 2]
+        } else {
+            body
+        };
+        return Ok(Some(body.to_vec()));
+    }
+
+    Ok(None)
+}
+
 /// Gets a `HashMap` containig query data for the given request.
 ///
 /// This function is provided as a helper so that we can return a consistent
@@ -281,3 +345,14 @@ where
         }
     }
 }
+
+//
+// Private functions
+//
+
+// Finds the first occurrence of `needle` in `haystack`.
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
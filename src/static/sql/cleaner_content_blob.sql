@@ -0,0 +1,21 @@
+WITH orphaned AS (
+    SELECT id
+    FROM content_blob
+    WHERE last_used_at < NOW() - $1::interval
+        AND NOT EXISTS (
+            SELECT 1
+            FROM podcast_feed_content
+            WHERE podcast_feed_content.sha256_hash = content_blob.sha256_hash
+        )
+    LIMIT $2
+),
+deleted_batch AS (
+    DELETE FROM content_blob
+    WHERE id IN (
+        SELECT id
+        FROM orphaned
+    )
+    RETURNING id
+)
+SELECT COUNT(*)
+FROM deleted_batch;
@@ -1,7 +1,7 @@
 WITH numbered AS (
     SELECT id, podcast_id,
         ROW_NUMBER() OVER (
-            PARTITION BY podcast_id
+            PARTITION BY podcast_id, page_number
             ORDER BY retrieved_at DESC
         )
     FROM podcast_feed_content
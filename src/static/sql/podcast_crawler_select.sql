@@ -1,32 +1,20 @@
 --
--- Differentiate podcasts that have seen some kind of update even remotely
--- recently and those that haven't. This allows us to back off to a much --
--- more conservative crawl cadence for podcasts that almost never see updates
--- (and in some cases, may never see an update again).
+-- Selects podcasts that are due to be crawled. Each podcast's next crawl time
+-- is scheduled by the podcast updater from its publishing history, so all we
+-- need to do here is compare it against the current time.
 --
-WITH podcast_with_refresh_interval AS (
-    SELECT podcast.id,
-        podcast.last_retrieved_at,
-        CASE
-            WHEN podcast_feed_content.retrieved_at > NOW() - '1 month'::interval
-                THEN $1::interval
-            ELSE $2::interval
-        END AS refresh_interval
-    FROM podcast
-        INNER JOIN podcast_feed_content
-            ON podcast.id = podcast_feed_content.podcast_id
-)
 SELECT id,
     (
        SELECT feed_url
        FROM podcast_feed_location
-       WHERE podcast_feed_location.podcast_id = podcast_with_refresh_interval.id
+       WHERE podcast_feed_location.podcast_id = podcast.id
+           -- locations that a podcast has moved away from are never crawled
+           AND podcast_feed_location.superseded_at IS NULL
        ORDER BY last_retrieved_at DESC
        LIMIT 1
     )
-FROM podcast_with_refresh_interval
-WHERE id > $3
-    AND last_retrieved_at - trunc(random() * $4) * '1 minute'::interval
-        <= NOW() - refresh_interval
+FROM podcast
+WHERE id > $1
+    AND next_crawl_at <= NOW()
 ORDER BY id
-LIMIT $5;
+LIMIT $2;
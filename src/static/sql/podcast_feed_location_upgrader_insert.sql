@@ -2,7 +2,8 @@ WITH podcast_feed_location_with_host AS (
     SELECT
         feed_url,
         substring(feed_url FROM '.*://([^/]*)') AS host,
-        podcast_id
+        podcast_id,
+        superseded_at
     FROM podcast_feed_location
 )
 INSERT INTO podcast_feed_location
@@ -17,6 +18,7 @@ SELECT
     podcast_id
 FROM podcast_feed_location_with_host l1
 WHERE feed_url LIKE 'http://%'
+    AND superseded_at IS NULL
     AND NOT EXISTS (
         SELECT 1
         FROM podcast_feed_location_with_host l2
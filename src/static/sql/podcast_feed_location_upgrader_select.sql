@@ -0,0 +1,24 @@
+--
+-- Selects active `http://` locations whose `https://` equivalents we don't
+-- know about yet, along with the hash of the most recent content stored for
+-- their podcasts.
+--
+SELECT l1.feed_url,
+    l1.podcast_id,
+    (
+        SELECT sha256_hash
+        FROM podcast_feed_content
+        WHERE podcast_feed_content.podcast_id = l1.podcast_id
+            AND page_number = 1
+        ORDER BY retrieved_at DESC
+        LIMIT 1
+    ) AS sha256_hash
+FROM podcast_feed_location l1
+WHERE feed_url LIKE 'http://%'
+    AND superseded_at IS NULL
+    AND NOT EXISTS (
+        SELECT 1
+        FROM podcast_feed_location l2
+        WHERE l2.feed_url = regexp_replace(l1.feed_url, '^http://', 'https://')
+    )
+ORDER BY l1.id;
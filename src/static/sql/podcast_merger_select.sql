@@ -0,0 +1,75 @@
+--
+-- Finds pairs of podcasts that are likely to be the same show under
+-- different feed URLs. The older podcast of each pair always comes first.
+--
+-- A shared `podcast:guid` is enough on its own because it's meant to be
+-- globally unique. So is a feed URL that only differs by scheme (`http://`
+-- versus `https://`) or a trailing slash.
+--
+-- A shared link URL isn't enough because networks often point all of their
+-- shows at the same homepage, so those pairs also need to have at least half
+-- of the smaller podcast's episode GUIDs in common. Podcasts that don't share
+-- a link URL need nearly all of them in common, and enough of them that it's
+-- unlikely to be a coincidence (some feeds number their episodes' GUIDs from
+-- one).
+--
+WITH podcast_episode_count AS (
+    SELECT podcast_id,
+        count(*) AS num_episodes
+    FROM episode
+    GROUP BY podcast_id
+),
+podcast_episode_overlap AS (
+    SELECT e1.podcast_id AS podcast1_id,
+        e2.podcast_id AS podcast2_id,
+        count(*) AS num_shared
+    FROM episode e1
+        INNER JOIN episode e2
+            ON e1.guid = e2.guid
+                AND e1.podcast_id < e2.podcast_id
+    GROUP BY e1.podcast_id, e2.podcast_id
+),
+podcast_normalized_feed_url AS (
+    SELECT DISTINCT podcast_id,
+        rtrim(regexp_replace(feed_url, '^https?://', ''), '/') AS feed_url
+    FROM podcast_feed_location
+)
+SELECT p1.id AS survivor_id,
+    p2.id AS duplicate_id
+FROM podcast p1
+    INNER JOIN podcast p2
+        ON p1.guid = p2.guid
+            AND p1.id < p2.id
+UNION
+SELECT u1.podcast_id,
+    u2.podcast_id
+FROM podcast_normalized_feed_url u1
+    INNER JOIN podcast_normalized_feed_url u2
+        ON u1.feed_url = u2.feed_url
+            AND u1.podcast_id < u2.podcast_id
+UNION
+SELECT p1.id,
+    p2.id
+FROM podcast p1
+    INNER JOIN podcast p2
+        ON p1.link_url = p2.link_url
+            AND p1.id < p2.id
+    INNER JOIN podcast_episode_overlap o
+        ON o.podcast1_id = p1.id
+            AND o.podcast2_id = p2.id
+    INNER JOIN podcast_episode_count c1
+        ON c1.podcast_id = p1.id
+    INNER JOIN podcast_episode_count c2
+        ON c2.podcast_id = p2.id
+WHERE o.num_shared * 2 >= LEAST(c1.num_episodes, c2.num_episodes)
+UNION
+SELECT o.podcast1_id,
+    o.podcast2_id
+FROM podcast_episode_overlap o
+    INNER JOIN podcast_episode_count c1
+        ON c1.podcast_id = o.podcast1_id
+    INNER JOIN podcast_episode_count c2
+        ON c2.podcast_id = o.podcast2_id
+WHERE LEAST(c1.num_episodes, c2.num_episodes) >= 5
+    AND o.num_shared * 10 >= LEAST(c1.num_episodes, c2.num_episodes) * 9
+ORDER BY survivor_id, duplicate_id;
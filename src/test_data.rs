@@ -260,7 +260,9 @@ pub mod podcast {
         podcast_updater::Mediator {
             conn,
             disable_shortcut: false,
+            enqueue_jobs: true,
             feed_url,
+            follow_pages: false,
             http_requester: &mut HttpRequesterPassThrough {
                 data: Arc::new(test_helpers::MINIMAL_FEED.to_vec()),
             },
@@ -270,6 +272,45 @@ pub mod podcast {
     }
 }
 
+pub mod podcast_websub_subscription {
+    use test_data::*;
+
+    use diesel::prelude::*;
+
+    #[derive(Default)]
+    pub struct Args<'a> {
+        pub podcast: Option<&'a model::Podcast>,
+    }
+
+    pub fn insert(log: &Logger, conn: &PgConnection) -> model::PodcastWebsubSubscription {
+        insert_args(log, conn, Args::default())
+    }
+
+    pub fn insert_args(
+        log: &Logger,
+        conn: &PgConnection,
+        args: Args,
+    ) -> model::PodcastWebsubSubscription {
+        let podcast = if args.podcast.is_none() {
+            Some(super::podcast::insert(log, conn))
+        } else {
+            None
+        };
+        let podcast_ref = args.podcast.unwrap_or_else(|| podcast.as_ref().unwrap());
+
+        diesel::insert_into(schema::podcast_websub_subscription::table)
+            .values(&insertable::PodcastWebsubSubscription {
+                hub_url:      "https://hub.example.com/".to_owned(),
+                podcast_id:   podcast_ref.id,
+                requested_at: Utc::now(),
+                secret:       "secret".to_owned(),
+                topic_url:    "https://example.com/feed.xml".to_owned(),
+            })
+            .get_result(conn)
+            .unwrap()
+    }
+}
+
 pub mod verification_code {
     use mediators::verification_code_creator;
     use test_data::*;
@@ -39,6 +39,12 @@ pub const IDEAL_FEED: &[u8] = br#"
 <rss>
   <channel>
     <description>Description</description>
+    <itunes:author>Author</itunes:author>
+    <itunes:image href="https://example.com/podcast-itunes-image-url.jpg"/>
+    <itunes:owner>
+      <itunes:name>Owner</itunes:name>
+      <itunes:email>owner@example.com</itunes:email>
+    </itunes:owner>
     <language>en-US</language>
     <link>https://example.com/podcast</link>
     <media:thumbnail url="https://example.com/podcast-image-url.jpg"/>
@@ -46,7 +52,12 @@ pub const IDEAL_FEED: &[u8] = br#"
     <item>
       <description><![CDATA[Item 1 description]]></description>
       <guid>1</guid>
+      <itunes:duration>01:02:03</itunes:duration>
+      <itunes:episode>2</itunes:episode>
+      <itunes:episodeType>full</itunes:episodeType>
       <itunes:explicit>yes</itunes:explicit>
+      <itunes:image href="https://example.com/item-1-image-url.jpg"/>
+      <itunes:season>1</itunes:season>
       <media:content url="https://example.com/item-1" type="audio/mpeg"/>
       <pubDate>Sun, 24 Dec 2017 21:37:32 +0000</pubDate>
       <title>Item 1 Title</title>
@@ -54,7 +65,11 @@ pub const IDEAL_FEED: &[u8] = br#"
     <item>
       <description><![CDATA[Item 2 description]]></description>
       <guid>2</guid>
+      <itunes:duration>3723</itunes:duration>
+      <itunes:episode>1</itunes:episode>
+      <itunes:episodeType>Trailer</itunes:episodeType>
       <itunes:explicit>yes</itunes:explicit>
+      <itunes:season>1</itunes:season>
       <media:content url="https://example.com/item-2" type="audio/mpeg"/>
       <pubDate>Sat, 23 Dec 2017 21:37:32 +0000</pubDate>
       <title>Item 2 Title</title>
@@ -189,6 +204,8 @@ pub fn clean_database(log: &Logger, conn: &PgConnection) {
     // I've left out `directory` even thought it's a leaf because there's no point
     // in deleting it over and over when it can be reused unchanged.
     conn.execute("TRUNCATE TABLE account CASCADE").unwrap();
+    conn.execute("TRUNCATE TABLE content_blob CASCADE").unwrap();
+    conn.execute("TRUNCATE TABLE content_dictionary CASCADE").unwrap();
     conn.execute("TRUNCATE TABLE job CASCADE").unwrap();
     conn.execute("TRUNCATE TABLE podcast CASCADE").unwrap();
 }
@@ -67,6 +67,7 @@ impl Server {
         });
 
         let server = actix_web::server::new(move || {
+            let csrf_origin_account = csrf_origin.clone();
             let csrf_origin_graphql = csrf_origin.clone();
             let csrf_origin_login = csrf_origin.clone();
             let csrf_origin_logout = csrf_origin.clone();
@@ -93,6 +94,18 @@ impl Server {
                 .resource("/account", move |r| {
                     r.method(Method::GET).a(endpoints::account_get::handler);
                 })
+                .resource("/account/subscriptions.opml", |r| {
+                    r.method(Method::GET)
+                        .a(endpoints::account_subscriptions_opml_get::handler);
+                })
+                .resource("/account/subscriptions/import", move |r| {
+                    r.middleware(csrf::CsrfFilter::new().allowed_origin(csrf_origin_account));
+                    r.method(Method::POST)
+                        .a(endpoints::account_subscriptions_import_post::handler);
+                })
+                .resource("/categories/{slug}", |r| {
+                    r.method(Method::GET).a(endpoints::category_get::handler)
+                })
                 .resource("/directory-podcasts/{id}", |r| {
                     r.method(Method::GET)
                         .a(endpoints::directory_podcast_get::handler)
@@ -136,6 +149,12 @@ impl Server {
                 .resource("/podcasts/{podcast_id}/episodes/{id}", |r| {
                     r.method(Method::GET).a(endpoints::episode_get::handler)
                 })
+                // Called back by WebSub hubs, which aren't going to have an origin that passes
+                // CSRF protection. Pushed content is checked against a signature instead.
+                .resource("/websub/{id}", |r| {
+                    r.method(Method::GET).a(endpoints::websub_get::handler);
+                    r.method(Method::POST).a(endpoints::websub_post::handler);
+                })
                 .handler(
                     format!("/assets/{}/", assets_version.as_str()).as_str(),
                     actix_web::fs::StaticFiles::new("./assets/"),
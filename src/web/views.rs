@@ -117,6 +117,117 @@ pub mod account_get {
                         }
                     }
                 }
+                p {
+                    a(href="/account/subscriptions.opml"): "Export subscriptions as OPML";
+                }
+                h2: "Import subscriptions";
+                p: "Upload an OPML file exported from another podcast app:";
+                form(
+                    action="/account/subscriptions/import",
+                    enctype="multipart/form-data",
+                    method="post"
+                ) {
+                    input(type="file", name="opml", accept=".opml,.xml");
+                    input(type="submit", value="Import");
+                }
+            }).into_string()?
+                .as_str(),
+        )
+    }
+}
+
+pub mod account_subscriptions_import_post {
+    use errors::*;
+    use links;
+    use mediators::opml_importer::FeedStatus;
+    use web::endpoints::account_subscriptions_import_post::view_model;
+    use web::endpoints::CommonViewModel;
+    use web::views;
+
+    use horrorshow::Template;
+
+    pub fn render(common: &CommonViewModel, view_model: &view_model::Ok) -> Result<String> {
+        views::render_layout(
+            common,
+            "Import Subscriptions",
+            (html! {
+                h1: "Import Subscriptions";
+                p: format!("Found {} feed(s):", view_model.feeds.len());
+                ul {
+                    @ for feed in &view_model.feeds {
+                        li {
+                            @ if let Some(ref podcast) = feed.podcast {
+                                a(href=links::link_podcast(podcast)) {
+                                    : podcast.title.as_str()
+                                }
+                            } else {
+                                : feed.title.as_ref().unwrap_or(&feed.feed_url).as_str()
+                            }
+                            : " - ";
+                            @ match feed.status {
+                                FeedStatus::Enqueued => {
+                                    : "Subscribing in the background"
+                                }
+                                FeedStatus::Invalid(ref message) => {
+                                    : format!("Skipped: {}", message)
+                                }
+                                FeedStatus::Subscribed => {
+                                    : "Subscribed"
+                                }
+                            }
+                        }
+                    }
+                }
+                p {
+                    a(href="/account"): "Back to your account";
+                }
+            }).into_string()?
+                .as_str(),
+        )
+    }
+}
+
+pub mod category_get {
+    use errors::*;
+    use links;
+    use web::endpoints::category_get::view_model;
+    use web::endpoints::CommonViewModel;
+    use web::views;
+
+    use horrorshow::Template;
+
+    pub fn render(common: &CommonViewModel, view_model: &view_model::Ok) -> Result<String> {
+        views::render_layout(
+            common,
+            &format!("Category: {}", view_model.category.name.as_str()),
+            (html! {
+                h1: view_model.category.name.as_str();
+                @ if let Some(ref parent) = view_model.parent {
+                    p {
+                        : "In ";
+                        a(href=links::link_category(parent)): parent.name.as_str();
+                    }
+                }
+                @ if !view_model.subcategories.is_empty() {
+                    ul(class="categories") {
+                        @ for category in &view_model.subcategories {
+                            li {
+                                a(href=links::link_category(category)): category.name.as_str();
+                            }
+                        }
+                    }
+                }
+                @ if view_model.podcasts.is_empty() {
+                    p: "There aren't any podcasts in this category yet.";
+                } else {
+                    ul {
+                        @ for podcast in &view_model.podcasts {
+                            li {
+                                a(href=links::link_podcast(podcast)): podcast.title.as_str();
+                            }
+                        }
+                    }
+                }
             }).into_string()?
                 .as_str(),
         )
@@ -138,9 +249,27 @@ pub mod episode_get {
             &format!("Episode: {}", view_model.episode.title.as_str()),
             (html! {
                 h1: view_model.episode.title.as_str();
+                @ if view_model.episode.removed_at.is_some() {
+                    p: "This episode has been removed from the podcast's feed.";
+                }
+                @ if view_model.episode.block == Some(true) {
+                    p: "This episode has asked to be hidden from podcast directories.";
+                }
+                @ if let Some(ref image_url) = view_model.episode.image_url {
+                    img(src=image_url.as_str(), alt=view_model.episode.title.as_str());
+                }
+                @ if let Some(ref details) = view_model.episode_details() {
+                    p: details.as_str();
+                }
                 div(id="favorited-toggle") {}
                 div(id="played-toggle") {}
+                @ if view_model.episode.media_gone_at.is_some() {
+                    p: "This episode's media is no longer available from its host.";
+                }
                 p: view_model.episode.media_url.as_str();
+                @ if let Some(ref media_details) = view_model.media_details() {
+                    p: media_details.as_str();
+                }
                 audio(controls) {
                     source(
                         src=view_model.episode.media_url.as_str(),
@@ -152,6 +281,76 @@ pub mod episode_get {
                     hr;
                     p: description.as_str();
                 }
+                @ if !view_model.persons.is_empty() {
+                    h2: "People";
+                    ul {
+                        @ for person in &view_model.persons {
+                            li {
+                                @ if let Some(ref href) = person.href {
+                                    a(href=href.as_str(), rel="nofollow"): person.name.as_str();
+                                } else {
+                                    : person.name.as_str();
+                                }
+                                @ if let Some(ref role) = person.role {
+                                    : format_args!(" ({})", role);
+                                }
+                            }
+                        }
+                    }
+                }
+                @ if !view_model.transcripts.is_empty() {
+                    h2: "Transcripts";
+                    ul {
+                        @ for transcript in &view_model.transcripts {
+                            li {
+                                a(href=transcript.url.as_str(), rel="nofollow") {
+                                    : transcript.media_type.as_str();
+                                    @ if let Some(ref language) = transcript.language {
+                                        : format_args!(" ({})", language);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                @ if !view_model.chapters.is_empty() {
+                    h2: "Chapters";
+                    ul(class="chapters") {
+                        @ for chapter in &view_model.chapters {
+                            li {
+                                // The media fragment lets the link work as a
+                                // plain anchor too; `app.js` seeks the player.
+                                a(
+                                    class="chapter-seek",
+                                    href=format_args!("#t={}", chapter.start_seconds)
+                                ): view_model.chapter_start(chapter);
+                                : " ";
+                                @ if let Some(ref href) = chapter.href {
+                                    a(href=href.as_str(), rel="nofollow"): chapter.title.as_str();
+                                } else {
+                                    : chapter.title.as_str();
+                                }
+                            }
+                        }
+                    }
+                }
+                @ for chapters_url in &view_model.chapters_urls {
+                    p {
+                        a(href=chapters_url.url.as_str(), rel="nofollow"): "Chapters";
+                    }
+                }
+                @ if !view_model.fundings.is_empty() {
+                    h2: "Support the podcast";
+                    ul {
+                        @ for funding in &view_model.fundings {
+                            li {
+                                a(href=funding.url.as_str(), rel="nofollow") {
+                                    : funding.title.as_ref().unwrap_or(&funding.url).as_str();
+                                }
+                            }
+                        }
+                    }
+                }
                 script : Raw(views::react_element(
                     "AccountPodcastEpisodeFavoritedToggler",
                     "favorited-toggle",
@@ -218,6 +417,24 @@ pub mod podcast_get {
             &format!("Podcast: {}", view_model.podcast.title.as_str()),
             (html! {
                 h1: view_model.podcast.title.as_str();
+                @ if view_model.podcast.block == Some(true) {
+                    p: "This podcast has asked to be hidden from podcast directories.";
+                }
+                @ if let Some(image_url) = view_model.image_url() {
+                    img(src=image_url, alt=view_model.podcast.title.as_str());
+                }
+                @ if let Some(ref author) = view_model.podcast.author {
+                    p: format_args!("By {}", author);
+                }
+                @ if !view_model.categories.is_empty() {
+                    ul(class="categories") {
+                        @ for category in &view_model.categories {
+                            li {
+                                a(href=links::link_category(category)): category.name.as_str();
+                            }
+                        }
+                    }
+                }
                 div(id="subscribed-toggle") {}
                 p {
                     : "Hello! This is <html />"
@@ -266,7 +483,7 @@ pub mod search_get {
             (html! {
                 h1: "Search";
                 form(action="/search", method="get") {
-                    input(type="text", name="q");
+                    input(type="text", name="q", placeholder="Search or paste a podcast's URL");
                     input(type="submit", value="Submit");
                 }
                 @ if let Some(ref query) = view_model.query {